@@ -0,0 +1,325 @@
+mod map;
+mod report;
+mod run;
+mod scenario;
+
+use crate::map::render_path;
+use crate::report::{write_report, ReportEntry, ReportFormat};
+use crate::run::run;
+use crate::scenario::check_scenarios;
+use pathfinder_core::heuristic::{HeuristicOptions, Heuristics};
+use pathfinder_core::json::{Document, RecordedRun};
+use pathfinder_core::{Grid, Movement, PathFindAlgorithms};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Usage:
+  pathfinder solve <map> [options] [--record <file.json>]
+  pathfinder batch <directory> [options] [--report <file>] [--format csv|json]
+  pathfinder scenario <file.scen or directory> [options]
+
+Maps are text files where every line is a row of the grid:
+  .  empty tile      #  wall      1-9  tile with that cost      S  start      E  end
+  a-z  waypoints, passed in the order of their letters      G  goal, the path ends at the closest goal or E
+  ^ V < >  one-way tiles      other capital letters  portals, a step onto one moves to the other one with the letter
+Maps ending in .json are json documents of pathfinder-core, which also contain the movement and wrapping.
+Batch mode runs every algorithm on every .txt and .json map in the directory.
+Scenario mode runs every algorithm on the problems of MovingAI .scen files and compares the paths
+with the optimal lengths, the .map files are looked up next to the .scen files.
+
+Options:
+  -a, --algorithm <id>   algorithm to run, can be repeated (default: a_star for solve, all otherwise)
+  -m, --movement <id>    four_way, eight_way, eight_way_no_corner_cutting or hex
+                         (default: the one of json maps, else four_way)
+  -W, --wrap             let the edges of the maps wrap around to the opposite side
+  -H, --heuristic <id>   auto, manhattan, euclidean, chebyshev, octile, hex or zero
+                         (default: auto)
+  -w, --weight <weight>  factor the heuristic is multiplied with (default: 1)
+  -r, --report <file>    write the batch report to the file instead of stdout
+  -f, --format <format>  csv or json (default: taken from the report file extension, else csv)
+  -o, --record <file>    write the map and the searches of solve as a json document
+  -t, --tour             visit the waypoints in the cheapest order instead of the order of their letters
+  -h, --help             print this help";
+
+#[derive(Clone, Debug)]
+enum Command {
+    Solve(PathBuf),
+    Batch(PathBuf),
+    Scenario(PathBuf),
+}
+
+#[derive(Clone, Debug)]
+struct Args {
+    command: Command,
+    algorithms: Vec<PathFindAlgorithms>,
+    movement: Option<Movement>,
+    wrap: bool,
+    heuristic: HeuristicOptions,
+    report: Option<PathBuf>,
+    format: Option<ReportFormat>,
+    record: Option<PathBuf>,
+    tour: bool,
+}
+
+fn main() -> ExitCode {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    if args.is_empty() || args.iter().any(|arg| arg == "-h" || arg == "--help") {
+        println!("{}", USAGE);
+        return ExitCode::SUCCESS;
+    }
+
+    let result = parse_args(&args).and_then(|args| match &args.command {
+        Command::Solve(map) => solve(&args, map),
+        Command::Batch(directory) => batch(&args, directory),
+        Command::Scenario(path) => scenario(&args, path),
+    });
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("error: {}", error);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn parse_args(args: &[String]) -> Result<Args, String> {
+    let mut args = args.iter();
+    let command = match (args.next().map(String::as_str), args.next()) {
+        (Some("solve"), Some(map)) => Command::Solve(map.into()),
+        (Some("batch"), Some(directory)) => Command::Batch(directory.into()),
+        (Some("scenario"), Some(path)) => Command::Scenario(path.into()),
+        (Some(command @ ("solve" | "batch" | "scenario")), None) => {
+            return Err(format!("{} needs a path, see --help", command))
+        }
+        (Some(command), _) => return Err(format!("unknown command '{}', see --help", command)),
+        (None, _) => return Err("missing command, see --help".to_string()),
+    };
+
+    let mut parsed = Args {
+        command,
+        algorithms: Vec::new(),
+        movement: None,
+        wrap: false,
+        heuristic: HeuristicOptions::default(),
+        report: None,
+        format: None,
+        record: None,
+        tour: false,
+    };
+    while let Some(option) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| format!("{} needs a value", option))
+        };
+        match option.as_str() {
+            "-a" | "--algorithm" => {
+                let id = value()?;
+                let algorithm = PathFindAlgorithms::from_id(id)
+                    .ok_or_else(|| format!("unknown algorithm '{}'", id))?;
+                parsed.algorithms.push(algorithm);
+            }
+            "-m" | "--movement" => {
+                let id = value()?;
+                parsed.movement = Some(
+                    Movement::from_id(id).ok_or_else(|| format!("unknown movement '{}'", id))?,
+                );
+            }
+            "-H" | "--heuristic" => {
+                let id = value()?;
+                parsed.heuristic.heuristic =
+                    Heuristics::from_id(id).ok_or_else(|| format!("unknown heuristic '{}'", id))?;
+            }
+            "-w" | "--weight" => {
+                let weight = value()?;
+                parsed.heuristic.weight = weight
+                    .parse()
+                    .ok()
+                    .filter(|weight: &f32| *weight >= 0.0)
+                    .ok_or_else(|| format!("invalid weight '{}'", weight))?;
+            }
+            "-r" | "--report" => parsed.report = Some(value()?.into()),
+            "-o" | "--record" => parsed.record = Some(value()?.into()),
+            "-t" | "--tour" => parsed.tour = true,
+            "-W" | "--wrap" => parsed.wrap = true,
+            "-f" | "--format" => {
+                let id = value()?;
+                parsed.format = Some(
+                    ReportFormat::from_id(id)
+                        .ok_or_else(|| format!("unknown report format '{}'", id))?,
+                );
+            }
+            option => return Err(format!("unknown option '{}', see --help", option)),
+        }
+    }
+    Ok(parsed)
+}
+
+/// Loads a text map or the grid of a json document.
+/// Text maps don't contain a movement, so they use four way movement unless another one is given,
+/// and they only wrap around if asked to.
+fn load_map(path: &Path, movement: Option<Movement>, wrap: bool) -> Result<Grid, String> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|error| format!("can't read {}: {}", path.display(), error))?;
+    let mut grid = if path
+        .extension()
+        .is_some_and(|extension| extension == "json")
+    {
+        Document::from_json(&text)
+            .map(|document| document.grid)
+            .map_err(|error| error.to_string())
+    } else {
+        text.parse::<Grid>().map_err(|error| error.to_string())
+    }
+    .map_err(|error| format!("{}: {}", path.display(), error))?;
+    if let Some(movement) = movement {
+        grid.set_movement(movement);
+    }
+    if wrap {
+        grid.set_wraps(true);
+    }
+    Ok(grid)
+}
+
+fn solve(args: &Args, map: &Path) -> Result<(), String> {
+    let grid = load_map(map, args.movement, args.wrap)?;
+    let algorithms = if args.algorithms.is_empty() {
+        &[PathFindAlgorithms::AStar][..]
+    } else {
+        &args.algorithms
+    };
+
+    let mut document = Document::new(grid.clone());
+    for (i, algorithm) in algorithms.iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+
+        let result = run(*algorithm, &grid, args.heuristic, args.tour);
+        println!("algorithm: {}", algorithm.id());
+        match &result.search.path {
+            Some(path) => {
+                print!("{}", render_path(&grid, path));
+                let path = path
+                    .iter()
+                    .map(|pos| format!("({}, {})", pos.x, pos.y))
+                    .collect::<Vec<_>>();
+                println!("path: {}", path.join(" "));
+            }
+            None => println!("path: not found"),
+        }
+        match result.search.cost {
+            Some(cost) => println!("cost: {}", cost),
+            None => println!("cost: -"),
+        }
+        println!("expanded: {}", result.search.expanded);
+        println!("steps: {}", result.search.steps);
+        println!("time: {:.3} ms", result.duration.as_secs_f64() * 1000.0);
+
+        document.runs.push(RecordedRun {
+            algorithm: *algorithm,
+            heuristic: args.heuristic,
+            result: result.search,
+        });
+    }
+
+    match &args.record {
+        Some(path) => std::fs::write(path, document.to_json())
+            .map_err(|error| format!("can't write {}: {}", path.display(), error)),
+        None => Ok(()),
+    }
+}
+
+fn batch(args: &Args, directory: &Path) -> Result<(), String> {
+    let mut maps = files_with_extension(directory, "txt")?;
+    maps.extend(files_with_extension(directory, "json")?);
+    maps.sort();
+    if maps.is_empty() {
+        return Err(format!("no maps found in {}", directory.display()));
+    }
+
+    let algorithms = if args.algorithms.is_empty() {
+        &PathFindAlgorithms::ALL[..]
+    } else {
+        &args.algorithms
+    };
+
+    let mut entries = Vec::new();
+    for map in maps {
+        let grid = match load_map(&map, args.movement, args.wrap) {
+            Ok(grid) => grid,
+            Err(error) => {
+                eprintln!("skipping map: {}", error);
+                continue;
+            }
+        };
+
+        let name = map
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        for algorithm in algorithms {
+            let result = run(*algorithm, &grid, args.heuristic, args.tour);
+            eprintln!(
+                "{} {}: {:.3} ms",
+                name,
+                algorithm.id(),
+                result.duration.as_secs_f64() * 1000.0
+            );
+            entries.push(ReportEntry {
+                map: name.clone(),
+                result,
+            });
+        }
+    }
+
+    let format = args.format.unwrap_or_else(|| {
+        args.report
+            .as_ref()
+            .and_then(|report| report.extension())
+            .and_then(|extension| ReportFormat::from_id(&extension.to_string_lossy()))
+            .unwrap_or(ReportFormat::Csv)
+    });
+    let report = write_report(&entries, format);
+    match &args.report {
+        Some(path) => std::fs::write(path, report)
+            .map_err(|error| format!("can't write {}: {}", path.display(), error)),
+        None => {
+            print!("{}", report);
+            Ok(())
+        }
+    }
+}
+
+fn scenario(args: &Args, path: &Path) -> Result<(), String> {
+    let scenarios = if path.is_dir() {
+        files_with_extension(path, "scen")?
+    } else {
+        vec![path.to_path_buf()]
+    };
+    if scenarios.is_empty() {
+        return Err(format!("no scenarios found in {}", path.display()));
+    }
+
+    let algorithms = if args.algorithms.is_empty() {
+        &PathFindAlgorithms::ALL[..]
+    } else {
+        &args.algorithms
+    };
+    if check_scenarios(&scenarios, algorithms, args.heuristic)? {
+        Ok(())
+    } else {
+        Err("some paths don't have the optimal length".to_string())
+    }
+}
+
+/// Returns the files in the directory with the extension, sorted by name.
+fn files_with_extension(directory: &Path, extension: &str) -> Result<Vec<PathBuf>, String> {
+    let mut files = std::fs::read_dir(directory)
+        .map_err(|error| format!("can't read {}: {}", directory.display(), error))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|found| found == extension))
+        .collect::<Vec<_>>();
+    files.sort();
+    Ok(files)
+}
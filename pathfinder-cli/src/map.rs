@@ -0,0 +1,29 @@
+use pathfinder_core::{Grid, Pos};
+
+/// Draws the grid in its text format, marking the tiles on the path with `*`.
+pub fn render_path(grid: &Grid, path: &[Pos]) -> String {
+    let mut rows = grid
+        .to_string()
+        .lines()
+        .map(|row| row.chars().collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+
+    // going through a portal or over the edge of a wrapping grid jumps to the other tile
+    // instead of crossing the tiles in between
+    let path_tiles = path
+        .windows(2)
+        .flat_map(|line| match grid.portal_partner(line[0]) {
+            Some(partner) if partner == line[1] => line.to_vec(),
+            _ if grid.crosses_edge(line[0], line[1]) => line.to_vec(),
+            _ => grid.line(line[0], line[1]),
+        });
+    for pos in path_tiles {
+        if pos != grid.start() && pos != grid.end() {
+            rows[pos.y as usize][pos.x as usize] = '*';
+        }
+    }
+
+    rows.into_iter()
+        .map(|row| row.into_iter().chain(['\n']).collect::<String>())
+        .collect()
+}
@@ -0,0 +1,101 @@
+use crate::run::RunResult;
+use serde::Serialize;
+use std::fmt::Write;
+
+/// The file formats a batch report can be written in.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ReportFormat {
+    Csv,
+    Json,
+}
+
+impl ReportFormat {
+    pub fn from_id(id: &str) -> Option<Self> {
+        match id {
+            "csv" => Some(Self::Csv),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+}
+
+/// The result of one algorithm on one map.
+#[derive(Clone, Debug)]
+pub struct ReportEntry {
+    pub map: String,
+    pub result: RunResult,
+}
+
+/// The columns of a report, one row for every entry.
+#[derive(Serialize)]
+struct ReportRow<'a> {
+    map: &'a str,
+    algorithm: &'static str,
+    found: bool,
+    path_length: usize,
+    cost: Option<f32>,
+    expanded: usize,
+    steps: usize,
+    time_ms: f64,
+}
+
+impl<'a> From<&'a ReportEntry> for ReportRow<'a> {
+    fn from(entry: &'a ReportEntry) -> Self {
+        let result = &entry.result;
+        Self {
+            map: &entry.map,
+            algorithm: result.algorithm.id(),
+            found: result.search.path.is_some(),
+            path_length: result.search.path.as_ref().map(Vec::len).unwrap_or(0),
+            cost: result.search.cost,
+            expanded: result.search.expanded,
+            steps: result.search.steps,
+            // microseconds are precise enough and keep the numbers short
+            time_ms: result.duration.as_micros() as f64 / 1000.0,
+        }
+    }
+}
+
+pub fn write_report(entries: &[ReportEntry], format: ReportFormat) -> String {
+    match format {
+        ReportFormat::Csv => write_csv(entries),
+        ReportFormat::Json => write_json(entries),
+    }
+}
+
+fn write_csv(entries: &[ReportEntry]) -> String {
+    let mut csv = String::from("map,algorithm,found,path_length,cost,expanded,steps,time_ms\n");
+    for row in entries.iter().map(ReportRow::from) {
+        writeln!(
+            csv,
+            "{},{},{},{},{},{},{},{:.3}",
+            csv_field(row.map),
+            row.algorithm,
+            row.found,
+            row.path_length,
+            row.cost.map(|cost| cost.to_string()).unwrap_or_default(),
+            row.expanded,
+            row.steps,
+            row.time_ms,
+        )
+        .unwrap();
+    }
+    csv
+}
+
+fn write_json(entries: &[ReportEntry]) -> String {
+    let rows = entries.iter().map(ReportRow::from).collect::<Vec<_>>();
+    let mut json = Vec::new();
+    serde_json::to_writer_pretty(&mut json, &rows).expect("report rows are always valid json");
+    json.push(b'\n');
+    String::from_utf8(json).expect("serde_json only writes utf-8")
+}
+
+/// Quotes the field if it contains characters which have a meaning in csv.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
@@ -0,0 +1,37 @@
+use pathfinder_core::heuristic::HeuristicOptions;
+use pathfinder_core::search_result::SearchResult;
+use pathfinder_core::tour::TourSearch;
+use pathfinder_core::{Grid, PathFindAlgorithm, PathFindAlgorithms};
+use std::time::{Duration, Instant};
+
+/// The outcome of running an algorithm until it found a path or gave up.
+#[derive(Clone, Debug)]
+pub struct RunResult {
+    pub algorithm: PathFindAlgorithms,
+    pub search: SearchResult,
+    pub duration: Duration,
+}
+
+/// Runs the algorithm on the grid without any pauses in between the steps.
+/// A tour visits the waypoints in the cheapest order instead of their own order.
+pub fn run(
+    algorithm: PathFindAlgorithms,
+    grid: &Grid,
+    heuristic: HeuristicOptions,
+    tour: bool,
+) -> RunResult {
+    let started = Instant::now();
+
+    let mut state: Box<dyn PathFindAlgorithm> = if tour {
+        Box::new(TourSearch::new(algorithm, grid.clone(), heuristic))
+    } else {
+        algorithm.make_state(grid.clone(), heuristic)
+    };
+    let search = SearchResult::run(state.as_mut(), grid);
+
+    RunResult {
+        algorithm,
+        search,
+        duration: started.elapsed(),
+    }
+}
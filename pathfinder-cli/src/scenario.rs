@@ -0,0 +1,123 @@
+use crate::run::run;
+use pathfinder_core::heuristic::HeuristicOptions;
+use pathfinder_core::movingai::{MovingAiMap, Scenario};
+use pathfinder_core::PathFindAlgorithms;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// The algorithms which have to find paths of exactly the optimal length on the benchmark maps.
+/// The others are only reported, their paths are allowed to be longer or, for any-angle paths, shorter.
+const OPTIMAL_ALGORITHMS: [PathFindAlgorithms; 8] = [
+    PathFindAlgorithms::Dijkstra,
+    PathFindAlgorithms::AStar,
+    PathFindAlgorithms::BidirectionalDijkstra,
+    PathFindAlgorithms::BidirectionalAStar,
+    PathFindAlgorithms::JumpPointSearch,
+    PathFindAlgorithms::JumpPointSearchPlus,
+    PathFindAlgorithms::LpaStar,
+    PathFindAlgorithms::IdaStar,
+];
+
+/// How the paths an algorithm found compare to the optimal lengths.
+#[derive(Clone, Debug, Default)]
+struct ScenarioSummary {
+    optimal: usize,
+    longer: usize,
+    shorter: usize,
+    not_found: usize,
+    duration: Duration,
+}
+
+/// Runs the algorithms on every problem of the `.scen` files
+/// and returns whether every algorithm that should find optimal paths did so.
+pub fn check_scenarios(
+    scenarios: &[PathBuf],
+    algorithms: &[PathFindAlgorithms],
+    heuristic: HeuristicOptions,
+) -> Result<bool, String> {
+    let mut maps = HashMap::new();
+    let mut summaries = vec![ScenarioSummary::default(); algorithms.len()];
+    for scenario_path in scenarios {
+        let scenario = std::fs::read_to_string(scenario_path)
+            .map_err(|error| format!("can't read {}: {}", scenario_path.display(), error))?
+            .parse::<Scenario>()
+            .map_err(|error| format!("{}: {}", scenario_path.display(), error))?;
+
+        for (i, entry) in scenario.entries.iter().enumerate() {
+            let map_path = map_path(scenario_path, &entry.map);
+            if !maps.contains_key(&map_path) {
+                maps.insert(map_path.clone(), load_movingai_map(&map_path)?);
+            }
+            let grid = entry.to_grid(&maps[&map_path]);
+
+            for (algorithm, summary) in algorithms.iter().zip(&mut summaries) {
+                let result = run(*algorithm, &grid, heuristic, false);
+                summary.duration += result.duration;
+
+                let tolerance = 1e-4 * entry.optimal_length.max(1.0);
+                match result.search.cost.map(f64::from) {
+                    None => summary.not_found += 1,
+                    Some(cost) if cost > entry.optimal_length + tolerance => summary.longer += 1,
+                    Some(cost) if cost < entry.optimal_length - tolerance => summary.shorter += 1,
+                    Some(_) => {
+                        summary.optimal += 1;
+                        continue;
+                    }
+                }
+                if OPTIMAL_ALGORITHMS.contains(algorithm) {
+                    eprintln!(
+                        "{} problem {}: {} found {} instead of {}",
+                        scenario_path.display(),
+                        i + 1,
+                        algorithm.id(),
+                        result
+                            .search
+                            .cost
+                            .map(|cost| cost.to_string())
+                            .unwrap_or_else(|| "no path".to_string()),
+                        entry.optimal_length
+                    );
+                }
+            }
+        }
+    }
+
+    println!("algorithm,optimal,longer,shorter,not_found,time_ms");
+    let mut all_optimal = true;
+    for (algorithm, summary) in algorithms.iter().zip(&summaries) {
+        println!(
+            "{},{},{},{},{},{:.3}",
+            algorithm.id(),
+            summary.optimal,
+            summary.longer,
+            summary.shorter,
+            summary.not_found,
+            summary.duration.as_secs_f64() * 1000.0
+        );
+        if OPTIMAL_ALGORITHMS.contains(algorithm)
+            && summary.longer + summary.shorter + summary.not_found > 0
+        {
+            all_optimal = false;
+        }
+    }
+    Ok(all_optimal)
+}
+
+/// The maps are given relative to the scenario file, but some benchmark sets include directories
+/// which aren't part of the download, so the file next to the scenario is used as a fallback.
+fn map_path(scenario: &Path, map: &str) -> PathBuf {
+    let directory = scenario.parent().unwrap_or(Path::new(""));
+    let path = directory.join(map);
+    match Path::new(map).file_name() {
+        Some(file_name) if !path.exists() => directory.join(file_name),
+        _ => path,
+    }
+}
+
+fn load_movingai_map(path: &Path) -> Result<MovingAiMap, String> {
+    std::fs::read_to_string(path)
+        .map_err(|error| format!("can't read {}: {}", path.display(), error))?
+        .parse()
+        .map_err(|error| format!("{}: {}", path.display(), error))
+}
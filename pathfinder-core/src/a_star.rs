@@ -0,0 +1,33 @@
+//! A*.
+
+use crate::best_first::{BestFirst, PosPrioritizer};
+use crate::heuristic::{GoalEstimate, HeuristicOptions};
+use crate::open_set::PriorityOpenSet;
+use crate::{Grid, Pos};
+
+/// Expands the position with the lowest sum of the path cost to it and the estimated cost to the goal.
+/// The path is the cheapest one as long as the heuristic never overestimates.
+pub type AStar = BestFirst<AStarPrioritizer>;
+
+/// Orders the positions for [AStar].
+#[derive(Clone, Debug)]
+pub struct AStarPrioritizer {
+    estimate: GoalEstimate,
+}
+
+impl PosPrioritizer for AStarPrioritizer {
+    type OpenSet = PriorityOpenSet;
+
+    fn new_prioritizer(grid: &Grid, goals: &[Pos], heuristic: HeuristicOptions) -> Self {
+        Self {
+            estimate: GoalEstimate::new(grid, goals, heuristic),
+        }
+    }
+
+    fn priority(&self, pos: Pos, distance: f32) -> f32 {
+        // we can only guess the distance to the goal as we don't know the path yet
+        let distance_to_goal = self.estimate.estimate(pos);
+
+        distance + distance_to_goal
+    }
+}
@@ -0,0 +1,220 @@
+//! The shared parts of the searches which expand one position after another.
+
+use crate::heuristic::HeuristicOptions;
+use crate::open_set::OpenSet;
+use crate::PathFindAlgorithmStepResult::{InProgress, NotFound};
+use crate::{
+    Grid, PathFindAlgorithm, PathFindAlgorithmConstructor, PathFindAlgorithmStepResult, Pos, Vec2d,
+};
+
+/// Decides in which order a [BestFirst] search expands the positions.
+pub trait PosPrioritizer {
+    /// The open set which decides in which order the discovered positions are expanded.
+    type OpenSet: OpenSet;
+    /// Whether a discovered position gets a new parent once a cheaper path to it is found.
+    /// Searches which don't care about the path cost keep the parent which discovered the position first.
+    const RELAX_EDGES: bool = true;
+    /// Whether the priority is just the distance from the position the search started at.
+    const PRIORITY_IS_DISTANCE: bool = false;
+
+    /// Creates the prioritizer for a search which tries to reach one of the goals.
+    fn new_prioritizer(grid: &Grid, goals: &[Pos], heuristic: HeuristicOptions) -> Self;
+    /// Returns the priority of a position, lower priorities are expanded first.
+    /// The distance is the cost of the path from the position the search started at.
+    fn priority(&self, pos: Pos, distance: f32) -> f32;
+}
+
+/// The state of a best first search growing from a single root position.
+/// It doesn't know in which direction the search moves, so it can be used from both ends of a path.
+#[derive(Clone, Debug)]
+pub struct SearchFrontier<P: PosPrioritizer> {
+    open_set: P::OpenSet,
+    distances: Vec2d<f32>,
+    closed: Vec2d<bool>,
+    backtrace: Vec2d<Option<Pos>>,
+    visited: Vec<Pos>,
+    expanded: usize,
+    prioritizer: P,
+}
+
+impl<P> SearchFrontier<P>
+where
+    P: PosPrioritizer,
+{
+    /// Creates a frontier which only contains the root and grows towards the closest of the goals.
+    pub fn new(grid: &Grid, root: Pos, goals: &[Pos], heuristic: HeuristicOptions) -> Self {
+        let (width, height) = (grid.width() as usize, grid.height() as usize);
+        let mut frontier = Self {
+            open_set: P::OpenSet::new_open_set(grid),
+            distances: Vec2d::new(width, height, f32::INFINITY),
+            closed: Vec2d::new(width, height, false),
+            backtrace: Vec2d::new(width, height, None),
+            prioritizer: P::new_prioritizer(grid, goals, heuristic),
+            visited: vec![],
+            expanded: 0,
+        };
+
+        frontier.distances.set(root, 0.0);
+        frontier
+            .open_set
+            .push(root, frontier.prioritizer.priority(root, 0.0));
+        frontier
+    }
+
+    /// Removes the next position from the open set and closes it.
+    pub fn pop(&mut self) -> Option<Pos> {
+        let pos = self.open_set.pop()?;
+        self.closed.set(pos, true);
+        self.expanded += 1;
+        Some(pos)
+    }
+
+    /// Returns the priority of the position which will be popped next.
+    pub fn peek_priority(&mut self) -> Option<f32> {
+        self.open_set.peek_priority()
+    }
+
+    /// Discovers the given neighbors of an expanded position, given with the cost of moving to them.
+    pub fn expand(&mut self, pos: Pos, neighbors: Vec<(Pos, f32)>) {
+        let distance = self.distance(pos);
+        for (neighbor, cost) in neighbors {
+            self.discover(neighbor, pos, distance + cost);
+        }
+    }
+
+    /// Discovers the position through the parent, if this is the cheapest known path to it.
+    /// The distance is the cost of the path from the root through the parent to the position.
+    pub fn discover(&mut self, pos: Pos, parent: Pos, distance: f32) {
+        // the shortest path to a closed position is already known
+        if self.closed(pos) {
+            return;
+        }
+        let discovered = self.discovered(pos);
+        if discovered && !P::RELAX_EDGES {
+            return;
+        }
+        if distance >= self.distance(pos) {
+            return;
+        }
+
+        self.distances.set(pos, distance);
+        self.backtrace.set(pos, Some(parent));
+        self.open_set
+            .push(pos, self.prioritizer.priority(pos, distance));
+        if !discovered {
+            self.visited.push(pos);
+        }
+    }
+
+    /// Replaces the parent of an already popped position without queueing it again.
+    pub fn reparent(&mut self, pos: Pos, parent: Pos, distance: f32) {
+        self.distances.set(pos, distance);
+        self.backtrace.set(pos, Some(parent));
+    }
+
+    /// Returns the cost of the best known path from the root to the position.
+    pub fn distance(&self, pos: Pos) -> f32 {
+        self.distances.get(pos).copied().unwrap_or(f32::INFINITY)
+    }
+
+    /// Returns whether a path from the root to the position is known.
+    pub fn discovered(&self, pos: Pos) -> bool {
+        self.distance(pos).is_finite()
+    }
+
+    /// Returns whether the position was already expanded.
+    pub fn closed(&self, pos: Pos) -> bool {
+        matches!(self.closed.get(pos), Some(true))
+    }
+
+    /// Returns whether the position was discovered but not expanded yet.
+    pub fn in_queue(&self, pos: Pos) -> bool {
+        self.open_set.contains(pos)
+    }
+
+    /// The discovered positions in the order they were discovered, without the root.
+    pub fn visited_list(&self) -> &[Pos] {
+        &self.visited
+    }
+
+    /// Returns the number of positions which were popped.
+    pub fn expanded(&self) -> usize {
+        self.expanded
+    }
+
+    /// Returns the position from which the position was discovered.
+    pub fn parent(&self, pos: Pos) -> Option<Pos> {
+        self.backtrace.get(pos).copied().flatten()
+    }
+
+    /// Returns the path from the position back to the root, starting with the position.
+    pub fn path_to_root(&self, pos: Pos) -> Option<Vec<Pos>> {
+        if !self.discovered(pos) {
+            return None;
+        }
+
+        let mut path = vec![pos];
+        let mut pos = pos;
+        while let Some(parent) = self.parent(pos) {
+            path.push(parent);
+            pos = parent;
+        }
+        Some(path)
+    }
+}
+
+/// A search which expands one position per step in the order the [PosPrioritizer] decides
+/// until it reaches the end or one of the goals.
+#[derive(Clone, Debug)]
+pub struct BestFirst<P: PosPrioritizer> {
+    grid: Grid,
+    frontier: SearchFrontier<P>,
+}
+
+impl<P> PathFindAlgorithmConstructor for BestFirst<P>
+where
+    P: PosPrioritizer,
+{
+    fn make_state(grid: Grid, heuristic: HeuristicOptions) -> Self {
+        Self {
+            frontier: SearchFrontier::new(&grid, grid.start(), &grid.targets(), heuristic),
+            grid,
+        }
+    }
+}
+impl<P> PathFindAlgorithm for BestFirst<P>
+where
+    P: PosPrioritizer,
+{
+    fn next_step(&mut self) -> Result<Vec<Pos>, PathFindAlgorithmStepResult> {
+        // if the open set is empty, no more tiles to search exist
+        let pos = self.frontier.pop().ok_or(NotFound)?;
+
+        // if the tile is the end or a goal, backtrace the path
+        if self.grid.is_goal(pos) {
+            let mut path = self.frontier.path_to_root(pos).ok_or(NotFound)?;
+            path.reverse();
+            return Ok(path);
+        }
+
+        self.frontier.expand(pos, self.grid.neighbors(pos));
+
+        Err(InProgress)
+    }
+
+    fn visited(&self, pos: Pos) -> bool {
+        self.frontier.discovered(pos)
+    }
+
+    fn visited_list(&self) -> &[Pos] {
+        self.frontier.visited_list()
+    }
+
+    fn expanded(&self) -> usize {
+        self.frontier.expanded()
+    }
+
+    fn in_queue(&self, pos: Pos) -> bool {
+        self.frontier.in_queue(pos)
+    }
+}
@@ -0,0 +1,158 @@
+//! Searches which start from both ends of the path.
+
+use crate::a_star::AStarPrioritizer;
+use crate::best_first::{PosPrioritizer, SearchFrontier};
+use crate::breadth_first::BreadthFirstPrioritizer;
+use crate::dijkstra::DijkstraPrioritizer;
+use crate::heuristic::HeuristicOptions;
+use crate::PathFindAlgorithmStepResult::{InProgress, NotFound};
+use crate::{
+    Grid, PathFindAlgorithm, PathFindAlgorithmConstructor, PathFindAlgorithmStepResult, Pos,
+};
+
+/// Breadth first search from both sides.
+pub type BidirectionalBreadthFirst = Bidirectional<BreadthFirstPrioritizer>;
+/// Dijkstra's algorithm from both sides.
+pub type BidirectionalDijkstra = Bidirectional<DijkstraPrioritizer>;
+/// A* from both sides, each side estimating the distance to the other end.
+pub type BidirectionalAStar = Bidirectional<AStarPrioritizer>;
+
+/// Searches from the start and the end at the same time, taking turns expanding one position on each side,
+/// and stitches the two halves together where the frontiers meet.
+///
+/// Searches which don't relax edges stop as soon as the frontiers meet.
+/// All other searches keep track of the cheapest path through a meeting point
+/// and stop once the open sets prove that no cheaper one can exist.
+#[derive(Clone, Debug)]
+pub struct Bidirectional<P: PosPrioritizer> {
+    grid: Grid,
+    forward: SearchFrontier<P>,
+    backward: SearchFrontier<P>,
+    expand_backward: bool,
+    /// The position through which the cheapest known path goes, with the cost of that path
+    meeting: Option<(Pos, f32)>,
+}
+
+impl<P> Bidirectional<P>
+where
+    P: PosPrioritizer,
+{
+    fn update_meeting(&mut self, pos: Pos) {
+        let cost = self.forward.distance(pos) + self.backward.distance(pos);
+        if !cost.is_finite() {
+            return;
+        }
+
+        match self.meeting {
+            Some((_, meeting_cost)) if meeting_cost <= cost => {}
+            _ => self.meeting = Some((pos, cost)),
+        }
+    }
+
+    fn can_stop(&mut self) -> bool {
+        let meeting_cost = match self.meeting {
+            Some((_, meeting_cost)) => meeting_cost,
+            None => return false,
+        };
+        if !P::RELAX_EDGES {
+            return true;
+        }
+
+        let forward_priority = self.forward.peek_priority().unwrap_or(f32::INFINITY);
+        let backward_priority = self.backward.peek_priority().unwrap_or(f32::INFINITY);
+        if P::PRIORITY_IS_DISTANCE {
+            // A cheaper path would have to leave both searched areas,
+            // which costs at least the distance of the next position on each side
+            forward_priority + backward_priority >= meeting_cost
+        } else {
+            // Every cheaper path has a position in both open sets with a priority below its cost,
+            // so once one side can't offer a lower priority, the meeting point is the best one
+            forward_priority.max(backward_priority) >= meeting_cost
+        }
+    }
+
+    fn path(&self) -> Option<Vec<Pos>> {
+        let (meeting, _) = self.meeting?;
+
+        let mut path = self.forward.path_to_root(meeting)?;
+        path.reverse();
+        let backward_path = self.backward.path_to_root(meeting)?;
+        path.extend(backward_path.into_iter().skip(1));
+        Some(path)
+    }
+}
+
+impl<P> PathFindAlgorithmConstructor for Bidirectional<P>
+where
+    P: PosPrioritizer,
+{
+    fn make_state(grid: Grid, heuristic: HeuristicOptions) -> Self {
+        let mut state = Self {
+            forward: SearchFrontier::new(&grid, grid.start(), &[grid.end()], heuristic),
+            backward: SearchFrontier::new(&grid, grid.end(), &[grid.start()], heuristic),
+            grid,
+            expand_backward: false,
+            meeting: None,
+        };
+        state.update_meeting(state.grid.start());
+        state
+    }
+}
+
+impl<P> PathFindAlgorithm for Bidirectional<P>
+where
+    P: PosPrioritizer,
+{
+    fn next_step(&mut self) -> Result<Vec<Pos>, PathFindAlgorithmStepResult> {
+        if self.can_stop() {
+            return self.path().ok_or(NotFound);
+        }
+
+        let expand_backward = self.expand_backward;
+        self.expand_backward = !expand_backward;
+        let frontier = if expand_backward {
+            &mut self.backward
+        } else {
+            &mut self.forward
+        };
+
+        // if one side runs out of tiles, no other path than the best known one can exist
+        let pos = match frontier.pop() {
+            Some(pos) => pos,
+            None => return self.path().ok_or(NotFound),
+        };
+        let neighbors = if expand_backward {
+            self.grid.predecessors(pos)
+        } else {
+            self.grid.neighbors(pos)
+        };
+        frontier.expand(pos, neighbors.clone());
+
+        self.update_meeting(pos);
+        for (neighbor, _) in neighbors {
+            self.update_meeting(neighbor);
+        }
+
+        Err(InProgress)
+    }
+
+    fn visited(&self, pos: Pos) -> bool {
+        self.forward.discovered(pos) || self.backward.discovered(pos)
+    }
+
+    fn visited_list(&self) -> &[Pos] {
+        self.forward.visited_list()
+    }
+
+    fn visited_from_end_list(&self) -> &[Pos] {
+        self.backward.visited_list()
+    }
+
+    fn expanded(&self) -> usize {
+        self.forward.expanded() + self.backward.expanded()
+    }
+
+    fn in_queue(&self, pos: Pos) -> bool {
+        self.forward.in_queue(pos) || self.backward.in_queue(pos)
+    }
+}
@@ -0,0 +1,26 @@
+//! Breadth first search.
+
+use crate::best_first::{BestFirst, PosPrioritizer};
+use crate::heuristic::HeuristicOptions;
+use crate::open_set::FifoOpenSet;
+use crate::{Grid, Pos};
+
+/// Expands the positions in the order they were discovered, which finds the path with the fewest steps.
+pub type BreadthFirst = BestFirst<BreadthFirstPrioritizer>;
+
+/// Orders the positions for [BreadthFirst].
+#[derive(Clone, Debug)]
+pub struct BreadthFirstPrioritizer;
+
+impl PosPrioritizer for BreadthFirstPrioritizer {
+    type OpenSet = FifoOpenSet;
+    const RELAX_EDGES: bool = false;
+
+    fn new_prioritizer(_grid: &Grid, _goals: &[Pos], _heuristic: HeuristicOptions) -> Self {
+        Self
+    }
+
+    fn priority(&self, _pos: Pos, _distance: f32) -> f32 {
+        0.0
+    }
+}
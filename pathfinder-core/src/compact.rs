@@ -0,0 +1,344 @@
+//! A compact encoding of grids, which is short enough to be shared in links.
+//!
+//! The grid is written as bytes and then as URL safe base64 without padding. The bytes are:
+//!
+//! 1. the version of the encoding, `1`, `2`, `3` or `4`
+//! 2. the width, the height and the x and y of the start and of the end as variable length integers
+//! 3. the movement, `0` for four way, `1` for eight way, `2` for eight way without corner cutting
+//!    and since version `3` also `3` for hex grids
+//! 4. since version `4`: `1` if the grid wraps around its edges, otherwise `0`
+//! 5. since version `2`: the number of waypoints followed by the x and y of every waypoint,
+//!    then the same for the goals, all as variable length integers
+//! 6. the tiles row by row, run-length encoded as pairs of a tile and a variable length count.
+//!    Before version `3`, empty tiles are `0`, walls `1` and weighted tiles `1` plus their cost,
+//!    so only costs from `1` to `254` can be written.
+//!    Since version `3`, empty tiles are `0`, walls `1`, weighted tiles `2` followed by their cost,
+//!    portals `3` followed by their number and one-way tiles `4` to `7` for up, down, left and right.
+//!
+//! Grids are written with the oldest version which can hold them, so the links of older grids stay the same:
+//! version `1` without waypoints and goals, version `3` only with portals, one-way tiles, costs of `0` or `255`
+//! or hex movement
+//! and version `4` only for wrapping grids.
+//!
+//! Variable length integers store 7 bits per byte, starting with the lowest ones,
+//! and set the highest bit of every byte except the last.
+//!
+//! ```
+//! use pathfinder_core::compact::{decode_grid, encode_grid};
+//! use pathfinder_core::Grid;
+//!
+//! let grid = "S...#####...E".parse::<Grid>().unwrap();
+//! assert_eq!(decode_grid(&encode_grid(&grid)), Ok(grid));
+//! ```
+
+use crate::{Direction, Grid, Movement, Pos, Tile, Unit};
+use std::fmt::{Display, Formatter};
+
+/// The newest version of the encoding, which [encode_grid] writes for wrapping grids.
+pub const VERSION: u8 = 4;
+
+/// The largest number of tiles a decoded grid may have, so a short text can't allocate huge grids.
+pub const MAX_TILES: u64 = 1 << 24;
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// Why an encoded grid couldn't be decoded.
+#[derive(Clone, Debug, PartialEq)]
+pub enum DecodeGridError {
+    /// The text contains characters which aren't URL safe base64.
+    InvalidBase64,
+    /// The grid was encoded with a version this library doesn't know.
+    UnsupportedVersion(u8),
+    /// The data ends before the grid is complete or has data left after it.
+    InvalidLength,
+    /// A byte which doesn't stand for a tile, movement or wrapping.
+    InvalidValue(u8),
+    /// The start, the end, a waypoint or a goal lies outside of the grid.
+    InvalidPosition(Pos),
+    /// The grid has more than [MAX_TILES] tiles.
+    TooLarge,
+}
+
+impl Display for DecodeGridError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DecodeGridError::InvalidBase64 => write!(f, "the grid isn't valid base64"),
+            DecodeGridError::UnsupportedVersion(version) => {
+                write!(f, "the grid has the unsupported version {}", version)
+            }
+            DecodeGridError::InvalidLength => write!(f, "the grid has the wrong length"),
+            DecodeGridError::InvalidValue(value) => {
+                write!(f, "the grid contains the invalid value {}", value)
+            }
+            DecodeGridError::InvalidPosition(pos) => write!(
+                f,
+                "the marker ({}, {}) lies outside of the grid",
+                pos.x, pos.y
+            ),
+            DecodeGridError::TooLarge => write!(f, "the grid has more than {} tiles", MAX_TILES),
+        }
+    }
+}
+
+impl std::error::Error for DecodeGridError {}
+
+/// Encodes the size, tiles, start, end, waypoints, goals, movement and wrapping of the grid.
+pub fn encode_grid(grid: &Grid) -> String {
+    let positions = (0..grid.height()).flat_map(|y| (0..grid.width()).map(move |x| Pos { x, y }));
+    let version = if grid.wraps() {
+        4
+    } else if grid.movement().is_hex()
+        || positions
+            .clone()
+            .any(|pos| !fits_old_versions(grid.tile(pos)))
+    {
+        3
+    } else if !grid.waypoints().is_empty() || !grid.goals().is_empty() {
+        2
+    } else {
+        1
+    };
+    let mut bytes = vec![version];
+    for value in [
+        grid.width(),
+        grid.height(),
+        grid.start().x,
+        grid.start().y,
+        grid.end().x,
+        grid.end().y,
+    ] {
+        write_varint(&mut bytes, value as u32);
+    }
+    bytes.push(match grid.movement() {
+        Movement::FourWay => 0,
+        Movement::EightWay => 1,
+        Movement::EightWayNoCornerCutting => 2,
+        Movement::Hex => 3,
+    });
+    if version >= 4 {
+        bytes.push(u8::from(grid.wraps()));
+    }
+    if version >= 2 {
+        for markers in [grid.waypoints(), grid.goals()] {
+            write_varint(&mut bytes, markers.len() as u32);
+            for pos in markers {
+                write_varint(&mut bytes, pos.x as u32);
+                write_varint(&mut bytes, pos.y as u32);
+            }
+        }
+    }
+
+    let mut run: Option<(Tile, u32)> = None;
+    for tile in positions.map(|pos| grid.tile(pos)) {
+        match &mut run {
+            Some((run_tile, count)) if *run_tile == tile => *count += 1,
+            _ => {
+                if let Some((run_tile, count)) = run {
+                    write_tile(&mut bytes, version, run_tile);
+                    write_varint(&mut bytes, count);
+                }
+                run = Some((tile, 1));
+            }
+        }
+    }
+    if let Some((run_tile, count)) = run {
+        write_tile(&mut bytes, version, run_tile);
+        write_varint(&mut bytes, count);
+    }
+
+    encode_base64(&bytes)
+}
+
+/// Decodes a grid written by [encode_grid].
+pub fn decode_grid(text: &str) -> Result<Grid, DecodeGridError> {
+    let bytes = decode_base64(text)?;
+    let mut bytes = bytes.iter().copied();
+
+    let version = bytes.next().ok_or(DecodeGridError::InvalidLength)?;
+    if !(1..=VERSION).contains(&version) {
+        return Err(DecodeGridError::UnsupportedVersion(version));
+    }
+
+    let (width, height) = (read_unit(&mut bytes)?, read_unit(&mut bytes)?);
+    let tile_count = width as u64 * height as u64;
+    if tile_count > MAX_TILES {
+        return Err(DecodeGridError::TooLarge);
+    }
+    let start = read_pos(&mut bytes)?;
+    let end = read_pos(&mut bytes)?;
+    let in_grid = |pos: Pos| {
+        if pos.x >= width || pos.y >= height {
+            return Err(DecodeGridError::InvalidPosition(pos));
+        }
+        Ok(pos)
+    };
+    let (start, end) = (in_grid(start)?, in_grid(end)?);
+
+    let movement = match bytes.next().ok_or(DecodeGridError::InvalidLength)? {
+        0 => Movement::FourWay,
+        1 => Movement::EightWay,
+        2 => Movement::EightWayNoCornerCutting,
+        3 if version >= 3 => Movement::Hex,
+        value => return Err(DecodeGridError::InvalidValue(value)),
+    };
+
+    let mut grid = Grid::new(width, height, start, end);
+    grid.set_movement(movement);
+    if version >= 4 {
+        grid.set_wraps(match bytes.next().ok_or(DecodeGridError::InvalidLength)? {
+            0 => false,
+            1 => true,
+            value => return Err(DecodeGridError::InvalidValue(value)),
+        });
+    }
+    if version >= 2 {
+        for add_marker in [Grid::add_waypoint as fn(&mut Grid, Pos), Grid::add_goal] {
+            for _ in 0..read_unit(&mut bytes)? {
+                add_marker(&mut grid, in_grid(read_pos(&mut bytes)?)?);
+            }
+        }
+    }
+
+    let mut index = 0;
+    while let Some(tile) = bytes.next() {
+        let tile = read_tile(&mut bytes, version, tile)?;
+        let count = read_varint(&mut bytes).ok_or(DecodeGridError::InvalidLength)? as u64;
+        if index + count > tile_count {
+            return Err(DecodeGridError::InvalidLength);
+        }
+
+        for i in index..index + count {
+            let pos = Pos {
+                x: (i % width as u64) as Unit,
+                y: (i / width as u64) as Unit,
+            };
+            grid.set_tile(pos, tile);
+        }
+        index += count;
+    }
+    if index != tile_count {
+        return Err(DecodeGridError::InvalidLength);
+    }
+
+    Ok(grid)
+}
+
+/// Returns whether the tile can be written before version `3`.
+fn fits_old_versions(tile: Tile) -> bool {
+    match tile {
+        Tile::None | Tile::Wall => true,
+        // a cost of 0 would be written as a wall and one of 255 wouldn't fit into a byte
+        Tile::Cost(cost) => (1..u8::MAX).contains(&cost),
+        Tile::Portal(_) | Tile::OneWay(_) => false,
+    }
+}
+
+fn write_tile(bytes: &mut Vec<u8>, version: u8, tile: Tile) {
+    match tile {
+        Tile::None => bytes.push(0),
+        Tile::Wall => bytes.push(1),
+        Tile::Cost(cost) if version < 3 => bytes.push(cost + 1),
+        Tile::Cost(cost) => bytes.extend([2, cost]),
+        Tile::Portal(number) => bytes.extend([3, number]),
+        Tile::OneWay(direction) => {
+            let index = Direction::ALL
+                .iter()
+                .position(|value| *value == direction)
+                .expect("every direction is in the list");
+            bytes.push(4 + index as u8);
+        }
+    }
+}
+
+fn read_tile(
+    bytes: &mut impl Iterator<Item = u8>,
+    version: u8,
+    tile: u8,
+) -> Result<Tile, DecodeGridError> {
+    let mut value = || bytes.next().ok_or(DecodeGridError::InvalidLength);
+    Ok(match tile {
+        0 => Tile::None,
+        1 => Tile::Wall,
+        cost if version < 3 => Tile::Cost(cost - 1),
+        2 => Tile::Cost(value()?),
+        3 => Tile::Portal(value()?),
+        4..=7 => Tile::OneWay(Direction::ALL[tile as usize - 4]),
+        value => return Err(DecodeGridError::InvalidValue(value)),
+    })
+}
+
+fn read_unit(bytes: &mut impl Iterator<Item = u8>) -> Result<Unit, DecodeGridError> {
+    read_varint(bytes)
+        .and_then(|value| Unit::try_from(value).ok())
+        .ok_or(DecodeGridError::InvalidLength)
+}
+
+fn read_pos(bytes: &mut impl Iterator<Item = u8>) -> Result<Pos, DecodeGridError> {
+    Ok(Pos {
+        x: read_unit(bytes)?,
+        y: read_unit(bytes)?,
+    })
+}
+
+fn write_varint(bytes: &mut Vec<u8>, mut value: u32) {
+    while value >= 0x80 {
+        bytes.push((value & 0x7f) as u8 | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+fn read_varint(bytes: &mut impl Iterator<Item = u8>) -> Option<u32> {
+    let mut value = 0u32;
+    for shift in (0..32).step_by(7) {
+        let byte = bytes.next()?;
+        value |= ((byte & 0x7f) as u32) << shift;
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+    None
+}
+
+fn encode_base64(bytes: &[u8]) -> String {
+    let mut text = String::with_capacity((bytes.len() * 4).div_ceil(3));
+    for chunk in bytes.chunks(3) {
+        let bits = chunk.iter().enumerate().fold(0u32, |bits, (i, byte)| {
+            bits | (*byte as u32) << (16 - 8 * i)
+        });
+        for i in 0..=chunk.len() {
+            let index = (bits >> (18 - 6 * i)) & 0x3f;
+            text.push(BASE64_ALPHABET[index as usize] as char);
+        }
+    }
+    text
+}
+
+fn decode_base64(text: &str) -> Result<Vec<u8>, DecodeGridError> {
+    let values = text
+        .bytes()
+        .map(|character| {
+            BASE64_ALPHABET
+                .iter()
+                .position(|letter| *letter == character)
+                .map(|value| value as u32)
+                .ok_or(DecodeGridError::InvalidBase64)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut bytes = Vec::with_capacity(values.len() * 3 / 4);
+    for chunk in values.chunks(4) {
+        if chunk.len() == 1 {
+            return Err(DecodeGridError::InvalidBase64);
+        }
+        let bits = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |bits, (i, value)| bits | value << (18 - 6 * i));
+        for i in 0..chunk.len() - 1 {
+            bytes.push((bits >> (16 - 8 * i)) as u8);
+        }
+    }
+    Ok(bytes)
+}
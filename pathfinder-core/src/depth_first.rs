@@ -0,0 +1,26 @@
+//! Depth first search.
+
+use crate::best_first::{BestFirst, PosPrioritizer};
+use crate::heuristic::HeuristicOptions;
+use crate::open_set::LifoOpenSet;
+use crate::{Grid, Pos};
+
+/// Always expands the most recently discovered position, the path is usually far from the shortest one.
+pub type DepthFirst = BestFirst<DepthFirstPrioritizer>;
+
+/// Orders the positions for [DepthFirst].
+#[derive(Clone, Debug)]
+pub struct DepthFirstPrioritizer;
+
+impl PosPrioritizer for DepthFirstPrioritizer {
+    type OpenSet = LifoOpenSet;
+    const RELAX_EDGES: bool = false;
+
+    fn new_prioritizer(_grid: &Grid, _goals: &[Pos], _heuristic: HeuristicOptions) -> Self {
+        Self
+    }
+
+    fn priority(&self, _pos: Pos, _distance: f32) -> f32 {
+        0.0
+    }
+}
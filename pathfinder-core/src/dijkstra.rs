@@ -0,0 +1,26 @@
+//! Dijkstra's algorithm.
+
+use crate::best_first::{BestFirst, PosPrioritizer};
+use crate::heuristic::HeuristicOptions;
+use crate::open_set::PriorityOpenSet;
+use crate::{Grid, Pos};
+
+/// Expands the position with the cheapest path to it, which finds the cheapest path.
+pub type Dijkstra = BestFirst<DijkstraPrioritizer>;
+
+/// Orders the positions for [Dijkstra].
+#[derive(Clone, Debug)]
+pub struct DijkstraPrioritizer;
+
+impl PosPrioritizer for DijkstraPrioritizer {
+    type OpenSet = PriorityOpenSet;
+    const PRIORITY_IS_DISTANCE: bool = true;
+
+    fn new_prioritizer(_grid: &Grid, _goals: &[Pos], _heuristic: HeuristicOptions) -> Self {
+        Self
+    }
+
+    fn priority(&self, _pos: Pos, distance: f32) -> f32 {
+        distance
+    }
+}
@@ -0,0 +1,64 @@
+//! The recursive backtracker, a randomized depth first search.
+
+use crate::generators::{Cells, MazeGenerator, Rng};
+use crate::{Grid, Pos, Tile, Vec2d};
+
+/// Walks into random unvisited cells and backtracks once it's stuck,
+/// which creates long and winding corridors with few dead ends.
+#[derive(Clone, Debug)]
+pub struct RecursiveBacktracker {
+    grid: Grid,
+    cells: Cells,
+    rng: Rng,
+    visited: Vec2d<bool>,
+    stack: Vec<Pos>,
+}
+
+impl RecursiveBacktracker {
+    /// Creates the generator for a maze with the size, start, end and movement of the grid.
+    pub fn new(grid: &Grid, mut rng: Rng) -> Self {
+        let (mut grid, cells) = Cells::new_grid(grid, Tile::Wall);
+        let mut visited = Vec2d::new(cells.columns as usize, cells.rows as usize, false);
+
+        let first = cells.random(&mut rng);
+        visited.set(first, true);
+        Cells::open(&mut grid, first);
+        Self {
+            grid,
+            cells,
+            rng,
+            visited,
+            stack: vec![first],
+        }
+    }
+}
+
+impl MazeGenerator for RecursiveBacktracker {
+    fn next_step(&mut self) -> bool {
+        while let Some(&cell) = self.stack.last() {
+            let unvisited = self
+                .cells
+                .neighbors(cell)
+                .into_iter()
+                .filter(|neighbor| self.visited.get(*neighbor) == Some(&false))
+                .collect::<Vec<_>>();
+
+            match self.rng.choose(&unvisited) {
+                Some(&next) => {
+                    self.visited.set(next, true);
+                    Cells::carve(&mut self.grid, cell, next);
+                    self.stack.push(next);
+                    return false;
+                }
+                None => {
+                    self.stack.pop();
+                }
+            }
+        }
+        true
+    }
+
+    fn grid(&self) -> &Grid {
+        &self.grid
+    }
+}
@@ -0,0 +1,57 @@
+//! Caves grown with a cellular automaton.
+
+use crate::generators::{positions, Rng};
+use crate::{Grid, Pos, Tile, Vec2d};
+
+/// Fills the grid randomly with walls and then smooths it several times:
+/// tiles surrounded by many walls become walls and tiles with few walls around them become open,
+/// until only round caves remain. Tiles outside of the grid count as walls, so the caves don't touch its border much.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct CellularCave {
+    /// The share of walls the grid is filled with before the first smoothing, from 0 to 1.
+    pub density: f32,
+    /// How often the grid is smoothed.
+    pub iterations: u32,
+}
+
+impl Default for CellularCave {
+    fn default() -> Self {
+        Self {
+            density: 0.45,
+            iterations: 4,
+        }
+    }
+}
+
+impl CellularCave {
+    /// Replaces the tiles of the grid with caves.
+    pub fn generate(&self, grid: &mut Grid, rng: &mut Rng) {
+        let (width, height) = (grid.width() as usize, grid.height() as usize);
+        let mut walls = Vec2d::new(width, height, false);
+        for pos in positions(grid) {
+            walls.set(pos, rng.chance(self.density));
+        }
+
+        for _ in 0..self.iterations {
+            let mut smoothed = walls.clone();
+            for pos in positions(grid) {
+                let neighbors = Pos::ALL_DIRECTIONS
+                    .iter()
+                    .filter(|dir| walls.get(pos + **dir).copied().unwrap_or(true))
+                    .count();
+                let wall = *walls.get(pos).unwrap();
+                smoothed.set(pos, neighbors >= 5 || (wall && neighbors >= 4));
+            }
+            walls = smoothed;
+        }
+
+        for pos in positions(grid) {
+            let tile = if *walls.get(pos).unwrap() {
+                Tile::Wall
+            } else {
+                Tile::None
+            };
+            grid.set_tile(pos, tile);
+        }
+    }
+}
@@ -0,0 +1,120 @@
+//! Recursive division.
+
+use crate::generators::{Cells, MazeGenerator, Rng};
+use crate::{Grid, Pos, Tile, Unit};
+
+/// Starts without any walls and divides the grid by a wall with a single gap,
+/// then divides both halves the same way until the chambers are only one cell wide.
+/// The walls create long straight corridors. Each step builds one wall.
+#[derive(Clone, Debug)]
+pub struct RecursiveDivision {
+    grid: Grid,
+    rng: Rng,
+    /// The chambers which still have to be divided, as their first cell and their size in cells.
+    chambers: Vec<(Pos, Pos)>,
+}
+
+impl RecursiveDivision {
+    /// Creates the generator for a maze with the size, start, end and movement of the grid.
+    pub fn new(grid: &Grid, rng: Rng) -> Self {
+        let (grid, cells) = Cells::new_grid(grid, Tile::None);
+        Self {
+            grid,
+            rng,
+            chambers: vec![(
+                Pos { x: 0, y: 0 },
+                Pos {
+                    x: cells.columns,
+                    y: cells.rows,
+                },
+            )],
+        }
+    }
+
+    /// Builds a wall across the chamber in front of the column or row of cells `at`,
+    /// leaving a gap at the cell `gap` of the other axis.
+    fn build_wall(&mut self, corner: Pos, size: Pos, vertical: bool, at: Unit, gap: Unit) {
+        let (start, length) = if vertical {
+            (corner.y, size.y)
+        } else {
+            (corner.x, size.x)
+        };
+        // the wall also covers the corners between the cells, so nothing can slip through diagonally
+        for along in 2 * start - 1..2 * (start + length) {
+            if along == 2 * gap {
+                continue;
+            }
+            let pos = if vertical {
+                Pos {
+                    x: 2 * at - 1,
+                    y: along,
+                }
+            } else {
+                Pos {
+                    x: along,
+                    y: 2 * at - 1,
+                }
+            };
+            self.grid.set_tile(pos, Tile::Wall);
+        }
+    }
+}
+
+impl MazeGenerator for RecursiveDivision {
+    fn next_step(&mut self) -> bool {
+        while let Some((corner, size)) = self.chambers.pop() {
+            if size.x < 2 && size.y < 2 {
+                continue;
+            }
+            let vertical = match size.x.cmp(&size.y) {
+                std::cmp::Ordering::Greater => true,
+                std::cmp::Ordering::Less => false,
+                std::cmp::Ordering::Equal => self.rng.chance(0.5),
+            };
+
+            if vertical {
+                let at = corner.x + 1 + self.rng.below(size.x as usize - 1) as Unit;
+                let gap = corner.y + self.rng.below(size.y as usize) as Unit;
+                self.build_wall(corner, size, true, at, gap);
+                self.chambers.push((
+                    corner,
+                    Pos {
+                        x: at - corner.x,
+                        y: size.y,
+                    },
+                ));
+                self.chambers.push((
+                    Pos { x: at, y: corner.y },
+                    Pos {
+                        x: corner.x + size.x - at,
+                        y: size.y,
+                    },
+                ));
+            } else {
+                let at = corner.y + 1 + self.rng.below(size.y as usize - 1) as Unit;
+                let gap = corner.x + self.rng.below(size.x as usize) as Unit;
+                self.build_wall(corner, size, false, at, gap);
+                self.chambers.push((
+                    corner,
+                    Pos {
+                        x: size.x,
+                        y: at - corner.y,
+                    },
+                ));
+                self.chambers.push((
+                    Pos { x: corner.x, y: at },
+                    Pos {
+                        x: size.x,
+                        y: corner.y + size.y - at,
+                    },
+                ));
+            }
+            return false;
+        }
+        true
+    }
+
+    fn grid(&self) -> &Grid {
+        &self.grid
+    }
+}
@@ -0,0 +1,103 @@
+//! Eller's algorithm.
+
+use crate::generators::{Cells, MazeGenerator, Rng};
+use crate::{Grid, Pos, Tile, Unit};
+
+/// Creates the maze row by row, only remembering which cells of the current row are connected.
+/// Each step finishes one row.
+#[derive(Clone, Debug)]
+pub struct Eller {
+    grid: Grid,
+    cells: Cells,
+    rng: Rng,
+    row: Unit,
+    /// The set of every cell of the current row, cells in the same set are connected.
+    /// None for cells which aren't connected to the row above.
+    sets: Vec<Option<usize>>,
+    next_set: usize,
+}
+
+impl Eller {
+    /// Creates the generator for a maze with the size, start, end and movement of the grid.
+    pub fn new(grid: &Grid, rng: Rng) -> Self {
+        let (grid, cells) = Cells::new_grid(grid, Tile::Wall);
+        Self {
+            grid,
+            cells,
+            rng,
+            row: 0,
+            sets: vec![None; cells.columns as usize],
+            next_set: 0,
+        }
+    }
+}
+
+impl MazeGenerator for Eller {
+    fn next_step(&mut self) -> bool {
+        if self.row >= self.cells.rows {
+            return true;
+        }
+        let y = self.row;
+        let last_row = y == self.cells.rows - 1;
+
+        let mut sets = Vec::with_capacity(self.sets.len());
+        for set in &self.sets {
+            sets.push(set.unwrap_or_else(|| {
+                self.next_set += 1;
+                self.next_set
+            }));
+        }
+        for x in 0..self.cells.columns {
+            Cells::open(&mut self.grid, Pos { x, y });
+        }
+
+        // join neighboring cells of different sets, in the last row all of them so the maze is connected
+        for x in 1..self.cells.columns as usize {
+            if sets[x - 1] == sets[x] || !(last_row || self.rng.chance(0.5)) {
+                continue;
+            }
+            let (old, new) = (sets[x], sets[x - 1]);
+            sets.iter_mut()
+                .filter(|set| **set == old)
+                .for_each(|set| *set = new);
+            Cells::carve(
+                &mut self.grid,
+                Pos {
+                    x: x as Unit - 1,
+                    y,
+                },
+                Pos { x: x as Unit, y },
+            );
+        }
+
+        // every set continues downwards at least once, so no set is cut off
+        let mut next_sets = vec![None; sets.len()];
+        if !last_row {
+            let mut columns = (0..sets.len()).collect::<Vec<_>>();
+            self.rng.shuffle(&mut columns);
+            for x in columns {
+                let continued = next_sets.contains(&Some(sets[x]));
+                if continued && !self.rng.chance(0.5) {
+                    continue;
+                }
+                next_sets[x] = Some(sets[x]);
+                Cells::carve(
+                    &mut self.grid,
+                    Pos { x: x as Unit, y },
+                    Pos {
+                        x: x as Unit,
+                        y: y + 1,
+                    },
+                );
+            }
+        }
+
+        self.sets = next_sets;
+        self.row += 1;
+        false
+    }
+
+    fn grid(&self) -> &Grid {
+        &self.grid
+    }
+}
@@ -0,0 +1,73 @@
+//! Randomized Kruskal's algorithm.
+
+use crate::generators::{Cells, MazeGenerator, Rng};
+use crate::{Grid, Pos, Tile};
+
+/// Opens the walls between cells in a random order, skipping walls between cells which are already connected.
+/// The maze grows in many places at once.
+#[derive(Clone, Debug)]
+pub struct Kruskal {
+    grid: Grid,
+    cells: Cells,
+    /// The walls which weren't looked at yet, as the two cells they separate.
+    walls: Vec<(Pos, Pos)>,
+    /// The parent of every cell in the union-find forest of connected cells.
+    parents: Vec<usize>,
+}
+
+impl Kruskal {
+    /// Creates the generator for a maze with the size, start, end and movement of the grid.
+    pub fn new(grid: &Grid, mut rng: Rng) -> Self {
+        let (grid, cells) = Cells::new_grid(grid, Tile::Wall);
+
+        let mut walls = cells
+            .all()
+            .flat_map(|cell| {
+                [Pos::RIGHT, Pos::DOWN]
+                    .into_iter()
+                    .map(move |dir| (cell, cell + dir))
+            })
+            .filter(|(_, neighbor)| cells.contains(*neighbor))
+            .collect::<Vec<_>>();
+        rng.shuffle(&mut walls);
+
+        Self {
+            grid,
+            cells,
+            walls,
+            parents: (0..cells.count()).collect(),
+        }
+    }
+
+    fn root(&mut self, mut index: usize) -> usize {
+        while self.parents[index] != index {
+            // path halving keeps the trees flat
+            self.parents[index] = self.parents[self.parents[index]];
+            index = self.parents[index];
+        }
+        index
+    }
+}
+
+impl MazeGenerator for Kruskal {
+    fn next_step(&mut self) -> bool {
+        while let Some((from, to)) = self.walls.pop() {
+            let from_root = self.root(self.cells.index(from));
+            let to_root = self.root(self.cells.index(to));
+            if from_root == to_root {
+                continue;
+            }
+
+            self.parents[from_root] = to_root;
+            Cells::carve(&mut self.grid, from, to);
+            return false;
+        }
+        // a maze of a single cell has no walls to open
+        Cells::open(&mut self.grid, Pos { x: 0, y: 0 });
+        true
+    }
+
+    fn grid(&self) -> &Grid {
+        &self.grid
+    }
+}
@@ -0,0 +1,330 @@
+//! Generators which write mazes into a grid, driven by a seeded random number generator.
+//! Caves and noise are in [terrain].
+//!
+//! Like the searches, every maze generator works in steps, so the renderer can show how the maze is created.
+//! [MazeGenerators::generate] runs a generator to the end at once.
+//!
+//! The mazes are made of cells at the even positions of the grid, the tiles in between are walls or passages.
+//! The start, the end, the waypoints and the goals of the grid are moved onto the closest cell, so they always lie inside the maze.
+//! With four way movement and eight way movement without corner cutting every maze is perfect,
+//! there is exactly one path between every two cells.
+//! Cutting corners and the slanted neighbors of hex tiles can step from one passage of a cell to another one,
+//! which adds loops around the cell, but they never connect cells the maze keeps apart.
+//!
+//! ```
+//! use pathfinder_core::generators::MazeGenerators;
+//! use pathfinder_core::{Grid, Pos};
+//!
+//! let grid = Grid::new(21, 15, Pos { x: 0, y: 0 }, Pos { x: 20, y: 14 });
+//! let maze = MazeGenerators::Wilson.generate(&grid, 42);
+//! assert_eq!(maze, MazeGenerators::Wilson.generate(&grid, 42));
+//! ```
+
+pub mod backtracker;
+pub mod cave;
+pub mod division;
+pub mod eller;
+pub mod kruskal;
+pub mod noise;
+pub mod prim;
+pub mod terrain;
+pub mod wilson;
+
+use crate::{Grid, Pos, Tile, Unit, Vec2d};
+use std::collections::VecDeque;
+
+/// A small and fast random number generator (SplitMix64), which creates the same numbers for the same seed everywhere.
+#[derive(Clone, Debug)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    /// Creates the generator, the same seed always creates the same numbers.
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// Returns the next random number.
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a random number from 0 to below the bound.
+    ///
+    /// # Panics
+    /// If the bound is 0.
+    pub fn below(&mut self, bound: usize) -> usize {
+        assert!(bound > 0, "the bound has to be above 0");
+        (self.next_u64() % bound as u64) as usize
+    }
+
+    /// Returns a random number from 0 to below 1.
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    /// Returns true with the probability.
+    pub fn chance(&mut self, probability: f32) -> bool {
+        self.next_f32() < probability
+    }
+
+    /// Returns a random element of the slice or None if it's empty.
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> Option<&'a T> {
+        if items.is_empty() {
+            None
+        } else {
+            Some(&items[self.below(items.len())])
+        }
+    }
+
+    /// Brings the elements into a random order.
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+/// The state of a maze which is being generated.
+pub trait MazeGenerator {
+    /// Does one step, which usually opens a passage or builds a wall.
+    /// Returns whether the maze is complete.
+    fn next_step(&mut self) -> bool;
+
+    /// The grid with the maze as far as it's generated.
+    fn grid(&self) -> &Grid;
+}
+
+/// All available maze generators, see their modules for how they work.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum MazeGenerators {
+    /// [backtracker::RecursiveBacktracker]
+    RecursiveBacktracker,
+    /// [prim::Prim]
+    Prim,
+    /// [kruskal::Kruskal]
+    Kruskal,
+    /// [eller::Eller]
+    Eller,
+    /// [wilson::Wilson]
+    Wilson,
+    /// [division::RecursiveDivision]
+    RecursiveDivision,
+}
+
+impl MazeGenerators {
+    /// Every generator.
+    pub const ALL: [Self; 6] = [
+        Self::RecursiveBacktracker,
+        Self::Prim,
+        Self::Kruskal,
+        Self::Eller,
+        Self::Wilson,
+        Self::RecursiveDivision,
+    ];
+
+    /// Returns the identifier of the generator, which is used to select it in the UI and in files.
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::RecursiveBacktracker => "recursive_backtracker",
+            Self::Prim => "prim",
+            Self::Kruskal => "kruskal",
+            Self::Eller => "eller",
+            Self::Wilson => "wilson",
+            Self::RecursiveDivision => "recursive_division",
+        }
+    }
+
+    /// Returns the generator with the identifier, see [MazeGenerators::id].
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|value| value.id() == id)
+    }
+
+    /// Creates a generator for a maze with the size, start, end, movement and wrapping of the grid.
+    /// The tiles of the grid are replaced.
+    pub fn make_state(&self, grid: &Grid, seed: u64) -> Box<dyn MazeGenerator> {
+        let rng = Rng::new(seed);
+        match self {
+            Self::RecursiveBacktracker => {
+                Box::new(backtracker::RecursiveBacktracker::new(grid, rng))
+            }
+            Self::Prim => Box::new(prim::Prim::new(grid, rng)),
+            Self::Kruskal => Box::new(kruskal::Kruskal::new(grid, rng)),
+            Self::Eller => Box::new(eller::Eller::new(grid, rng)),
+            Self::Wilson => Box::new(wilson::Wilson::new(grid, rng)),
+            Self::RecursiveDivision => Box::new(division::RecursiveDivision::new(grid, rng)),
+        }
+    }
+
+    /// Generates the whole maze at once, see [MazeGenerators::make_state].
+    pub fn generate(&self, grid: &Grid, seed: u64) -> Grid {
+        let mut generator = self.make_state(grid, seed);
+        while !generator.next_step() {}
+        generator.grid().clone()
+    }
+}
+
+/// Opens as few walls as possible, so the end, the waypoints and the goals can be reached from the start with any movement.
+/// Grids in which that's already possible aren't changed.
+pub fn connect(grid: &mut Grid) {
+    let (width, height) = (grid.width() as usize, grid.height() as usize);
+    let mut walls_passed = Vec2d::new(width, height, u32::MAX);
+    let mut parents = Vec2d::new(width, height, None);
+
+    // a breadth first search in which walls cost 1 and everything else 0,
+    // so positions reached without passing a wall are expanded first
+    let mut queue = VecDeque::from([grid.start()]);
+    walls_passed.set(grid.start(), 0);
+    while let Some(pos) = queue.pop_front() {
+        let passed = *walls_passed.get(pos).unwrap();
+        for dir in Pos::STRAIGHT_DIRECTIONS {
+            let neighbor = pos + dir;
+            let Some(tile) = grid.tile_opt(neighbor) else {
+                continue;
+            };
+            let wall = !tile.is_walkable();
+            let neighbor_passed = passed + wall as u32;
+            if neighbor_passed >= *walls_passed.get(neighbor).unwrap() {
+                continue;
+            }
+
+            walls_passed.set(neighbor, neighbor_passed);
+            parents.set(neighbor, Some(pos));
+            if wall {
+                queue.push_back(neighbor);
+            } else {
+                queue.push_front(neighbor);
+            }
+        }
+    }
+
+    let targets = grid.waypoints().iter().copied().chain(grid.targets());
+    for target in targets.collect::<Vec<_>>() {
+        let mut pos = target;
+        while let Some(parent) = *parents.get(pos).unwrap() {
+            if !grid.walkable(pos) {
+                grid.set_tile(pos, Tile::None);
+            }
+            pos = parent;
+        }
+    }
+}
+
+/// Returns every position of the grid, row by row.
+pub(crate) fn positions(grid: &Grid) -> impl Iterator<Item = Pos> {
+    let width = grid.width();
+    (0..grid.height()).flat_map(move |y| (0..width).map(move |x| Pos { x, y }))
+}
+
+/// The cells of a maze, which lie at the even positions of the grid.
+/// Positions of cells count cells, not tiles.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub(crate) struct Cells {
+    pub columns: Unit,
+    pub rows: Unit,
+}
+
+impl Cells {
+    /// Creates a grid for a maze with the size, movement and wrapping of the template, filled with the tile.
+    /// The start, the end, the waypoints and the goals are moved onto the closest cell which no marker took before them,
+    /// and the tiles which don't belong to any cell are walls.
+    pub fn new_grid(template: &Grid, fill: Tile) -> (Grid, Self) {
+        let cells = Self {
+            columns: (template.width() + 1) / 2,
+            rows: (template.height() + 1) / 2,
+        };
+        let mut taken = Vec::new();
+        let mut snap = |pos: Pos| {
+            // ties go to the cell which comes first row by row, which is the one up and left of the position
+            let free = cells
+                .all()
+                .map(|cell| Pos {
+                    x: 2 * cell.x,
+                    y: 2 * cell.y,
+                })
+                .filter(|tile| !taken.contains(tile))
+                .min_by_key(|tile| (tile.x - pos.x).abs() + (tile.y - pos.y).abs());
+            // a maze with fewer cells than markers has to stack some of them
+            let snapped = free.unwrap_or(Pos {
+                x: pos.x - pos.x % 2,
+                y: pos.y - pos.y % 2,
+            });
+            taken.push(snapped);
+            snapped
+        };
+
+        let (start, end) = (snap(template.start()), snap(template.end()));
+        let mut grid = Grid::new(template.width(), template.height(), start, end);
+        grid.set_movement(template.movement());
+        grid.set_wraps(template.wraps());
+        for waypoint in template.waypoints() {
+            grid.add_waypoint(snap(*waypoint));
+        }
+        for goal in template.goals() {
+            grid.add_goal(snap(*goal));
+        }
+        for y in 0..grid.height() {
+            for x in 0..grid.width() {
+                let outside = x > 2 * (cells.columns - 1) || y > 2 * (cells.rows - 1);
+                grid.set_tile(Pos { x, y }, if outside { Tile::Wall } else { fill });
+            }
+        }
+        (grid, cells)
+    }
+
+    pub fn contains(&self, cell: Pos) -> bool {
+        cell.x >= 0 && cell.y >= 0 && cell.x < self.columns && cell.y < self.rows
+    }
+
+    pub fn count(&self) -> usize {
+        (self.columns * self.rows) as usize
+    }
+
+    pub fn all(&self) -> impl Iterator<Item = Pos> {
+        let columns = self.columns;
+        (0..self.rows).flat_map(move |y| (0..columns).map(move |x| Pos { x, y }))
+    }
+
+    /// Returns the index of the cell, counting row by row.
+    pub fn index(&self, cell: Pos) -> usize {
+        (cell.y * self.columns + cell.x) as usize
+    }
+
+    pub fn random(&self, rng: &mut Rng) -> Pos {
+        Pos {
+            x: rng.below(self.columns as usize) as Unit,
+            y: rng.below(self.rows as usize) as Unit,
+        }
+    }
+
+    /// Returns the cells above, below, left and right of the cell.
+    pub fn neighbors(&self, cell: Pos) -> Vec<Pos> {
+        Pos::STRAIGHT_DIRECTIONS
+            .iter()
+            .map(|dir| cell + *dir)
+            .filter(|neighbor| self.contains(*neighbor))
+            .collect()
+    }
+
+    /// Returns the tile of the cell.
+    pub fn tile(cell: Pos) -> Pos {
+        cell * 2
+    }
+
+    /// Opens the cell.
+    pub fn open(grid: &mut Grid, cell: Pos) {
+        grid.set_tile(Self::tile(cell), Tile::None);
+    }
+
+    /// Opens both neighboring cells and the passage between them.
+    pub fn carve(grid: &mut Grid, from: Pos, to: Pos) {
+        Self::open(grid, from);
+        Self::open(grid, to);
+        grid.set_tile(Self::tile(from) + (to - from), Tile::None);
+    }
+}
@@ -0,0 +1,105 @@
+//! Smooth two dimensional noise, which turns into organic looking terrain.
+
+use crate::generators::Rng;
+use std::f32::consts::{SQRT_2, TAU};
+
+/// A smooth random function over the plane, the same seed always gives the same values.
+pub trait Noise {
+    /// Returns the value at the point, between 0 and 1.
+    /// Points one unit apart are roughly independent, closer ones have similar values.
+    fn sample(&self, x: f32, y: f32) -> f32;
+
+    /// Adds the noise at several scales, each one twice as fine and half as strong as the one before,
+    /// which gives the large shapes rougher edges.
+    fn fractal(&self, x: f32, y: f32, octaves: u32) -> f32 {
+        let (mut sum, mut total, mut frequency, mut amplitude) = (0.0, 0.0, 1.0, 1.0);
+        for octave in 0..octaves {
+            // shift the octaves against each other, so their lattices don't line up
+            let offset = octave as f32 * 17.31;
+            sum += self.sample(x * frequency + offset, y * frequency + offset) * amplitude;
+            total += amplitude;
+            frequency *= 2.0;
+            amplitude *= 0.5;
+        }
+        sum / total
+    }
+}
+
+/// Interpolates random values at the points of an integer lattice.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ValueNoise {
+    seed: u64,
+}
+
+impl ValueNoise {
+    /// Creates the noise.
+    pub fn new(seed: u64) -> Self {
+        Self { seed }
+    }
+}
+
+impl Noise for ValueNoise {
+    fn sample(&self, x: f32, y: f32) -> f32 {
+        let (x0, y0) = (x.floor(), y.floor());
+        let (tx, ty) = (fade(x - x0), fade(y - y0));
+        let value = |dx: f32, dy: f32| lattice_rng(self.seed, x0 + dx, y0 + dy).next_f32();
+
+        lerp(
+            lerp(value(0.0, 0.0), value(1.0, 0.0), tx),
+            lerp(value(0.0, 1.0), value(1.0, 1.0), tx),
+            ty,
+        )
+    }
+}
+
+/// Perlin's gradient noise, which interpolates random slopes instead of values
+/// and looks less blocky than [ValueNoise].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct PerlinNoise {
+    seed: u64,
+}
+
+impl PerlinNoise {
+    /// Creates the noise.
+    pub fn new(seed: u64) -> Self {
+        Self { seed }
+    }
+}
+
+impl Noise for PerlinNoise {
+    fn sample(&self, x: f32, y: f32) -> f32 {
+        let (x0, y0) = (x.floor(), y.floor());
+        let (fx, fy) = (x - x0, y - y0);
+        let gradient = |dx: f32, dy: f32| {
+            let angle = lattice_rng(self.seed, x0 + dx, y0 + dy).next_f32() * TAU;
+            angle.cos() * (fx - dx) + angle.sin() * (fy - dy)
+        };
+
+        let (tx, ty) = (fade(fx), fade(fy));
+        let value = lerp(
+            lerp(gradient(0.0, 0.0), gradient(1.0, 0.0), tx),
+            lerp(gradient(0.0, 1.0), gradient(1.0, 1.0), tx),
+            ty,
+        );
+        // the values lie between -√2/2 and √2/2
+        (value / SQRT_2 + 0.5).clamp(0.0, 1.0)
+    }
+}
+
+/// Returns a generator seeded with the lattice point, so no table of random values has to be stored.
+fn lattice_rng(seed: u64, x: f32, y: f32) -> Rng {
+    let (x, y) = (x as i64 as u64, y as i64 as u64);
+    let mut rng =
+        Rng::new(seed ^ x.wrapping_mul(0x9e3779b97f4a7c15) ^ y.wrapping_mul(0xc2b2ae3d27d4eb4f));
+    rng.next_u64();
+    rng
+}
+
+/// Eases the interpolation in and out, so the noise has no visible seams at the lattice.
+fn fade(t: f32) -> f32 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+fn lerp(from: f32, to: f32, t: f32) -> f32 {
+    from + (to - from) * t
+}
@@ -0,0 +1,67 @@
+//! Randomized Prim's algorithm.
+
+use crate::generators::{Cells, MazeGenerator, Rng};
+use crate::{Grid, Pos, Tile, Vec2d};
+
+/// Grows the maze from a single cell by connecting a random cell at its border,
+/// which creates many short dead ends.
+#[derive(Clone, Debug)]
+pub struct Prim {
+    grid: Grid,
+    cells: Cells,
+    rng: Rng,
+    in_maze: Vec2d<bool>,
+    /// Cells next to the maze together with the cell of the maze they would be connected to.
+    frontier: Vec<(Pos, Pos)>,
+}
+
+impl Prim {
+    /// Creates the generator for a maze with the size, start, end and movement of the grid.
+    pub fn new(grid: &Grid, mut rng: Rng) -> Self {
+        let (grid, cells) = Cells::new_grid(grid, Tile::Wall);
+        let first = cells.random(&mut rng);
+
+        let mut prim = Self {
+            grid,
+            cells,
+            rng,
+            in_maze: Vec2d::new(cells.columns as usize, cells.rows as usize, false),
+            frontier: vec![],
+        };
+        prim.add(first);
+        prim
+    }
+
+    fn add(&mut self, cell: Pos) {
+        self.in_maze.set(cell, true);
+        Cells::open(&mut self.grid, cell);
+        for neighbor in self.cells.neighbors(cell) {
+            if self.in_maze.get(neighbor) == Some(&false) {
+                self.frontier.push((neighbor, cell));
+            }
+        }
+    }
+}
+
+impl MazeGenerator for Prim {
+    fn next_step(&mut self) -> bool {
+        while !self.frontier.is_empty() {
+            let (cell, from) = self
+                .frontier
+                .swap_remove(self.rng.below(self.frontier.len()));
+            // a cell can be in the frontier several times, once for every neighbor in the maze
+            if self.in_maze.get(cell) == Some(&true) {
+                continue;
+            }
+
+            Cells::carve(&mut self.grid, from, cell);
+            self.add(cell);
+            return false;
+        }
+        true
+    }
+
+    fn grid(&self) -> &Grid {
+        &self.grid
+    }
+}
@@ -0,0 +1,139 @@
+//! Organic maps made of random walls, caves or noise, as opposed to the perfect mazes of the other generators.
+//!
+//! ```
+//! use pathfinder_core::generators::terrain::{TerrainGenerators, TerrainOptions};
+//! use pathfinder_core::{Grid, Pos};
+//!
+//! let grid = Grid::new(40, 30, Pos { x: 0, y: 0 }, Pos { x: 39, y: 29 });
+//! let cave = TerrainGenerators::Cave.generate(&grid, TerrainOptions::default(), 7);
+//! assert_eq!(cave, TerrainGenerators::Cave.generate(&grid, TerrainOptions::default(), 7));
+//! ```
+
+use crate::generators::cave::CellularCave;
+use crate::generators::noise::{Noise, PerlinNoise, ValueNoise};
+use crate::generators::{connect, positions, Rng};
+use crate::{Grid, Tile};
+
+/// How the values of noise turn into tiles.
+#[derive(Copy, Clone, Debug, PartialEq, Default)]
+pub enum NoiseMapping {
+    /// The tiles with the lowest values become walls, the others stay empty.
+    #[default]
+    Walls,
+    /// The values become costs from 1 to [Tile::MAX_COST], like hills and valleys.
+    Costs,
+}
+
+/// The settings shared by the terrain generators.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TerrainOptions {
+    /// The share of walls, from 0 to 1. For noise it's only approximate.
+    pub density: f32,
+    /// The size of the hills and valleys of noise in tiles.
+    pub scale: f32,
+    /// How noise turns into tiles.
+    pub mapping: NoiseMapping,
+}
+
+impl Default for TerrainOptions {
+    fn default() -> Self {
+        Self {
+            density: 0.45,
+            scale: 8.0,
+            mapping: NoiseMapping::Walls,
+        }
+    }
+}
+
+/// All available terrain generators.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum TerrainGenerators {
+    /// Every tile is a wall with the probability of the density.
+    RandomFill,
+    /// [CellularCave] with the density as the initial share of walls.
+    Cave,
+    /// [ValueNoise]
+    ValueNoise,
+    /// [PerlinNoise]
+    PerlinNoise,
+}
+
+impl TerrainGenerators {
+    /// Every generator.
+    pub const ALL: [Self; 4] = [
+        Self::RandomFill,
+        Self::Cave,
+        Self::ValueNoise,
+        Self::PerlinNoise,
+    ];
+
+    /// Returns the identifier of the generator, which is used to select it in the UI and in files.
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::RandomFill => "random_fill",
+            Self::Cave => "cave",
+            Self::ValueNoise => "value_noise",
+            Self::PerlinNoise => "perlin_noise",
+        }
+    }
+
+    /// Returns the generator with the identifier, see [TerrainGenerators::id].
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|value| value.id() == id)
+    }
+
+    /// Creates terrain with the size, start, end, waypoints, goals, movement and wrapping of the grid.
+    /// Walls are opened afterwards where necessary, so every marker can be reached from the start, see [connect].
+    pub fn generate(&self, grid: &Grid, options: TerrainOptions, seed: u64) -> Grid {
+        let mut rng = Rng::new(seed);
+        let mut terrain = Grid::new(grid.width(), grid.height(), grid.start(), grid.end());
+        terrain.set_movement(grid.movement());
+        terrain.set_wraps(grid.wraps());
+        for waypoint in grid.waypoints() {
+            terrain.add_waypoint(*waypoint);
+        }
+        for goal in grid.goals() {
+            terrain.add_goal(*goal);
+        }
+
+        match self {
+            Self::RandomFill => {
+                for pos in positions(&terrain) {
+                    if rng.chance(options.density) {
+                        terrain.set_tile(pos, Tile::Wall);
+                    }
+                }
+            }
+            Self::Cave => CellularCave {
+                density: options.density,
+                ..CellularCave::default()
+            }
+            .generate(&mut terrain, &mut rng),
+            Self::ValueNoise => apply_noise(&mut terrain, &ValueNoise::new(seed), options),
+            Self::PerlinNoise => apply_noise(&mut terrain, &PerlinNoise::new(seed), options),
+        }
+
+        connect(&mut terrain);
+        terrain
+    }
+}
+
+fn apply_noise(grid: &mut Grid, noise: &impl Noise, options: TerrainOptions) {
+    let scale = options.scale.max(1.0);
+    for pos in positions(grid) {
+        let value = noise.fractal(pos.x as f32 / scale, pos.y as f32 / scale, 3);
+        let tile = match options.mapping {
+            NoiseMapping::Walls if value < options.density => Tile::Wall,
+            NoiseMapping::Walls => Tile::None,
+            NoiseMapping::Costs => {
+                // spread the values, which are mostly close to the middle, over all costs
+                let spread = ((value - 0.5) * 2.5 + 0.5).clamp(0.0, 1.0);
+                match (spread * Tile::MAX_COST as f32) as u8 + 1 {
+                    1 => Tile::None,
+                    cost => Tile::Cost(cost.min(Tile::MAX_COST)),
+                }
+            }
+        };
+        grid.set_tile(pos, tile);
+    }
+}
@@ -0,0 +1,81 @@
+//! Wilson's algorithm.
+
+use crate::generators::{Cells, MazeGenerator, Rng};
+use crate::{Grid, Pos, Tile, Vec2d};
+
+/// Adds loop-erased random walks from random cells until they hit the maze.
+/// It picks every possible maze with the same probability, so the mazes have no bias in any direction.
+/// Each step adds one walk.
+#[derive(Clone, Debug)]
+pub struct Wilson {
+    grid: Grid,
+    cells: Cells,
+    rng: Rng,
+    in_maze: Vec2d<bool>,
+    /// The cells the walks start at, in a random order.
+    remaining: Vec<Pos>,
+}
+
+impl Wilson {
+    /// Creates the generator for a maze with the size, start, end and movement of the grid.
+    pub fn new(grid: &Grid, mut rng: Rng) -> Self {
+        let (mut grid, cells) = Cells::new_grid(grid, Tile::Wall);
+        let mut in_maze = Vec2d::new(cells.columns as usize, cells.rows as usize, false);
+
+        let first = cells.random(&mut rng);
+        in_maze.set(first, true);
+        Cells::open(&mut grid, first);
+
+        let mut remaining = cells.all().collect::<Vec<_>>();
+        rng.shuffle(&mut remaining);
+        Self {
+            grid,
+            cells,
+            rng,
+            in_maze,
+            remaining,
+        }
+    }
+}
+
+impl MazeGenerator for Wilson {
+    fn next_step(&mut self) -> bool {
+        let start = loop {
+            match self.remaining.pop() {
+                Some(cell) if self.in_maze.get(cell) == Some(&false) => break cell,
+                Some(_) => {}
+                None => return true,
+            }
+        };
+
+        // walk randomly until the maze is hit, cutting off loops as soon as they appear
+        let mut walk = vec![start];
+        loop {
+            let cell = *walk.last().unwrap();
+            let neighbors = self.cells.neighbors(cell);
+            let next = *self
+                .rng
+                .choose(&neighbors)
+                .expect("the maze has more than one cell");
+
+            if let Some(i) = walk.iter().position(|visited| *visited == next) {
+                walk.truncate(i + 1);
+                continue;
+            }
+            walk.push(next);
+            if self.in_maze.get(next) == Some(&true) {
+                break;
+            }
+        }
+
+        for step in walk.windows(2) {
+            self.in_maze.set(step[0], true);
+            Cells::carve(&mut self.grid, step[0], step[1]);
+        }
+        false
+    }
+
+    fn grid(&self) -> &Grid {
+        &self.grid
+    }
+}
@@ -0,0 +1,30 @@
+//! Greedy best first search.
+
+use crate::best_first::{BestFirst, PosPrioritizer};
+use crate::heuristic::{GoalEstimate, HeuristicOptions};
+use crate::open_set::PriorityOpenSet;
+use crate::{Grid, Pos};
+
+/// Always expands the position which seems closest to the goal, ignoring how long the path to it already is.
+pub type GreedyBestFirst = BestFirst<GreedyBestFirstPrioritizer>;
+
+/// Orders the positions for [GreedyBestFirst].
+#[derive(Clone, Debug)]
+pub struct GreedyBestFirstPrioritizer {
+    estimate: GoalEstimate,
+}
+
+impl PosPrioritizer for GreedyBestFirstPrioritizer {
+    type OpenSet = PriorityOpenSet;
+    const RELAX_EDGES: bool = false;
+
+    fn new_prioritizer(grid: &Grid, goals: &[Pos], heuristic: HeuristicOptions) -> Self {
+        Self {
+            estimate: GoalEstimate::new(grid, goals, heuristic),
+        }
+    }
+
+    fn priority(&self, pos: Pos, _distance: f32) -> f32 {
+        self.estimate.estimate(pos)
+    }
+}
@@ -0,0 +1,334 @@
+//! Estimates of the remaining path cost used to guide searches towards the goal.
+
+use crate::{Grid, Movement, Pos, Unit};
+use std::f32::consts::SQRT_2;
+
+/// Estimates the cost of the cheapest path between two positions.
+pub trait Heuristic {
+    /// Returns the estimated cost of moving from one position to the other.
+    fn estimate(&self, from: Pos, to: Pos) -> f32;
+
+    /// Returns the heuristic which should be used on grids with the movement.
+    /// Only [Heuristics::Auto] depends on the movement, every other heuristic stays the same.
+    fn for_movement(self, _movement: Movement) -> Self
+    where
+        Self: Sized,
+    {
+        self
+    }
+
+    /// Returns the estimated cost of moving from the position to the closest of the goals.
+    /// Taking the lowest estimate keeps the heuristic from overestimating if the estimate for every single goal doesn't.
+    fn estimate_nearest(&self, from: Pos, goals: &[Pos]) -> f32 {
+        goals
+            .iter()
+            .map(|goal| self.estimate(from, *goal))
+            .fold(f32::INFINITY, f32::min)
+    }
+}
+
+/// The sum of the horizontal and vertical distance, exact for four way movement.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Manhattan;
+
+/// The straight line distance.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Euclidean;
+
+/// The longer one of the horizontal and vertical distance, exact for diagonal moves costing 1.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Chebyshev;
+
+/// Exact for diagonal moves costing √2.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Octile;
+
+/// The number of steps between two tiles of a hex grid, see [Movement::Hex].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Hex;
+
+/// Always guesses 0, turning A* into Dijkstra.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Zero;
+
+fn diff(from: Pos, to: Pos) -> (f32, f32) {
+    let x_diff = (to.x - from.x).abs() as f32;
+    let y_diff = (to.y - from.y).abs() as f32;
+    (x_diff, y_diff)
+}
+
+impl Heuristic for Manhattan {
+    fn estimate(&self, from: Pos, to: Pos) -> f32 {
+        let (x_diff, y_diff) = diff(from, to);
+        x_diff + y_diff
+    }
+}
+
+impl Heuristic for Euclidean {
+    fn estimate(&self, from: Pos, to: Pos) -> f32 {
+        let (x_diff, y_diff) = diff(from, to);
+        x_diff.hypot(y_diff)
+    }
+}
+
+impl Heuristic for Chebyshev {
+    fn estimate(&self, from: Pos, to: Pos) -> f32 {
+        let (x_diff, y_diff) = diff(from, to);
+        x_diff.max(y_diff)
+    }
+}
+
+impl Heuristic for Octile {
+    fn estimate(&self, from: Pos, to: Pos) -> f32 {
+        let (x_diff, y_diff) = diff(from, to);
+        let (short, long) = (x_diff.min(y_diff), x_diff.max(y_diff));
+        long + (SQRT_2 - 1.0) * short
+    }
+}
+
+impl Heuristic for Hex {
+    fn estimate(&self, from: Pos, to: Pos) -> f32 {
+        // in axial coordinates the column doesn't change along the diagonals going down to the right
+        let axial = |pos: Pos| (pos.x - pos.y.div_euclid(2), pos.y);
+        let (from_q, from_r) = axial(from);
+        let (to_q, to_r) = axial(to);
+        let (q_diff, r_diff) = (to_q - from_q, to_r - from_r);
+        ((q_diff.abs() + r_diff.abs() + (q_diff + r_diff).abs()) / 2) as f32
+    }
+}
+
+impl Heuristic for Zero {
+    fn estimate(&self, _from: Pos, _to: Pos) -> f32 {
+        0.0
+    }
+}
+
+/// The heuristics which can be selected for a search.
+#[derive(Copy, Clone, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Heuristics {
+    /// Picks the heuristic that fits the movement of the grid best without ever overestimating.
+    #[default]
+    Auto,
+    /// [Manhattan]
+    Manhattan,
+    /// [Euclidean]
+    Euclidean,
+    /// [Chebyshev]
+    Chebyshev,
+    /// [Octile]
+    Octile,
+    /// [Hex]
+    Hex,
+    /// [Zero]
+    Zero,
+}
+
+impl Heuristics {
+    /// Every heuristic.
+    pub const ALL: [Self; 7] = [
+        Self::Auto,
+        Self::Manhattan,
+        Self::Euclidean,
+        Self::Chebyshev,
+        Self::Octile,
+        Self::Hex,
+        Self::Zero,
+    ];
+
+    /// Returns the identifier of the heuristic, which is used to select it in the UI and in files.
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::Auto => "auto",
+            Self::Manhattan => "manhattan",
+            Self::Euclidean => "euclidean",
+            Self::Chebyshev => "chebyshev",
+            Self::Octile => "octile",
+            Self::Hex => "hex",
+            Self::Zero => "zero",
+        }
+    }
+
+    /// Returns the heuristic with the identifier, see [Heuristics::id].
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|value| value.id() == id)
+    }
+
+    /// Replaces [Heuristics::Auto] with the best heuristic for the movement.
+    pub fn resolve(&self, movement: Movement) -> Self {
+        match self {
+            Self::Auto if movement.is_hex() => Self::Hex,
+            Self::Auto if movement.is_diagonal() => Self::Octile,
+            Self::Auto => Self::Manhattan,
+            heuristic => *heuristic,
+        }
+    }
+}
+
+/// [Heuristics::Auto] estimates with [Chebyshev] until it's [resolved](Heuristics::resolve),
+/// which never overestimates for any movement but guesses lower than the heuristic fitting the movement.
+impl Heuristic for Heuristics {
+    fn estimate(&self, from: Pos, to: Pos) -> f32 {
+        match self {
+            Self::Auto => Chebyshev.estimate(from, to),
+            Self::Manhattan => Manhattan.estimate(from, to),
+            Self::Euclidean => Euclidean.estimate(from, to),
+            Self::Chebyshev => Chebyshev.estimate(from, to),
+            Self::Octile => Octile.estimate(from, to),
+            Self::Hex => Hex.estimate(from, to),
+            Self::Zero => Zero.estimate(from, to),
+        }
+    }
+
+    fn for_movement(self, movement: Movement) -> Self {
+        self.resolve(movement)
+    }
+}
+
+/// The heuristic a search uses, multiplied by a weight.
+/// Weights above 1 make weighted A* expand fewer tiles, but the path might not be the shortest anymore.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HeuristicOptions {
+    /// The heuristic which estimates the remaining cost.
+    pub heuristic: Heuristics,
+    /// The factor the estimate is multiplied with.
+    pub weight: f32,
+}
+
+impl Default for HeuristicOptions {
+    fn default() -> Self {
+        Self {
+            heuristic: Heuristics::Auto,
+            weight: 1.0,
+        }
+    }
+}
+
+impl HeuristicOptions {
+    /// Replaces [Heuristics::Auto] with the best heuristic for the movement.
+    pub fn resolve(&self, movement: Movement) -> Self {
+        Self {
+            heuristic: self.heuristic.resolve(movement),
+            weight: self.weight,
+        }
+    }
+}
+
+impl Heuristic for HeuristicOptions {
+    fn estimate(&self, from: Pos, to: Pos) -> f32 {
+        self.heuristic.estimate(from, to) * self.weight
+    }
+
+    fn for_movement(self, movement: Movement) -> Self {
+        self.resolve(movement)
+    }
+}
+
+/// Estimates the cost from a position to the closest of several goals on a grid, including the shortcuts through portals
+/// and over the edges of wrapping grids.
+///
+/// Going through a portal can be cheaper than walking, so the estimate is the lowest one of going straight to a goal
+/// and of going to a paired portal first and estimating the rest of the way from its partner in the same way.
+/// On wrapping grids the distances are the lowest ones to the copies of the target in the copies of the grid
+/// around it, as if the grid was repeated in every direction.
+/// If the heuristic never overestimates, neither does this estimate.
+///
+/// Any [Heuristic] can be plugged in, the searches use the one selected in their [HeuristicOptions].
+///
+/// ```
+/// use pathfinder_core::heuristic::{Chebyshev, GoalEstimate};
+/// use pathfinder_core::{Grid, Pos};
+///
+/// let mut grid = Grid::new(10, 10, Pos { x: 0, y: 0 }, Pos { x: 9, y: 9 });
+/// assert_eq!(GoalEstimate::new(&grid, &[grid.end()], Chebyshev).estimate(grid.start()), 9.0);
+///
+/// // over the edges of a wrapping grid the end is right next to the start
+/// grid.set_wraps(true);
+/// assert_eq!(GoalEstimate::new(&grid, &[grid.end()], Chebyshev).estimate(grid.start()), 1.0);
+/// ```
+#[derive(Clone, Debug)]
+pub struct GoalEstimate<H = HeuristicOptions> {
+    heuristic: H,
+    /// The offsets of the copies of the grid whose targets might be closer than the ones of the grid itself
+    copies: Vec<Pos>,
+    goals: Vec<Pos>,
+    /// The paired portals together with the lowest estimate from their partner to a goal
+    portals: Vec<(Pos, f32)>,
+}
+
+impl<H> GoalEstimate<H>
+where
+    H: Heuristic,
+{
+    /// Creates the estimate for the heuristic, see [Heuristic::for_movement].
+    pub fn new(grid: &Grid, goals: &[Pos], heuristic: H) -> Self {
+        let heuristic = heuristic.for_movement(grid.movement());
+        let partners = grid
+            .portals()
+            .iter()
+            .filter_map(|portal| Some((*portal, grid.portal_partner(*portal)?)))
+            .collect::<Vec<_>>();
+        let (horizontally, vertically) = grid.wrapping_axes();
+        let offsets = |wraps: bool, size: Unit| if wraps { vec![-size, 0, size] } else { vec![0] };
+        let copies = offsets(horizontally, grid.width())
+            .into_iter()
+            .flat_map(|x| {
+                offsets(vertically, grid.height())
+                    .into_iter()
+                    .map(move |y| Pos { x, y })
+            })
+            .collect();
+
+        let mut estimate = Self {
+            heuristic,
+            copies,
+            goals: goals.to_vec(),
+            portals: vec![],
+        };
+        estimate.portals = partners
+            .iter()
+            .map(|(portal, partner)| (*portal, estimate.estimate_goals(*partner)))
+            .collect();
+
+        // the way from a partner might lead through more portals, which can only lower the estimates
+        loop {
+            let mut lowered = false;
+            for (i, (_, partner)) in partners.iter().enumerate() {
+                let through = estimate.estimate(*partner);
+                if through < estimate.portals[i].1 {
+                    estimate.portals[i].1 = through;
+                    lowered = true;
+                }
+            }
+            if !lowered {
+                return estimate;
+            }
+        }
+    }
+
+    /// Returns the estimated cost of moving from the position to the closest goal.
+    pub fn estimate(&self, pos: Pos) -> f32 {
+        self.portals
+            .iter()
+            .map(|(portal, through)| self.distance(pos, *portal) + through)
+            .fold(self.estimate_goals(pos), f32::min)
+    }
+
+    /// Returns the estimated cost of walking from the position to the closest goal without going through portals.
+    fn estimate_goals(&self, pos: Pos) -> f32 {
+        self.goals
+            .iter()
+            .map(|goal| self.distance(pos, *goal))
+            .fold(f32::INFINITY, f32::min)
+    }
+
+    /// Returns the estimate to the closest copy of the target.
+    fn distance(&self, from: Pos, to: Pos) -> f32 {
+        self.copies
+            .iter()
+            .map(|offset| self.heuristic.estimate(from, to + *offset))
+            .fold(f32::INFINITY, f32::min)
+    }
+}
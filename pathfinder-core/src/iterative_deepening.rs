@@ -0,0 +1,230 @@
+//! Depth first searches which are repeated with a growing limit.
+
+use crate::heuristic::{GoalEstimate, HeuristicOptions};
+use crate::PathFindAlgorithmStepResult::{InProgress, NotFound};
+use crate::{
+    Grid, PathFindAlgorithm, PathFindAlgorithmConstructor, PathFindAlgorithmStepResult, Pos, Vec2d,
+};
+
+/// Iterative deepening A*, which limits the sum of path cost and estimated remaining cost.
+pub type IdaStar = IterativeDeepening<IdaStarBound>;
+/// Iterative deepening depth first search, which limits the number of steps.
+pub type IterativeDeepeningDepthFirst = IterativeDeepening<DepthBound>;
+
+/// Decides how far an iteration of an [IterativeDeepening] search may go.
+pub trait DeepeningBound {
+    /// Creates the bound for a search on the grid.
+    fn new_bound(grid: &Grid, heuristic: HeuristicOptions) -> Self;
+    /// Returns the value which is compared against the threshold of the current iteration.
+    /// The distance is the cost of the path to the position and the depth the amount of steps on it.
+    fn bound(&self, pos: Pos, distance: f32, depth: usize) -> f32;
+}
+
+/// Limits the search by the estimated cost of the whole path, like A* does.
+#[derive(Clone, Debug)]
+pub struct IdaStarBound {
+    estimate: GoalEstimate,
+}
+
+impl DeepeningBound for IdaStarBound {
+    fn new_bound(grid: &Grid, heuristic: HeuristicOptions) -> Self {
+        Self {
+            estimate: GoalEstimate::new(grid, &[grid.end()], heuristic),
+        }
+    }
+
+    fn bound(&self, pos: Pos, distance: f32, _depth: usize) -> f32 {
+        distance + self.estimate.estimate(pos)
+    }
+}
+
+/// Limits the search by the amount of steps, ignoring the cost of the tiles.
+#[derive(Clone, Debug)]
+pub struct DepthBound;
+
+impl DeepeningBound for DepthBound {
+    fn new_bound(_grid: &Grid, _heuristic: HeuristicOptions) -> Self {
+        Self
+    }
+
+    fn bound(&self, _pos: Pos, _distance: f32, depth: usize) -> f32 {
+        depth as f32
+    }
+}
+
+/// The iteration an iterative deepening search is in.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct DeepeningProgress {
+    /// Starts with 1 and goes up every time the search restarts
+    pub iteration: usize,
+    /// The highest bound a position may have to be expanded in this iteration
+    pub threshold: f32,
+}
+
+/// A depth first search which only follows paths up to a threshold.
+/// If the end isn't found, the search restarts with the threshold raised to the lowest bound that exceeded it.
+///
+/// Only the current path is kept in memory, so each iteration only remembers the lowest bound
+/// every position was reached with and doesn't follow worse paths to it again.
+#[derive(Clone, Debug)]
+pub struct IterativeDeepening<B> {
+    grid: Grid,
+    bound: B,
+    stack: Vec<Frame>,
+    lowest_bounds: Vec2d<f32>,
+    visited: Vec<Pos>,
+    seen: Vec2d<bool>,
+    /// The expansions of all iterations, unlike the visited list which starts over with every iteration
+    expanded: usize,
+    progress: DeepeningProgress,
+    next_threshold: f32,
+}
+
+/// A position on the current path together with the neighbors which haven't been tried yet.
+#[derive(Clone, Debug)]
+struct Frame {
+    pos: Pos,
+    distance: f32,
+    neighbors: Vec<(Pos, f32)>,
+}
+
+impl<B> IterativeDeepening<B>
+where
+    B: DeepeningBound,
+{
+    fn start_iteration(&mut self) {
+        let (width, height) = (self.grid.width() as usize, self.grid.height() as usize);
+        self.stack.clear();
+        self.lowest_bounds = Vec2d::new(width, height, f32::INFINITY);
+        self.visited.clear();
+        self.seen = Vec2d::new(width, height, false);
+        self.next_threshold = f32::INFINITY;
+
+        let start = self.grid.start();
+        let bound = self.bound.bound(start, 0.0, 0);
+        self.enter(start, 0.0, bound);
+    }
+
+    fn enter(&mut self, pos: Pos, distance: f32, bound: f32) {
+        self.lowest_bounds.set(pos, bound);
+        self.expanded += 1;
+        if let Some(false) = self.seen.get(pos) {
+            self.seen.set(pos, true);
+            self.visited.push(pos);
+        }
+
+        // reversed, so the neighbors are tried in the order the grid returns them
+        let mut neighbors = self.grid.neighbors(pos);
+        neighbors.reverse();
+        self.stack.push(Frame {
+            pos,
+            distance,
+            neighbors,
+        });
+    }
+
+    fn path(&self) -> Vec<Pos> {
+        self.stack.iter().map(|frame| frame.pos).collect()
+    }
+}
+
+impl<B> PathFindAlgorithmConstructor for IterativeDeepening<B>
+where
+    B: DeepeningBound,
+{
+    fn make_state(grid: Grid, heuristic: HeuristicOptions) -> Self {
+        let (width, height) = (grid.width() as usize, grid.height() as usize);
+        let bound = B::new_bound(&grid, heuristic);
+        let threshold = bound.bound(grid.start(), 0.0, 0);
+
+        let mut state = Self {
+            grid,
+            bound,
+            stack: vec![],
+            lowest_bounds: Vec2d::new(width, height, f32::INFINITY),
+            visited: vec![],
+            seen: Vec2d::new(width, height, false),
+            expanded: 0,
+            progress: DeepeningProgress {
+                iteration: 1,
+                threshold,
+            },
+            next_threshold: f32::INFINITY,
+        };
+        state.start_iteration();
+        state
+    }
+}
+
+impl<B> PathFindAlgorithm for IterativeDeepening<B>
+where
+    B: DeepeningBound,
+{
+    fn next_step(&mut self) -> Result<Vec<Pos>, PathFindAlgorithmStepResult> {
+        if self.grid.start() == self.grid.end() {
+            return Ok(self.path());
+        }
+
+        // the iteration is over, so restart with a higher threshold
+        if self.stack.is_empty() {
+            if self.next_threshold.is_infinite() {
+                return Err(NotFound);
+            }
+            self.progress = DeepeningProgress {
+                iteration: self.progress.iteration + 1,
+                threshold: self.next_threshold,
+            };
+            self.start_iteration();
+            return Err(InProgress);
+        }
+
+        // backtrack until a neighbor can be expanded
+        while let Some(frame) = self.stack.last_mut() {
+            let (neighbor, cost) = match frame.neighbors.pop() {
+                Some(neighbor) => neighbor,
+                None => {
+                    self.stack.pop();
+                    continue;
+                }
+            };
+            let distance = frame.distance + cost;
+            let bound = self.bound.bound(neighbor, distance, self.stack.len());
+
+            if bound > self.progress.threshold {
+                self.next_threshold = self.next_threshold.min(bound);
+                continue;
+            }
+            if bound >= *self.lowest_bounds.get(neighbor).unwrap() {
+                continue;
+            }
+
+            self.enter(neighbor, distance, bound);
+            if neighbor == self.grid.end() {
+                return Ok(self.path());
+            }
+            return Err(InProgress);
+        }
+
+        Err(InProgress)
+    }
+
+    fn visited(&self, pos: Pos) -> bool {
+        matches!(self.seen.get(pos), Some(true))
+    }
+
+    fn visited_list(&self) -> &[Pos] {
+        &self.visited
+    }
+
+    fn expanded(&self) -> usize {
+        self.expanded
+    }
+
+    fn in_queue(&self, pos: Pos) -> bool {
+        self.stack.iter().any(|frame| frame.pos == pos)
+    }
+
+    fn deepening_progress(&self) -> Option<DeepeningProgress> {
+        Some(self.progress)
+    }
+}
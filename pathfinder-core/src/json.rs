@@ -0,0 +1,212 @@
+//! A json format for grids and recorded searches, which is shared by the app, the command line tool and other tools.
+//! It's only available with the `serde` feature.
+//!
+//! A file is a [Document], which looks like this:
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "grid": {
+//!     "tiles": { "width": 3, "height": 1, "values": ["none", "wall", { "cost": 5 }] },
+//!     "start": { "x": 0, "y": 0 },
+//!     "end": { "x": 2, "y": 0 },
+//!     "movement": "four_way"
+//!   },
+//!   "runs": [
+//!     {
+//!       "algorithm": "a_star",
+//!       "heuristic": { "heuristic": "auto", "weight": 1.0 },
+//!       "result": { "path": null, "visited": [{ "x": 0, "y": 0 }], "cost": null, "steps": 2 }
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! - `version` is [VERSION]. It's raised whenever the format changes in a way older readers can't handle.
+//! - `values` holds the tiles row by row. Tiles are `"none"`, `"wall"`, `{ "cost": <1 to 255> }`,
+//!   `{ "portal": <0 to 255> }` or `{ "one_way": <direction> }` with the identifiers of [Direction::id](crate::Direction::id).
+//! - `waypoints` and `goals` are lists of positions like `start`, which are only written if they aren't empty,
+//!   see [Grid::waypoints] and [Grid::goals].
+//! - `wraps` is only written as `true` for grids which wrap around their edges, see [Grid::set_wraps].
+//! - `movement` and `algorithm` use the identifiers of [Movement::id](crate::Movement::id)
+//!   and [PathFindAlgorithms::id], `heuristic` the ones of [Heuristics::id](crate::heuristic::Heuristics::id).
+//! - `runs` may be left out, the fields of a run are the ones of [SearchResult].
+//!   `visited_from_end` is only written for bidirectional searches.
+//!
+//! ```
+//! use pathfinder_core::json::Document;
+//! use pathfinder_core::Grid;
+//!
+//! let grid = "S.#\n..E".parse::<Grid>().unwrap();
+//! let json = Document::new(grid.clone()).to_json();
+//! assert_eq!(Document::from_json(&json).unwrap().grid, grid);
+//! ```
+
+use crate::heuristic::HeuristicOptions;
+use crate::search_result::SearchResult;
+use crate::{Grid, Movement, PathFindAlgorithms, Pos, Tile, Unit, Vec2d};
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+
+/// The version of the format written by [Document::to_json].
+pub const VERSION: u32 = 1;
+
+/// Why a json document couldn't be read.
+#[derive(Clone, Debug, PartialEq)]
+pub enum JsonError {
+    /// The text isn't json or doesn't match the format.
+    Invalid(String),
+    /// The document was written with a version this library doesn't know.
+    UnsupportedVersion(u32),
+}
+
+impl Display for JsonError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JsonError::Invalid(error) => write!(f, "invalid document: {}", error),
+            JsonError::UnsupportedVersion(version) => {
+                write!(f, "the document has the unsupported version {}", version)
+            }
+        }
+    }
+}
+
+impl std::error::Error for JsonError {}
+
+/// A grid together with searches which were run on it.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Document {
+    /// The version of the format, see [VERSION].
+    pub version: u32,
+    /// The grid.
+    pub grid: Grid,
+    /// The searches which were run on the grid.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub runs: Vec<RecordedRun>,
+}
+
+/// One search which was run on the grid of a [Document].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RecordedRun {
+    /// The algorithm of the search.
+    pub algorithm: PathFindAlgorithms,
+    /// The heuristic the search was made with, algorithms without a heuristic ignore it.
+    pub heuristic: HeuristicOptions,
+    /// What the search found.
+    pub result: SearchResult,
+}
+
+impl Document {
+    /// Creates a document with the grid and without any runs.
+    pub fn new(grid: Grid) -> Self {
+        Self {
+            version: VERSION,
+            grid,
+            runs: Vec::new(),
+        }
+    }
+
+    /// Reads a document, checking its version before anything else.
+    pub fn from_json(json: &str) -> Result<Self, JsonError> {
+        let value = serde_json::from_str::<serde_json::Value>(json)
+            .map_err(|error| JsonError::Invalid(error.to_string()))?;
+        let version = value
+            .get("version")
+            .and_then(serde_json::Value::as_u64)
+            .ok_or_else(|| JsonError::Invalid("missing version".to_string()))?;
+        if version != VERSION as u64 {
+            return Err(JsonError::UnsupportedVersion(
+                version.try_into().unwrap_or(u32::MAX),
+            ));
+        }
+
+        serde_json::from_value(value).map_err(|error| JsonError::Invalid(error.to_string()))
+    }
+
+    /// Writes the document as indented json.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("documents can always be written as json")
+    }
+}
+
+/// The fields of a [Vec2d], which are checked before the array is created.
+#[derive(Deserialize)]
+pub(crate) struct Vec2dData<T> {
+    width: usize,
+    height: usize,
+    values: Vec<T>,
+}
+
+impl<T> TryFrom<Vec2dData<T>> for Vec2d<T> {
+    type Error = String;
+
+    fn try_from(data: Vec2dData<T>) -> Result<Self, Self::Error> {
+        if data.width.checked_mul(data.height) != Some(data.values.len()) {
+            return Err(format!(
+                "{} values don't fit a {}x{} array",
+                data.values.len(),
+                data.width,
+                data.height
+            ));
+        }
+        Ok(Self {
+            width: data.width,
+            height: data.height,
+            flattened: data.values,
+        })
+    }
+}
+
+/// The fields of a [Grid], which are checked before the grid is created.
+#[derive(Deserialize)]
+pub(crate) struct GridData {
+    tiles: Vec2d<Tile>,
+    start: Pos,
+    end: Pos,
+    #[serde(default)]
+    waypoints: Vec<Pos>,
+    #[serde(default)]
+    goals: Vec<Pos>,
+    #[serde(default)]
+    movement: Movement,
+    #[serde(default)]
+    wraps: bool,
+}
+
+impl TryFrom<GridData> for Grid {
+    type Error = String;
+
+    fn try_from(data: GridData) -> Result<Self, Self::Error> {
+        for pos in [data.start, data.end] {
+            if data.tiles.get(pos).is_none() {
+                return Err(format!(
+                    "the start or end ({}, {}) lies outside of the grid",
+                    pos.x, pos.y
+                ));
+            }
+        }
+        for pos in data.waypoints.iter().chain(&data.goals) {
+            if data.tiles.get(*pos).is_none() {
+                return Err(format!(
+                    "the waypoint or goal ({}, {}) lies outside of the grid",
+                    pos.x, pos.y
+                ));
+            }
+        }
+        let (width, height) = (data.tiles.width() as Unit, data.tiles.height() as Unit);
+        let portals = (0..height)
+            .flat_map(|y| (0..width).map(move |x| Pos { x, y }))
+            .filter(|pos| matches!(data.tiles.get(*pos), Some(Tile::Portal(_))))
+            .collect();
+        Ok(Self {
+            tiles: data.tiles,
+            start: data.start,
+            end: data.end,
+            waypoints: data.waypoints,
+            goals: data.goals,
+            movement: data.movement,
+            wraps: data.wraps,
+            portals,
+        })
+    }
+}
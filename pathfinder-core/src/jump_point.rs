@@ -0,0 +1,340 @@
+//! Jump Point Search and its preprocessed variant JPS+.
+
+use crate::a_star::AStarPrioritizer;
+use crate::best_first::SearchFrontier;
+use crate::heuristic::{Heuristic, HeuristicOptions, Octile};
+use crate::PathFindAlgorithmStepResult::{InProgress, NotFound};
+use crate::{
+    Grid, Movement, PathFindAlgorithm, PathFindAlgorithmConstructor, PathFindAlgorithmStepResult,
+    Pos, Unit, Vec2d,
+};
+
+/// Jump Point Search, an A* which skips over the tiles between jump points on uniform-cost grids.
+///
+/// It always moves in eight directions without cutting corners, treats weighted tiles like normal tiles
+/// and doesn't wrap around the edges of the grid, as its jumps would never end on wrapping grids.
+/// Portals and one-way tiles break the symmetry the jumps rely on, so the tiles around them are jump points
+/// from which every direction is searched, and going through a portal is a jump of its own.
+/// The visited list contains the expanded jump points and all tiles scanned while looking for them,
+/// but only the jump points count as expanded.
+///
+/// With preprocessing (JPS+) the distances to the next jump point are calculated for every tile and direction
+/// when the search is created, so the search itself doesn't have to scan the grid anymore.
+/// The visited list then only contains the tiles between the expanded jump points.
+/// Its jumps only know the end, so [route::NearestGoalSearch](crate::route::NearestGoalSearch) runs it on grids with goals.
+#[derive(Clone, Debug)]
+pub struct JumpPointSearch {
+    grid: Grid,
+    frontier: SearchFrontier<AStarPrioritizer>,
+    visited: Vec<Pos>,
+    seen: Vec2d<bool>,
+    jump_distances: Option<Vec2d<[JumpDistance; 8]>>,
+}
+
+/// How far a straight or diagonal jump from a tile can go.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+struct JumpDistance {
+    /// The amount of steps until the jump point or until the last tile before a wall
+    steps: Unit,
+    /// Whether the jump ends at a jump point or at a wall
+    jump_point: bool,
+}
+
+impl JumpPointSearch {
+    /// Creates a JPS+ search, which preprocesses the grid before searching.
+    pub fn with_preprocessing(grid: Grid, heuristic: HeuristicOptions) -> Self {
+        let mut state = Self::make_state(grid, heuristic);
+        state.jump_distances = Some(state.calculate_jump_distances());
+        state
+    }
+
+    fn scan(&mut self, pos: Pos) {
+        if let Some(false) = self.seen.get(pos) {
+            self.seen.set(pos, true);
+            self.visited.push(pos);
+        }
+    }
+
+    fn can_move(&self, pos: Pos, dir: Pos) -> bool {
+        self.grid.step_cost(pos, pos + dir).is_some()
+    }
+
+    fn has_forced_neighbor(&self, pos: Pos, dir: Pos) -> bool {
+        // a wall behind a free side of a straight move forces a turn
+        self.near_changed_moves(pos)
+            || sides(dir).iter().any(|side| {
+                self.grid.walkable(pos + *side) && !self.grid.walkable(pos + *side - dir)
+            })
+    }
+
+    /// Returns whether the position or one around it changes the moves, see [Tile::changes_moves](crate::Tile::changes_moves).
+    fn near_changed_moves(&self, pos: Pos) -> bool {
+        std::iter::once(pos)
+            .chain(Pos::ALL_DIRECTIONS.iter().map(|dir| pos + *dir))
+            .any(|pos| matches!(self.grid.tile_opt(pos), Some(tile) if tile.changes_moves()))
+    }
+
+    /// Moves from the position in the direction until a jump point is found.
+    fn jump(&mut self, from: Pos, dir: Pos) -> Option<Pos> {
+        let is_diagonal = dir.x != 0 && dir.y != 0;
+        let mut pos = from;
+
+        loop {
+            if !self.can_move(pos, dir) {
+                return None;
+            }
+            pos = pos + dir;
+            self.scan(pos);
+
+            if self.grid.is_goal(pos) || self.near_changed_moves(pos) {
+                return Some(pos);
+            }
+            if is_diagonal {
+                let horizontal = Pos { x: dir.x, y: 0 };
+                let vertical = Pos { x: 0, y: dir.y };
+                if self.jump(pos, horizontal).is_some() || self.jump(pos, vertical).is_some() {
+                    return Some(pos);
+                }
+            } else if self.has_forced_neighbor(pos, dir) {
+                return Some(pos);
+            }
+        }
+    }
+
+    /// Returns the directions which have to be searched from a jump point.
+    fn pruned_directions(&self, pos: Pos) -> Vec<Pos> {
+        let parent = match self.frontier.parent(pos) {
+            Some(parent) if !self.near_changed_moves(pos) => parent,
+            _ => {
+                return Pos::ALL_DIRECTIONS
+                    .into_iter()
+                    .filter(|dir| self.can_move(pos, *dir))
+                    .collect()
+            }
+        };
+        let dir = (pos - parent).signum();
+
+        let mut directions = Vec::with_capacity(5);
+        if dir.x != 0 && dir.y != 0 {
+            directions.push(Pos { x: dir.x, y: 0 });
+            directions.push(Pos { x: 0, y: dir.y });
+            directions.push(dir);
+        } else {
+            directions.push(dir);
+            for side in sides(dir) {
+                if self.grid.walkable(pos + side) {
+                    directions.push(side);
+                    directions.push(dir + side);
+                }
+            }
+        }
+
+        directions
+            .into_iter()
+            .filter(|dir| self.can_move(pos, *dir))
+            .collect()
+    }
+
+    fn successor(&mut self, pos: Pos, dir: Pos) -> Option<Pos> {
+        match &self.jump_distances {
+            None => self.jump(pos, dir),
+            Some(jump_distances) => {
+                let distance = jump_distances.get(pos)?[direction_index(dir)];
+                let successor = self.preprocessed_successor(pos, dir, distance)?;
+
+                let steps = (successor.x - pos.x).abs().max((successor.y - pos.y).abs());
+                for step in 1..=steps {
+                    self.scan(pos + dir * step);
+                }
+                Some(successor)
+            }
+        }
+    }
+
+    fn preprocessed_successor(&self, pos: Pos, dir: Pos, distance: JumpDistance) -> Option<Pos> {
+        let end = self.grid.end();
+        let to_end = end - pos;
+
+        if dir.x != 0 && dir.y != 0 {
+            // if the end is in the direction, stop on its row or column so a straight jump can reach it
+            if to_end.signum() == dir {
+                let steps = to_end.x.abs().min(to_end.y.abs());
+                if steps <= distance.steps {
+                    return Some(pos + dir * steps);
+                }
+            }
+        } else {
+            let steps = to_end.x.abs().max(to_end.y.abs());
+            if to_end.signum() == dir && steps <= distance.steps {
+                return Some(end);
+            }
+        }
+
+        if distance.jump_point {
+            Some(pos + dir * distance.steps)
+        } else {
+            None
+        }
+    }
+
+    fn calculate_jump_distances(&self) -> Vec2d<[JumpDistance; 8]> {
+        let mut distances = Vec2d::new(
+            self.grid.width() as usize,
+            self.grid.height() as usize,
+            [JumpDistance::default(); 8],
+        );
+        let positions = (0..self.grid.height())
+            .flat_map(|y| (0..self.grid.width()).map(move |x| Pos { x, y }))
+            .filter(|pos| self.grid.walkable(*pos))
+            .collect::<Vec<_>>();
+
+        // straight jumps first, because diagonal jumps end where a straight jump finds a jump point
+        for pos in &positions {
+            let mut tile_distances = *distances.get(*pos).unwrap();
+            for dir in Pos::STRAIGHT_DIRECTIONS {
+                let mut current = *pos;
+                let mut steps = 0;
+                let jump_point = loop {
+                    if !self.can_move(current, dir) {
+                        break false;
+                    }
+                    current = current + dir;
+                    steps += 1;
+                    if self.has_forced_neighbor(current, dir) {
+                        break true;
+                    }
+                };
+                tile_distances[direction_index(dir)] = JumpDistance { steps, jump_point };
+            }
+            distances.set(*pos, tile_distances);
+        }
+
+        for pos in &positions {
+            let mut tile_distances = *distances.get(*pos).unwrap();
+            for dir in [Pos::UP_LEFT, Pos::UP_RIGHT, Pos::DOWN_LEFT, Pos::DOWN_RIGHT] {
+                let horizontal = direction_index(Pos { x: dir.x, y: 0 });
+                let vertical = direction_index(Pos { x: 0, y: dir.y });
+
+                let mut current = *pos;
+                let mut steps = 0;
+                let jump_point = loop {
+                    if !self.can_move(current, dir) {
+                        break false;
+                    }
+                    current = current + dir;
+                    steps += 1;
+                    let current_distances = distances.get(current).unwrap();
+                    if current_distances[horizontal].jump_point
+                        || current_distances[vertical].jump_point
+                        || self.near_changed_moves(current)
+                    {
+                        break true;
+                    }
+                };
+                tile_distances[direction_index(dir)] = JumpDistance { steps, jump_point };
+            }
+            distances.set(*pos, tile_distances);
+        }
+
+        distances
+    }
+
+    /// Fills in the tiles between the jump points of the path.
+    fn expand_path(&self, jump_points: &[Pos]) -> Vec<Pos> {
+        let mut path = Vec::new();
+        for segment in jump_points.windows(2) {
+            let (from, to) = (segment[0], segment[1]);
+            if self.grid.portal_partner(from) == Some(to) {
+                path.push(from);
+                continue;
+            }
+            let dir = (to - from).signum();
+
+            let mut pos = from;
+            while pos != to {
+                path.push(pos);
+                pos = pos + dir;
+            }
+        }
+        path.extend(jump_points.last());
+        path
+    }
+}
+
+/// Returns the two directions perpendicular to a straight direction.
+fn sides(dir: Pos) -> [Pos; 2] {
+    [
+        Pos { x: dir.y, y: dir.x },
+        Pos {
+            x: -dir.y,
+            y: -dir.x,
+        },
+    ]
+}
+
+fn direction_index(dir: Pos) -> usize {
+    Pos::ALL_DIRECTIONS
+        .iter()
+        .position(|direction| *direction == dir)
+        .expect("invalid direction")
+}
+
+impl PathFindAlgorithmConstructor for JumpPointSearch {
+    fn make_state(mut grid: Grid, heuristic: HeuristicOptions) -> Self {
+        grid.set_movement(Movement::EightWayNoCornerCutting);
+        grid.set_wraps(false);
+        let (width, height) = (grid.width() as usize, grid.height() as usize);
+
+        Self {
+            frontier: SearchFrontier::new(&grid, grid.start(), &grid.targets(), heuristic),
+            visited: vec![],
+            seen: Vec2d::new(width, height, false),
+            jump_distances: None,
+            grid,
+        }
+    }
+}
+
+impl PathFindAlgorithm for JumpPointSearch {
+    fn next_step(&mut self) -> Result<Vec<Pos>, PathFindAlgorithmStepResult> {
+        let pos = self.frontier.pop().ok_or(NotFound)?;
+        self.scan(pos);
+
+        if self.grid.is_goal(pos) {
+            let mut jump_points = self.frontier.path_to_root(pos).ok_or(NotFound)?;
+            jump_points.reverse();
+            return Ok(self.expand_path(&jump_points));
+        }
+
+        let mut successors = Vec::new();
+        for dir in self.pruned_directions(pos) {
+            if let Some(successor) = self.successor(pos, dir) {
+                // all tiles cost the same, so the cost of a jump is the octile distance
+                let cost = Octile.estimate(pos, successor);
+                successors.push((successor, cost));
+            }
+        }
+        if let Some(partner) = self.grid.portal_partner(pos) {
+            successors.push((partner, 0.0));
+        }
+        self.frontier.expand(pos, successors);
+
+        Err(InProgress)
+    }
+
+    fn visited(&self, pos: Pos) -> bool {
+        matches!(self.seen.get(pos), Some(true))
+    }
+
+    fn visited_list(&self) -> &[Pos] {
+        &self.visited
+    }
+
+    fn expanded(&self) -> usize {
+        self.frontier.expanded()
+    }
+
+    fn in_queue(&self, pos: Pos) -> bool {
+        self.frontier.in_queue(pos)
+    }
+}
@@ -0,0 +1,1080 @@
+//! Grids and step-wise pathfinding algorithms, without any UI dependencies.
+//!
+//! A [Grid] holds the tiles together with the start and the end of the path.
+//! Every algorithm of [PathFindAlgorithms] creates a [PathFindAlgorithm] from it,
+//! which does one step of the search every time [PathFindAlgorithm::next_step] is called,
+//! so the progress of the search can be shown in between.
+//!
+//! ```
+//! use pathfinder_core::heuristic::HeuristicOptions;
+//! use pathfinder_core::{Grid, PathFindAlgorithmStepResult, PathFindAlgorithms, Pos, Tile};
+//!
+//! // S # E
+//! // . . .
+//! let mut grid = Grid::new(3, 2, Pos { x: 0, y: 0 }, Pos { x: 2, y: 0 });
+//! grid.set_tile(Pos { x: 1, y: 0 }, Tile::Wall);
+//!
+//! let mut search = PathFindAlgorithms::AStar.make_state(grid.clone(), HeuristicOptions::default());
+//! let path = loop {
+//!     match search.next_step() {
+//!         Ok(path) => break Some(path),
+//!         Err(PathFindAlgorithmStepResult::NotFound) => break None,
+//!         Err(PathFindAlgorithmStepResult::InProgress) => {}
+//!     }
+//! };
+//! assert_eq!(grid.path_cost(&path.unwrap()), Some(4.0));
+//! ```
+#![warn(missing_docs)]
+
+use crate::breadth_first::BreadthFirst;
+use crate::heuristic::HeuristicOptions;
+use crate::iterative_deepening::DeepeningProgress;
+
+use std::f32::consts::SQRT_2;
+use std::fmt::Debug;
+use std::ops::{Add, Mul, Sub};
+
+/// The type of the coordinates and sizes of a grid.
+pub type Unit = i32;
+
+/// The position of a tile, with x going to the right and y going down.
+/// It's also used for the difference between two positions, like the direction of a move.
+#[derive(Copy, Clone, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Pos {
+    /// The column
+    pub x: Unit,
+    /// The row
+    pub y: Unit,
+}
+impl Pos {
+    const UP: Pos = Pos { x: 0, y: -1 };
+    const DOWN: Pos = Pos { x: 0, y: 1 };
+    const LEFT: Pos = Pos { x: -1, y: 0 };
+    const RIGHT: Pos = Pos { x: 1, y: 0 };
+    const UP_LEFT: Pos = Pos { x: -1, y: -1 };
+    const UP_RIGHT: Pos = Pos { x: 1, y: -1 };
+    const DOWN_LEFT: Pos = Pos { x: -1, y: 1 };
+    const DOWN_RIGHT: Pos = Pos { x: 1, y: 1 };
+
+    const STRAIGHT_DIRECTIONS: [Pos; 4] = [Pos::UP, Pos::DOWN, Pos::LEFT, Pos::RIGHT];
+    const ALL_DIRECTIONS: [Pos; 8] = [
+        Pos::UP,
+        Pos::DOWN,
+        Pos::LEFT,
+        Pos::RIGHT,
+        Pos::UP_LEFT,
+        Pos::UP_RIGHT,
+        Pos::DOWN_LEFT,
+        Pos::DOWN_RIGHT,
+    ];
+    // odd rows of hex grids are shifted half a tile to the right,
+    // so the diagonal neighbors of even rows are further left than the ones of odd rows
+    const HEX_EVEN_ROW_DIRECTIONS: [Pos; 6] = [
+        Pos::UP,
+        Pos::DOWN,
+        Pos::LEFT,
+        Pos::RIGHT,
+        Pos::UP_LEFT,
+        Pos::DOWN_LEFT,
+    ];
+    const HEX_ODD_ROW_DIRECTIONS: [Pos; 6] = [
+        Pos::UP,
+        Pos::DOWN,
+        Pos::LEFT,
+        Pos::RIGHT,
+        Pos::UP_RIGHT,
+        Pos::DOWN_RIGHT,
+    ];
+}
+
+impl Pos {
+    /// Returns the direction of the position, with both coordinates being -1, 0 or 1.
+    pub fn signum(self) -> Self {
+        Self {
+            x: self.x.signum(),
+            y: self.y.signum(),
+        }
+    }
+}
+
+impl Add for Pos {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self {
+            x: self.x + rhs.x,
+            y: self.y + rhs.y,
+        }
+    }
+}
+
+impl Sub for Pos {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self {
+            x: self.x - rhs.x,
+            y: self.y - rhs.y,
+        }
+    }
+}
+
+impl Mul<Unit> for Pos {
+    type Output = Self;
+
+    fn mul(self, rhs: Unit) -> Self::Output {
+        Self {
+            x: self.x * rhs,
+            y: self.y * rhs,
+        }
+    }
+}
+
+/// The content of a single tile of a [Grid].
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Tile {
+    /// An empty tile which costs 1 to step onto.
+    None,
+    /// A tile that can't be walked on.
+    Wall,
+    /// A walkable tile that costs the given amount to step onto.
+    /// A [Tile::None] costs 1 and costs below 1 are treated as 1.
+    Cost(u8),
+    /// A portal, which is paired with the other portal of the same number: stepping onto one moves you
+    /// to its partner for free. A portal without exactly one partner is walked on like an empty tile.
+    /// See [Grid::portal_partner].
+    Portal(u8),
+    /// A tile which can only be crossed in its direction.
+    /// Steps onto it can't go against the direction and steps off it have to go along it.
+    OneWay(Direction),
+}
+
+impl Tile {
+    /// The highest cost the editor and renderers use for weighted tiles.
+    pub const MAX_COST: u8 = 9;
+
+    /// Returns the cost of stepping onto this tile or None if it can't be walked on.
+    pub fn cost(&self) -> Option<f32> {
+        match self {
+            Tile::None => Some(1.0),
+            Tile::Wall => None,
+            Tile::Cost(cost) => Some((*cost).max(1) as f32),
+            Tile::Portal(_) | Tile::OneWay(_) => Some(1.0),
+        }
+    }
+
+    /// Returns whether the tile can be walked on.
+    pub fn is_walkable(&self) -> bool {
+        self.cost().is_some()
+    }
+
+    /// Returns whether the tile changes which moves can be made onto or off it, like portals and one-way tiles do.
+    pub fn changes_moves(&self) -> bool {
+        matches!(self, Tile::Portal(_) | Tile::OneWay(_))
+    }
+}
+
+/// One of the four straight directions, in which a [Tile::OneWay] can be crossed.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Direction {
+    /// Towards the first row
+    Up,
+    /// Towards the last row
+    Down,
+    /// Towards the first column
+    Left,
+    /// Towards the last column
+    Right,
+}
+
+impl Direction {
+    /// Every direction.
+    pub const ALL: [Self; 4] = [Self::Up, Self::Down, Self::Left, Self::Right];
+
+    /// Returns the identifier of the direction, which is used to select it in the UI and in files.
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::Up => "up",
+            Self::Down => "down",
+            Self::Left => "left",
+            Self::Right => "right",
+        }
+    }
+
+    /// Returns the direction with the identifier, see [Direction::id].
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|value| value.id() == id)
+    }
+
+    /// Returns the step of one tile in the direction.
+    pub fn offset(&self) -> Pos {
+        match self {
+            Self::Up => Pos::UP,
+            Self::Down => Pos::DOWN,
+            Self::Left => Pos::LEFT,
+            Self::Right => Pos::RIGHT,
+        }
+    }
+
+    /// Returns how far the step goes along the direction, negative if it goes against it.
+    fn along(&self, step: Pos) -> Unit {
+        let offset = self.offset();
+        offset.x * step.x + offset.y * step.y
+    }
+}
+
+/// The moves a search is allowed to make from one tile to another.
+#[derive(Copy, Clone, Debug, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Movement {
+    /// Only up, down, left and right.
+    #[default]
+    FourWay,
+    /// Additionally diagonal moves, which cost √2 times the cost of the tile stepped onto.
+    EightWay,
+    /// Like [Movement::EightWay], but diagonal moves can't cut past the corner of a wall.
+    EightWayNoCornerCutting,
+    /// Hexagonal tiles in offset coordinates: odd rows are shifted half a tile to the right,
+    /// so every tile has six neighbors, two in its own row and two in each row above and below.
+    /// Every step costs the cost of the tile stepped onto.
+    ///
+    /// Jump Point Search and Theta* rely on the square grid, so they ignore it and search with
+    /// [Movement::EightWayNoCornerCutting] like on every other grid.
+    Hex,
+}
+
+impl Movement {
+    /// Every movement.
+    pub const ALL: [Self; 4] = [
+        Self::FourWay,
+        Self::EightWay,
+        Self::EightWayNoCornerCutting,
+        Self::Hex,
+    ];
+
+    /// Returns the identifier of the movement, which is used to select it in the UI and in files.
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::FourWay => "four_way",
+            Self::EightWay => "eight_way",
+            Self::EightWayNoCornerCutting => "eight_way_no_corner_cutting",
+            Self::Hex => "hex",
+        }
+    }
+
+    /// Returns the movement with the identifier, see [Movement::id].
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|value| value.id() == id)
+    }
+
+    /// Returns the directions in which a single step can be made from the position.
+    /// Only on hex grids they depend on the position, see [Movement::Hex].
+    pub fn directions(&self, pos: Pos) -> &'static [Pos] {
+        match self {
+            Movement::FourWay => &Pos::STRAIGHT_DIRECTIONS,
+            Movement::EightWay | Movement::EightWayNoCornerCutting => &Pos::ALL_DIRECTIONS,
+            Movement::Hex if pos.y.rem_euclid(2) == 0 => &Pos::HEX_EVEN_ROW_DIRECTIONS,
+            Movement::Hex => &Pos::HEX_ODD_ROW_DIRECTIONS,
+        }
+    }
+
+    /// Returns whether diagonal steps are allowed.
+    pub fn is_diagonal(&self) -> bool {
+        !matches!(self, Movement::FourWay)
+    }
+
+    /// Returns whether the tiles are hexagons instead of squares.
+    pub fn is_hex(&self) -> bool {
+        matches!(self, Movement::Hex)
+    }
+}
+
+/// A fixed size two dimensional array, which is indexed by positions.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(
+        try_from = "json::Vec2dData<T>",
+        bound(deserialize = "T: serde::Deserialize<'de>")
+    )
+)]
+pub struct Vec2d<T> {
+    width: usize,
+    height: usize,
+    #[cfg_attr(feature = "serde", serde(rename = "values"))]
+    flattened: Vec<T>,
+}
+
+impl<T> Vec2d<T> {
+    /// Creates the array with every element set to the value.
+    pub fn new(width: usize, height: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        let flattened = vec![value; height * width];
+        Self {
+            width,
+            height,
+            flattened,
+        }
+    }
+
+    /// Returns the number of columns.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+    /// Returns the number of rows.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns the element at the position or None if the position is outside of the array.
+    pub fn get(&self, pos: Pos) -> Option<&T> {
+        let i = self.index(pos)?;
+        Some(&self.flattened[i])
+    }
+    /// Replaces the element at the position, positions outside of the array are ignored.
+    pub fn set(&mut self, pos: Pos, value: T) {
+        let i = self.index(pos);
+        let i = match i {
+            Some(i) => i,
+            None => return,
+        };
+        self.flattened[i] = value;
+    }
+
+    fn index(&self, pos: Pos) -> Option<usize> {
+        if pos.x < 0 || pos.y < 0 {
+            return None;
+        }
+        let x = pos.x as usize;
+        let y = pos.y as usize;
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        Some(y * self.width + x)
+    }
+}
+
+/// The tiles a path is searched on, together with the start and the end of the path
+/// and the moves which are allowed between the tiles.
+///
+/// Besides the start and the end, a grid can hold waypoints, which the path has to pass in their order
+/// before it goes to the end, and additional goals. If there are goals, the path ends at whichever of
+/// the end and the goals is reached first, see [Grid::targets].
+///
+/// Portals and one-way tiles change the moves between the tiles, see [Tile::Portal] and [Tile::OneWay].
+/// A grid can also wrap around its edges, see [Grid::set_wraps].
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "json::GridData"))]
+pub struct Grid {
+    tiles: Vec2d<Tile>,
+    start: Pos,
+    end: Pos,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    waypoints: Vec<Pos>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    goals: Vec<Pos>,
+    movement: Movement,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "std::ops::Not::not")
+    )]
+    wraps: bool,
+    /// The positions of all portals row by row, so their partners can be found without going through every tile
+    #[cfg_attr(feature = "serde", serde(skip))]
+    portals: Vec<Pos>,
+}
+
+impl Grid {
+    /// Creates a grid of empty tiles with [Movement::FourWay].
+    pub fn new(width: Unit, height: Unit, start: Pos, end: Pos) -> Self {
+        Self {
+            tiles: Vec2d::new(width as usize, height as usize, Tile::None),
+            start,
+            end,
+            waypoints: vec![],
+            goals: vec![],
+            movement: Movement::default(),
+            wraps: false,
+            portals: vec![],
+        }
+    }
+
+    /// Returns the number of columns.
+    pub fn width(&self) -> Unit {
+        self.tiles.width() as Unit
+    }
+    /// Returns the number of rows.
+    pub fn height(&self) -> Unit {
+        self.tiles.height() as Unit
+    }
+
+    /// Returns the tile at the position.
+    ///
+    /// # Panics
+    /// If the position is outside of the grid, see [Grid::tile_opt].
+    pub fn tile(&self, pos: Pos) -> Tile {
+        *self.tiles.get(pos).expect("invalid position")
+    }
+    /// Returns the tile at the position or None if the position is outside of the grid.
+    pub fn tile_opt(&self, pos: Pos) -> Option<Tile> {
+        self.tiles.get(pos).copied()
+    }
+    /// Returns whether the position is inside the grid and can be walked on.
+    pub fn walkable(&self, pos: Pos) -> bool {
+        matches!(self.tile_opt(pos), Some(tile) if tile.is_walkable())
+    }
+    /// Replaces the tile at the position.
+    /// The tiles of the start, the end, the waypoints and the goals can't be changed
+    /// and positions outside of the grid are ignored.
+    pub fn set_tile(&mut self, pos: Pos, tile: Tile) {
+        if self.is_marker(pos) || self.tile_opt(pos).is_none() {
+            return;
+        }
+        self.tiles.set(pos, tile);
+
+        // keeps the portals sorted row by row, so equal grids have equal lists
+        let index = self
+            .portals
+            .partition_point(|portal| (portal.y, portal.x) < (pos.y, pos.x));
+        let listed = self.portals.get(index) == Some(&pos);
+        match tile {
+            Tile::Portal(_) if !listed => self.portals.insert(index, pos),
+            Tile::Portal(_) => {}
+            _ if listed => {
+                self.portals.remove(index);
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns the positions of all portals row by row, including the ones without a partner.
+    pub fn portals(&self) -> &[Pos] {
+        &self.portals
+    }
+    /// Returns the portal which the portal at the position is paired with,
+    /// or None if there is no portal at the position or not exactly one other portal with its number.
+    pub fn portal_partner(&self, pos: Pos) -> Option<Pos> {
+        let number = match self.tile_opt(pos)? {
+            Tile::Portal(number) => number,
+            _ => return None,
+        };
+        let mut partners = self
+            .portals
+            .iter()
+            .filter(|portal| **portal != pos && self.tile(**portal) == Tile::Portal(number));
+        match (partners.next(), partners.next()) {
+            (Some(partner), None) => Some(*partner),
+            _ => None,
+        }
+    }
+
+    /// Returns the position the path starts at.
+    pub fn start(&self) -> Pos {
+        self.start
+    }
+    /// Moves the start, clearing the tile it is moved onto.
+    pub fn set_start(&mut self, pos: Pos) {
+        self.set_tile(pos, Tile::None);
+        self.start = pos;
+    }
+
+    /// Returns the position the path ends at.
+    pub fn end(&self) -> Pos {
+        self.end
+    }
+    /// Moves the end, clearing the tile it is moved onto.
+    pub fn set_end(&mut self, pos: Pos) {
+        self.set_tile(pos, Tile::None);
+        self.end = pos;
+    }
+
+    /// Returns the positions the path has to pass in this order before it goes to the end.
+    pub fn waypoints(&self) -> &[Pos] {
+        &self.waypoints
+    }
+    /// Adds a waypoint after the others, clearing the tile it is placed on.
+    pub fn add_waypoint(&mut self, pos: Pos) {
+        self.set_tile(pos, Tile::None);
+        self.waypoints.push(pos);
+    }
+    /// Moves the waypoint with the index, clearing the tile it is moved onto.
+    /// Invalid indices are ignored.
+    pub fn move_waypoint(&mut self, index: usize, pos: Pos) {
+        if index < self.waypoints.len() {
+            self.set_tile(pos, Tile::None);
+            self.waypoints[index] = pos;
+        }
+    }
+    /// Removes the waypoint with the index, invalid indices are ignored.
+    pub fn remove_waypoint(&mut self, index: usize) {
+        if index < self.waypoints.len() {
+            self.waypoints.remove(index);
+        }
+    }
+
+    /// Returns the goals which, like the end, finish the path once one of them is reached.
+    pub fn goals(&self) -> &[Pos] {
+        &self.goals
+    }
+    /// Adds a goal, clearing the tile it is placed on.
+    pub fn add_goal(&mut self, pos: Pos) {
+        self.set_tile(pos, Tile::None);
+        self.goals.push(pos);
+    }
+    /// Moves the goal with the index, clearing the tile it is moved onto.
+    /// Invalid indices are ignored.
+    pub fn move_goal(&mut self, index: usize, pos: Pos) {
+        if index < self.goals.len() {
+            self.set_tile(pos, Tile::None);
+            self.goals[index] = pos;
+        }
+    }
+    /// Removes the goal with the index, invalid indices are ignored.
+    pub fn remove_goal(&mut self, index: usize) {
+        if index < self.goals.len() {
+            self.goals.remove(index);
+        }
+    }
+
+    /// Removes all waypoints and goals.
+    pub fn clear_markers(&mut self) {
+        self.waypoints.clear();
+        self.goals.clear();
+    }
+
+    /// Returns the end followed by the goals, the path ends at the first of them it reaches.
+    pub fn targets(&self) -> Vec<Pos> {
+        let mut targets = vec![self.end];
+        targets.extend_from_slice(&self.goals);
+        targets
+    }
+    /// Returns whether the path can end at the position, because it's the end or one of the goals.
+    pub fn is_goal(&self, pos: Pos) -> bool {
+        self.end == pos || self.goals.contains(&pos)
+    }
+    /// Returns whether the start, the end, a waypoint or a goal is at the position.
+    pub fn is_marker(&self, pos: Pos) -> bool {
+        self.start == pos || self.is_goal(pos) || self.waypoints.contains(&pos)
+    }
+
+    /// Returns the moves which are allowed between the tiles.
+    pub fn movement(&self) -> Movement {
+        self.movement
+    }
+    /// Changes the moves which are allowed between the tiles.
+    pub fn set_movement(&mut self, movement: Movement) {
+        self.movement = movement;
+    }
+
+    /// Returns whether leaving the grid on one side re-enters it on the opposite side.
+    pub fn wraps(&self) -> bool {
+        self.wraps
+    }
+    /// Changes whether leaving the grid on one side re-enters it on the opposite side,
+    /// so the neighbors of the tiles at the edges include the tiles at the opposite edge.
+    ///
+    /// Hex grids only wrap vertically if they have an even number of rows,
+    /// as otherwise the first and the last row would both be shifted the same way.
+    /// Jump Point Search and Theta* ignore it, just like the movement.
+    pub fn set_wraps(&mut self, wraps: bool) {
+        self.wraps = wraps;
+    }
+    /// Returns whether the grid wraps around horizontally and vertically, see [Grid::set_wraps].
+    pub fn wrapping_axes(&self) -> (bool, bool) {
+        let vertically = !self.movement.is_hex() || self.height() % 2 == 0;
+        (self.wraps, self.wraps && vertically)
+    }
+    /// Returns the position of the tile a position outside of a wrapping grid stands for.
+    /// Other positions are returned as they are.
+    pub fn wrap(&self, pos: Pos) -> Pos {
+        let (horizontally, vertically) = self.wrapping_axes();
+        Pos {
+            x: if horizontally {
+                pos.x.rem_euclid(self.width())
+            } else {
+                pos.x
+            },
+            y: if vertically {
+                pos.y.rem_euclid(self.height())
+            } else {
+                pos.y
+            },
+        }
+    }
+    /// Returns the shortest difference between two positions,
+    /// which on wrapping grids might leave the grid on one side and re-enter it on the other.
+    pub fn step_offset(&self, from: Pos, to: Pos) -> Pos {
+        let (horizontally, vertically) = self.wrapping_axes();
+        let shortest = |diff: Unit, size: Unit, wraps: bool| {
+            if !wraps {
+                return diff;
+            }
+            let diff = diff.rem_euclid(size);
+            if diff > size / 2 {
+                diff - size
+            } else {
+                diff
+            }
+        };
+        Pos {
+            x: shortest(to.x - from.x, self.width(), horizontally),
+            y: shortest(to.y - from.y, self.height(), vertically),
+        }
+    }
+    /// Returns whether a step between two neighboring tiles goes over the edge of a wrapping grid.
+    pub fn crosses_edge(&self, from: Pos, to: Pos) -> bool {
+        self.step_offset(from, to) != to - from
+    }
+
+    /// Returns the tiles around the position in the directions of the movement.
+    fn adjacent(&self, pos: Pos) -> Vec<Pos> {
+        let mut adjacent = Vec::with_capacity(8);
+        for dir in self.movement.directions(pos) {
+            let neighbor = self.wrap(pos + *dir);
+            // a wrapping grid which is only one or two tiles wide reaches the same tile in several directions
+            if neighbor != pos && !adjacent.contains(&neighbor) {
+                adjacent.push(neighbor);
+            }
+        }
+        adjacent
+    }
+
+    /// Returns all tiles which can be reached in one step from the position together with the cost of that step.
+    /// From a paired portal, its partner can be reached for free.
+    pub fn neighbors(&self, pos: Pos) -> Vec<(Pos, f32)> {
+        let mut neighbors = self
+            .adjacent(pos)
+            .into_iter()
+            .filter_map(|neighbor| Some((neighbor, self.step_cost(pos, neighbor)?)))
+            .collect::<Vec<_>>();
+        self.add_portal_partner(pos, &mut neighbors);
+        neighbors
+    }
+
+    /// Returns all tiles from which the position can be reached in one step together with the cost of that step.
+    pub fn predecessors(&self, pos: Pos) -> Vec<(Pos, f32)> {
+        let mut predecessors = self
+            .adjacent(pos)
+            .into_iter()
+            .filter(|predecessor| self.walkable(*predecessor))
+            .filter_map(|predecessor| Some((predecessor, self.step_cost(predecessor, pos)?)))
+            .collect::<Vec<_>>();
+        // portals are paired both ways, so the partner is a predecessor as well
+        self.add_portal_partner(pos, &mut predecessors);
+        predecessors
+    }
+
+    fn add_portal_partner(&self, pos: Pos, steps: &mut Vec<(Pos, f32)>) {
+        if let Some(partner) = self.portal_partner(pos) {
+            // a partner right next to the portal is already in the list
+            if steps.iter().all(|(step, _)| *step != partner) {
+                steps.push((partner, 0.0));
+            }
+        }
+    }
+
+    /// Returns the cost of moving from one tile onto a neighboring tile or through a portal onto its partner,
+    /// or None if the tiles aren't neighbors, the move isn't allowed by the movement or by a one-way tile
+    /// or the target can't be walked on.
+    pub fn step_cost(&self, from: Pos, to: Pos) -> Option<f32> {
+        if self.portal_partner(from) == Some(to) {
+            return Some(0.0);
+        }
+        let cost = self.tile_opt(to)?.cost()?;
+
+        let step = self.step_offset(from, to);
+        let (diff_x, diff_y) = (step.x, step.y);
+        if diff_x.abs() > 1 || diff_y.abs() > 1 {
+            return None;
+        }
+        if !self.one_way_allows(from, to) {
+            return None;
+        }
+        if diff_x == 0 || diff_y == 0 {
+            return Some(cost);
+        }
+
+        match self.movement {
+            Movement::FourWay => None,
+            Movement::EightWay => Some(cost * SQRT_2),
+            // only two of the four diagonal steps lead to neighbors of a hex tile
+            Movement::Hex => self
+                .movement
+                .directions(from)
+                .contains(&step)
+                .then_some(cost),
+            Movement::EightWayNoCornerCutting => {
+                let corner_x = self.wrap(from + Pos { x: diff_x, y: 0 });
+                let corner_y = self.wrap(from + Pos { x: 0, y: diff_y });
+
+                if self.walkable(corner_x) && self.walkable(corner_y) {
+                    Some(cost * SQRT_2)
+                } else {
+                    None
+                }
+            }
+        }
+    }
+    /// Returns whether one-way tiles allow a step between two neighboring tiles.
+    fn one_way_allows(&self, from: Pos, to: Pos) -> bool {
+        let step = self.step_offset(from, to);
+        let onto = match self.tile_opt(to) {
+            Some(Tile::OneWay(direction)) => direction.along(step) >= 0,
+            _ => true,
+        };
+        let off = match self.tile_opt(from) {
+            Some(Tile::OneWay(direction)) => direction.along(step) > 0,
+            _ => true,
+        };
+        onto && off
+    }
+
+    /// Returns the summed up cost of walking along the path.
+    pub fn path_cost(&self, path: &[Pos]) -> Option<f32> {
+        path.windows(2)
+            .map(|step| self.step_cost(step[0], step[1]))
+            .sum()
+    }
+
+    /// Returns the tiles a straight line between the centers of the two tiles passes through, starting with `from`.
+    /// Where the line passes exactly through the corner of two tiles, these tiles are left out.
+    pub fn line(&self, from: Pos, to: Pos) -> Vec<Pos> {
+        self.line_crossings(from, to).0
+    }
+
+    /// Returns whether a straight line between the centers of the two tiles only passes walkable tiles.
+    /// Like [Movement::EightWayNoCornerCutting], the line can't squeeze through the corner between a wall and another tile.
+    pub fn line_of_sight(&self, from: Pos, to: Pos) -> bool {
+        let (tiles, corners) = self.line_crossings(from, to);
+        tiles
+            .into_iter()
+            .chain(corners)
+            .all(|pos| self.walkable(pos))
+    }
+
+    /// Returns the length of a straight line between the centers of the two tiles
+    /// or None if it isn't in [Grid::line_of_sight].
+    /// Lines can only pass portals and one-way tiles when they are a single step the tiles allow,
+    /// and going from a paired portal to its partner is free. The cost of the tiles is ignored.
+    pub fn line_cost(&self, from: Pos, to: Pos) -> Option<f32> {
+        if self.portal_partner(from) == Some(to) {
+            return Some(0.0);
+        }
+        if !self.line_of_sight(from, to) {
+            return None;
+        }
+
+        let (diff_x, diff_y) = (to.x - from.x, to.y - from.y);
+        let changes_moves = self
+            .line(from, to)
+            .into_iter()
+            .any(|pos| self.tile(pos).changes_moves());
+        if changes_moves && (diff_x.abs() > 1 || diff_y.abs() > 1 || !self.one_way_allows(from, to))
+        {
+            return None;
+        }
+        Some((diff_x as f32).hypot(diff_y as f32))
+    }
+
+    /// Returns the length of a path made of straight lines between its positions
+    /// or None if one of the lines isn't possible, see [Grid::line_cost].
+    pub fn any_angle_path_cost(&self, path: &[Pos]) -> Option<f32> {
+        path.windows(2)
+            .map(|line| self.line_cost(line[0], line[1]))
+            .sum()
+    }
+
+    /// Walks along the line between the centers of the two tiles (supercover)
+    /// and returns the crossed tiles together with the tiles the line only touches at a corner.
+    fn line_crossings(&self, from: Pos, to: Pos) -> (Vec<Pos>, Vec<Pos>) {
+        let (diff_x, diff_y) = ((to.x - from.x).abs(), (to.y - from.y).abs());
+        let dir = (to - from).signum();
+
+        let mut tiles = vec![from];
+        let mut corners = vec![];
+        let mut pos = from;
+        let (mut steps_x, mut steps_y) = (0, 0);
+        while steps_x < diff_x || steps_y < diff_y {
+            // compares where the line leaves the current tile horizontally and vertically
+            let decision = (1 + 2 * steps_x) * diff_y - (1 + 2 * steps_y) * diff_x;
+            if decision == 0 {
+                corners.push(Pos {
+                    x: pos.x + dir.x,
+                    y: pos.y,
+                });
+                corners.push(Pos {
+                    x: pos.x,
+                    y: pos.y + dir.y,
+                });
+                pos = pos + dir;
+                steps_x += 1;
+                steps_y += 1;
+            } else if decision < 0 {
+                pos.x += dir.x;
+                steps_x += 1;
+            } else {
+                pos.y += dir.y;
+                steps_y += 1;
+            }
+            tiles.push(pos);
+        }
+
+        (tiles, corners)
+    }
+}
+
+// description of pathfinding algorithms https://happycoding.io/tutorials/libgdx/pathfinding
+pub mod a_star;
+pub mod best_first;
+pub mod bidirectional;
+pub mod breadth_first;
+pub mod compact;
+pub mod depth_first;
+pub mod dijkstra;
+pub mod generators;
+pub mod greedy_best_first;
+pub mod heuristic;
+pub mod iterative_deepening;
+#[cfg(feature = "serde")]
+pub mod json;
+pub mod jump_point;
+pub mod lpa_star;
+pub mod movingai;
+pub mod open_set;
+pub mod route;
+pub mod search_result;
+pub mod text;
+pub mod theta_star;
+pub mod tour;
+
+/// All available algorithms, see their modules for how they work.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum PathFindAlgorithms {
+    /// [depth_first::DepthFirst]
+    DepthFirst,
+    /// [breadth_first::BreadthFirst]
+    BreadthFirst,
+    /// [dijkstra::Dijkstra]
+    Dijkstra,
+    /// [a_star::AStar]
+    AStar,
+    /// [greedy_best_first::GreedyBestFirst]
+    GreedyBestFirst,
+    /// [bidirectional::BidirectionalBreadthFirst]
+    BidirectionalBreadthFirst,
+    /// [bidirectional::BidirectionalDijkstra]
+    BidirectionalDijkstra,
+    /// [bidirectional::BidirectionalAStar]
+    BidirectionalAStar,
+    /// [jump_point::JumpPointSearch]
+    JumpPointSearch,
+    /// [jump_point::JumpPointSearch::with_preprocessing]
+    JumpPointSearchPlus,
+    /// [lpa_star::LpaStar]
+    LpaStar,
+    /// [iterative_deepening::IdaStar]
+    IdaStar,
+    /// [iterative_deepening::IterativeDeepeningDepthFirst]
+    IterativeDeepeningDepthFirst,
+    /// [theta_star::ThetaStar]
+    ThetaStar,
+    /// [theta_star::ThetaStar::lazy]
+    LazyThetaStar,
+}
+impl PathFindAlgorithms {
+    /// Every algorithm.
+    pub const ALL: [Self; 15] = [
+        Self::DepthFirst,
+        Self::BreadthFirst,
+        Self::Dijkstra,
+        Self::AStar,
+        Self::GreedyBestFirst,
+        Self::BidirectionalBreadthFirst,
+        Self::BidirectionalDijkstra,
+        Self::BidirectionalAStar,
+        Self::JumpPointSearch,
+        Self::JumpPointSearchPlus,
+        Self::LpaStar,
+        Self::IdaStar,
+        Self::IterativeDeepeningDepthFirst,
+        Self::ThetaStar,
+        Self::LazyThetaStar,
+    ];
+
+    /// Returns the identifier of the algorithm, which is used to select it in the UI and in files.
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::DepthFirst => "depth_first",
+            Self::BreadthFirst => "breadth_first",
+            Self::Dijkstra => "dijkstra",
+            Self::AStar => "a_star",
+            Self::GreedyBestFirst => "greedy_best_first",
+            Self::BidirectionalBreadthFirst => "bidirectional_breadth_first",
+            Self::BidirectionalDijkstra => "bidirectional_dijkstra",
+            Self::BidirectionalAStar => "bidirectional_a_star",
+            Self::JumpPointSearch => "jump_point_search",
+            Self::JumpPointSearchPlus => "jump_point_search_plus",
+            Self::LpaStar => "lpa_star",
+            Self::IdaStar => "ida_star",
+            Self::IterativeDeepeningDepthFirst => "iterative_deepening_depth_first",
+            Self::ThetaStar => "theta_star",
+            Self::LazyThetaStar => "lazy_theta_star",
+        }
+    }
+
+    /// Returns the algorithm with the identifier, see [PathFindAlgorithms::id].
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|value| value.id() == id)
+    }
+
+    /// Returns whether the search itself stops at the closest of several goals, see [Grid::goals].
+    /// The other algorithms search a path to every goal, see [route::NearestGoalSearch].
+    pub fn supports_goals(&self) -> bool {
+        matches!(
+            self,
+            Self::DepthFirst
+                | Self::BreadthFirst
+                | Self::Dijkstra
+                | Self::AStar
+                | Self::GreedyBestFirst
+                | Self::JumpPointSearch
+                | Self::ThetaStar
+                | Self::LazyThetaStar
+        )
+    }
+
+    /// Creates a search for a path from the start through the waypoints to the end or the closest goal of the grid.
+    /// Grids with waypoints or, for algorithms which don't [support goals](PathFindAlgorithms::supports_goals),
+    /// with goals are searched by one of the searches of [route].
+    pub fn make_state(
+        &self,
+        grid: Grid,
+        heuristic: HeuristicOptions,
+    ) -> Box<dyn PathFindAlgorithm> {
+        if !grid.waypoints().is_empty() {
+            Box::new(route::WaypointSearch::new(*self, grid, heuristic))
+        } else if !grid.goals().is_empty() && !self.supports_goals() {
+            Box::new(route::NearestGoalSearch::new(*self, grid, heuristic))
+        } else {
+            self.make_single_state(grid, heuristic)
+        }
+    }
+
+    fn make_single_state(
+        &self,
+        grid: Grid,
+        heuristic: HeuristicOptions,
+    ) -> Box<dyn PathFindAlgorithm> {
+        match self {
+            Self::DepthFirst => Box::new(depth_first::DepthFirst::make_state(grid, heuristic)),
+            Self::BreadthFirst => Box::new(BreadthFirst::make_state(grid, heuristic)),
+            Self::Dijkstra => Box::new(dijkstra::Dijkstra::make_state(grid, heuristic)),
+            Self::AStar => Box::new(a_star::AStar::make_state(grid, heuristic)),
+            Self::GreedyBestFirst => Box::new(greedy_best_first::GreedyBestFirst::make_state(
+                grid, heuristic,
+            )),
+            Self::BidirectionalBreadthFirst => Box::new(
+                bidirectional::BidirectionalBreadthFirst::make_state(grid, heuristic),
+            ),
+            Self::BidirectionalDijkstra => Box::new(
+                bidirectional::BidirectionalDijkstra::make_state(grid, heuristic),
+            ),
+            Self::BidirectionalAStar => Box::new(bidirectional::BidirectionalAStar::make_state(
+                grid, heuristic,
+            )),
+            Self::JumpPointSearch => {
+                Box::new(jump_point::JumpPointSearch::make_state(grid, heuristic))
+            }
+            Self::JumpPointSearchPlus => Box::new(jump_point::JumpPointSearch::with_preprocessing(
+                grid, heuristic,
+            )),
+            Self::LpaStar => Box::new(lpa_star::LpaStar::make_state(grid, heuristic)),
+            Self::IdaStar => Box::new(iterative_deepening::IdaStar::make_state(grid, heuristic)),
+            Self::IterativeDeepeningDepthFirst => Box::new(
+                iterative_deepening::IterativeDeepeningDepthFirst::make_state(grid, heuristic),
+            ),
+            Self::ThetaStar => Box::new(theta_star::ThetaStar::make_state(grid, heuristic)),
+            Self::LazyThetaStar => Box::new(theta_star::ThetaStar::lazy(grid, heuristic)),
+        }
+    }
+}
+
+/// Creates the state of a search.
+pub trait PathFindAlgorithmConstructor {
+    /// Creates a search for a path from the start to the end of the grid.
+    /// Searches which don't estimate the distance to the end ignore the heuristic.
+    fn make_state(grid: Grid, heuristic: HeuristicOptions) -> Self;
+}
+/// The state of a running search.
+pub trait PathFindAlgorithm {
+    /// Does one step of the search.
+    /// Returns the path from the start to the end once it's found.
+    fn next_step(&mut self) -> Result<Vec<Pos>, PathFindAlgorithmStepResult>;
+
+    /// Returns whether the search has visited the position.
+    fn visited(&self, pos: Pos) -> bool;
+    /// The visited tiles in the order they were visited.
+    fn visited_list(&self) -> &[Pos];
+    /// The tiles visited by the half of a bidirectional search which starts at the end.
+    fn visited_from_end_list(&self) -> &[Pos] {
+        &[]
+    }
+    /// How often the search expanded a tile so far, which is the same measure for all searches.
+    /// Tiles expanded again, like in later iterations of iterative deepening, are counted every time.
+    fn expanded(&self) -> usize;
+    /// Returns whether the position is waiting to be expanded.
+    fn in_queue(&self, pos: Pos) -> bool;
+
+    /// Tells the search that the tiles at the positions changed, so it can repair its result instead of starting over.
+    /// Returns false if the search can't handle the change and has to be restarted.
+    fn grid_changed(&mut self, _grid: &Grid, _changed: &[Pos]) -> bool {
+        false
+    }
+    /// Whether the search keeps being useful after it finished, because it can repair its result when the grid changes.
+    fn is_incremental(&self) -> bool {
+        false
+    }
+    /// The tiles expanded again since the grid last changed.
+    fn reexpanded_list(&self) -> &[Pos] {
+        &[]
+    }
+    /// Whether the found path is made of straight lines between its positions, see [Grid::any_angle_path_cost].
+    /// Otherwise each position of the path is a neighbor of the one before.
+    fn is_any_angle(&self) -> bool {
+        false
+    }
+    /// The iteration and threshold of an iterative deepening search.
+    fn deepening_progress(&self) -> Option<DeepeningProgress> {
+        None
+    }
+}
+
+/// Why [PathFindAlgorithm::next_step] didn't return a path.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum PathFindAlgorithmStepResult {
+    /// The search needs more steps.
+    InProgress,
+    /// The search is done and the end can't be reached from the start.
+    NotFound,
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,256 @@
+//! Lifelong Planning A*.
+
+use crate::heuristic::{GoalEstimate, HeuristicOptions};
+use crate::open_set::PriorityQueue;
+use crate::PathFindAlgorithmStepResult::{InProgress, NotFound};
+use crate::{
+    Grid, PathFindAlgorithm, PathFindAlgorithmConstructor, PathFindAlgorithmStepResult, Pos, Tile,
+    Vec2d,
+};
+
+/// The relative difference between two keys which is still treated as a rounding error.
+const KEY_TOLERANCE: f32 = 1e-5;
+
+/// Lifelong Planning A*, an A* which can repair its result after tiles of the grid changed.
+///
+/// Every tile stores its distance to the start (g) and a one step lookahead of it (rhs),
+/// which is calculated from the distances of its predecessors.
+/// Only tiles where both disagree are queued, so after a change only the affected tiles are expanded again.
+#[derive(Clone, Debug)]
+pub struct LpaStar {
+    grid: Grid,
+    estimate: GoalEstimate,
+    distances: Vec2d<f32>,
+    lookaheads: Vec2d<f32>,
+    queue: PriorityQueue<(f32, f32)>,
+    visited: Vec<Pos>,
+    seen: Vec2d<bool>,
+    expanded: usize,
+    reexpanded: Vec<Pos>,
+    reexpanded_seen: Vec2d<bool>,
+    changed: bool,
+}
+
+impl LpaStar {
+    fn distance(&self, pos: Pos) -> f32 {
+        *self.distances.get(pos).unwrap_or(&f32::INFINITY)
+    }
+
+    fn lookahead(&self, pos: Pos) -> f32 {
+        *self.lookaheads.get(pos).unwrap_or(&f32::INFINITY)
+    }
+
+    fn key(&self, pos: Pos) -> (f32, f32) {
+        let distance = self.distance(pos).min(self.lookahead(pos));
+        let distance_to_goal = self.estimate.estimate(pos);
+
+        (distance + distance_to_goal, distance)
+    }
+
+    /// Recalculates the lookahead of the position and queues it if it's inconsistent.
+    fn update(&mut self, pos: Pos) {
+        if pos != self.grid.start() {
+            let lookahead = self
+                .grid
+                .predecessors(pos)
+                .into_iter()
+                .map(|(predecessor, cost)| self.distance(predecessor) + cost)
+                .fold(f32::INFINITY, f32::min);
+            self.lookaheads.set(pos, lookahead);
+        }
+
+        if self.distance(pos) == self.lookahead(pos) {
+            self.queue.remove(pos);
+        } else {
+            self.queue.insert(pos, self.key(pos));
+        }
+    }
+
+    fn expand(&mut self, pos: Pos) {
+        self.mark_expanded(pos);
+
+        if self.distance(pos) > self.lookahead(pos) {
+            self.distances.set(pos, self.lookahead(pos));
+        } else {
+            // the tile got more expensive to reach, so everything that was reached through it has to be checked again
+            self.distances.set(pos, f32::INFINITY);
+            self.update(pos);
+        }
+
+        for (neighbor, _) in self.grid.neighbors(pos) {
+            self.update(neighbor);
+        }
+    }
+
+    fn mark_expanded(&mut self, pos: Pos) {
+        self.expanded += 1;
+        if let Some(false) = self.seen.get(pos) {
+            self.seen.set(pos, true);
+            self.visited.push(pos);
+        }
+        if self.changed {
+            if let Some(false) = self.reexpanded_seen.get(pos) {
+                self.reexpanded_seen.set(pos, true);
+                self.reexpanded.push(pos);
+            }
+        }
+    }
+
+    /// Whether the end and every tile that could still lead to a cheaper path to it are consistent.
+    fn is_finished(&mut self) -> bool {
+        let end = self.grid.end();
+        let end_key = self.key(end);
+        let end_consistent = self.distance(end) == self.lookahead(end);
+
+        match self.queue.peek() {
+            // tiles with about the same key as the end could still be on the path, so they are expanded as well,
+            // the keys are sums of rounded step costs, which is why they are compared with a small tolerance
+            Some((_, key)) => key.0 > end_key.0 * (1.0 + KEY_TOLERANCE) && end_consistent,
+            None => true,
+        }
+    }
+
+    /// Walks back from the end to the start, always stepping onto the predecessor the end is reached cheapest through.
+    fn path(&self) -> Option<Vec<Pos>> {
+        let (start, end) = (self.grid.start(), self.grid.end());
+        if self.distance(end).is_infinite() {
+            return None;
+        }
+
+        let mut path = vec![end];
+        let mut pos = end;
+        while pos != start {
+            // queued tiles might still have an outdated distance, which could lead the path in circles
+            let distance = self.distance(pos);
+            pos = self
+                .grid
+                .predecessors(pos)
+                .into_iter()
+                .filter(|(predecessor, _)| !self.queue.contains(*predecessor))
+                // going through a portal is free, so its partner is reached with the same distance
+                .filter(|(predecessor, cost)| {
+                    self.distance(*predecessor) < distance
+                        || (*cost == 0.0 && !path.contains(predecessor))
+                })
+                .map(|(predecessor, cost)| (predecessor, self.distance(predecessor) + cost))
+                .min_by(|(_, a), (_, b)| a.total_cmp(b))?
+                .0;
+            path.push(pos);
+        }
+
+        path.reverse();
+        Some(path)
+    }
+}
+
+impl PathFindAlgorithmConstructor for LpaStar {
+    fn make_state(grid: Grid, heuristic: HeuristicOptions) -> Self {
+        let (width, height) = (grid.width() as usize, grid.height() as usize);
+
+        let mut state = Self {
+            estimate: GoalEstimate::new(&grid, &[grid.end()], heuristic),
+            distances: Vec2d::new(width, height, f32::INFINITY),
+            lookaheads: Vec2d::new(width, height, f32::INFINITY),
+            queue: PriorityQueue::new(&grid),
+            visited: vec![],
+            seen: Vec2d::new(width, height, false),
+            expanded: 0,
+            reexpanded: vec![],
+            reexpanded_seen: Vec2d::new(width, height, false),
+            changed: false,
+            grid,
+        };
+
+        let start = state.grid.start();
+        state.lookaheads.set(start, 0.0);
+        state.queue.insert(start, state.key(start));
+
+        state
+    }
+}
+
+impl PathFindAlgorithm for LpaStar {
+    fn next_step(&mut self) -> Result<Vec<Pos>, PathFindAlgorithmStepResult> {
+        if self.is_finished() {
+            return self.path().ok_or(NotFound);
+        }
+
+        if let Some((pos, _)) = self.queue.pop() {
+            self.expand(pos);
+        }
+        Err(InProgress)
+    }
+
+    fn visited(&self, pos: Pos) -> bool {
+        matches!(self.seen.get(pos), Some(true))
+    }
+
+    fn visited_list(&self) -> &[Pos] {
+        &self.visited
+    }
+
+    fn expanded(&self) -> usize {
+        self.expanded
+    }
+
+    fn in_queue(&self, pos: Pos) -> bool {
+        self.queue.contains(pos)
+    }
+
+    fn grid_changed(&mut self, grid: &Grid, changed: &[Pos]) -> bool {
+        let same_layout = grid.width() == self.grid.width()
+            && grid.height() == self.grid.height()
+            && grid.start() == self.grid.start()
+            && grid.end() == self.grid.end()
+            && grid.waypoints() == self.grid.waypoints()
+            && grid.goals() == self.grid.goals()
+            && grid.movement() == self.grid.movement()
+            && grid.wraps() == self.grid.wraps();
+        // the estimate and the steps through portals depend on where the portals are
+        let portal_changed = changed.iter().any(|pos| {
+            matches!(grid.tile_opt(*pos), Some(Tile::Portal(_)))
+                || matches!(self.grid.tile_opt(*pos), Some(Tile::Portal(_)))
+        });
+        if !same_layout || portal_changed {
+            return false;
+        }
+
+        self.grid = grid.clone();
+        self.changed = true;
+        self.reexpanded.clear();
+        self.reexpanded_seen = Vec2d::new(
+            self.grid.width() as usize,
+            self.grid.height() as usize,
+            false,
+        );
+
+        // a changed tile changes the cost of stepping onto it and, without corner cutting, of the diagonal moves around it
+        for pos in changed {
+            self.update(*pos);
+            // on wrapping grids the tiles around it can be on the opposite edge, or the same tile in several directions
+            let mut around = Vec::with_capacity(8);
+            for dir in Pos::ALL_DIRECTIONS {
+                let neighbor = self.grid.wrap(*pos + dir);
+                if neighbor != *pos
+                    && self.grid.tile_opt(neighbor).is_some()
+                    && !around.contains(&neighbor)
+                {
+                    around.push(neighbor);
+                }
+            }
+            for neighbor in around {
+                self.update(neighbor);
+            }
+        }
+
+        true
+    }
+
+    fn is_incremental(&self) -> bool {
+        true
+    }
+
+    fn reexpanded_list(&self) -> &[Pos] {
+        &self.reexpanded
+    }
+}
@@ -0,0 +1,303 @@
+//! The map and scenario formats of the MovingAI grid benchmarks,
+//! see [movingai.com/benchmarks/formats.html](https://movingai.com/benchmarks/formats.html).
+//!
+//! The optimal lengths of the benchmark scenarios are calculated with diagonal moves costing √2,
+//! which aren't allowed to cut corners, so the grids use [Movement::EightWayNoCornerCutting].
+
+use crate::{Grid, Movement, Pos, Tile, Unit, Vec2d};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// Why a map or scenario file couldn't be read.
+#[derive(Clone, Debug, PartialEq)]
+pub enum MovingAiError {
+    /// A line of the header or of a scenario isn't in the expected format.
+    InvalidLine {
+        /// The line number, starting at 1.
+        line: usize,
+        /// What was expected instead.
+        expected: &'static str,
+    },
+    /// The tiles don't have the size given in the header.
+    WrongSize {
+        /// The width given in the header.
+        width: Unit,
+        /// The height given in the header.
+        height: Unit,
+    },
+    /// A character which doesn't stand for a tile.
+    InvalidTile {
+        /// The position of the character.
+        pos: Pos,
+        /// The character.
+        character: char,
+    },
+}
+
+impl Display for MovingAiError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MovingAiError::InvalidLine { line, expected } => {
+                write!(f, "line {}: expected {}", line, expected)
+            }
+            MovingAiError::WrongSize { width, height } => {
+                write!(f, "the tiles don't match the size {}x{}", width, height)
+            }
+            MovingAiError::InvalidTile { pos, character } => write!(
+                f,
+                "invalid tile '{}' at x {}, y {}",
+                character, pos.x, pos.y
+            ),
+        }
+    }
+}
+
+impl std::error::Error for MovingAiError {}
+
+/// The tiles of a `.map` file, which only knows passable tiles and walls.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MovingAiMap {
+    tiles: Vec2d<Tile>,
+}
+
+impl MovingAiMap {
+    /// Takes the walls of the grid, weighted tiles become normal passable tiles.
+    pub fn from_grid(grid: &Grid) -> Self {
+        let mut tiles = Vec2d::new(grid.width() as usize, grid.height() as usize, Tile::None);
+        for y in 0..grid.height() {
+            for x in 0..grid.width() {
+                let pos = Pos { x, y };
+                if !grid.tile(pos).is_walkable() {
+                    tiles.set(pos, Tile::Wall);
+                }
+            }
+        }
+        Self { tiles }
+    }
+
+    /// Returns the number of columns.
+    pub fn width(&self) -> Unit {
+        self.tiles.width() as Unit
+    }
+    /// Returns the number of rows.
+    pub fn height(&self) -> Unit {
+        self.tiles.height() as Unit
+    }
+
+    /// Returns the tile at the position or None if the position is outside of the map.
+    pub fn tile(&self, pos: Pos) -> Option<Tile> {
+        self.tiles.get(pos).copied()
+    }
+
+    /// Creates a grid with the tiles of the map and the movement of the benchmarks.
+    pub fn to_grid(&self, start: Pos, end: Pos) -> Grid {
+        let mut grid = Grid::new(self.width(), self.height(), start, end);
+        grid.set_movement(Movement::EightWayNoCornerCutting);
+        for y in 0..self.height() {
+            for x in 0..self.width() {
+                let pos = Pos { x, y };
+                grid.set_tile(pos, self.tiles.get(pos).copied().unwrap_or(Tile::Wall));
+            }
+        }
+        grid
+    }
+}
+
+impl FromStr for MovingAiMap {
+    type Err = MovingAiError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let lines = text.lines().map(str::trim_end).collect::<Vec<_>>();
+        let header = |i: usize, name: &str, expected: &'static str| {
+            let mut parts = lines.get(i).copied().unwrap_or_default().split_whitespace();
+            match (parts.next(), parts.next(), parts.next()) {
+                (Some(found), value, None) if found == name => Ok(value),
+                _ => Err(MovingAiError::InvalidLine {
+                    line: i + 1,
+                    expected,
+                }),
+            }
+        };
+        let size = |i: usize, name: &str, expected: &'static str| {
+            header(i, name, expected)?
+                .and_then(|value| value.parse::<Unit>().ok())
+                .ok_or(MovingAiError::InvalidLine {
+                    line: i + 1,
+                    expected,
+                })
+        };
+
+        // the type is always octile, the movement rules are the same for every map
+        header(0, "type", "'type octile'")?;
+        let height = size(1, "height", "'height <rows>'")?;
+        let width = size(2, "width", "'width <columns>'")?;
+        header(3, "map", "'map'")?;
+
+        let rows = lines
+            .iter()
+            .skip(4)
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>();
+        if width <= 0
+            || rows.len() != height as usize
+            || rows.iter().any(|row| row.chars().count() != width as usize)
+        {
+            return Err(MovingAiError::WrongSize { width, height });
+        }
+
+        let mut tiles = Vec2d::new(width as usize, height as usize, Tile::None);
+        for (y, row) in rows.iter().enumerate() {
+            for (x, character) in row.chars().enumerate() {
+                let pos = Pos {
+                    x: x as Unit,
+                    y: y as Unit,
+                };
+                let tile = match character {
+                    // ground and swamp
+                    '.' | 'G' | 'S' => Tile::None,
+                    // out of bounds, trees and water
+                    '@' | 'O' | 'T' | 'W' => Tile::Wall,
+                    _ => return Err(MovingAiError::InvalidTile { pos, character }),
+                };
+                tiles.set(pos, tile);
+            }
+        }
+        Ok(Self { tiles })
+    }
+}
+
+impl Display for MovingAiMap {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "type octile")?;
+        writeln!(f, "height {}", self.height())?;
+        writeln!(f, "width {}", self.width())?;
+        writeln!(f, "map")?;
+        for y in 0..self.height() {
+            for x in 0..self.width() {
+                let walkable = self.tiles.get(Pos { x, y }).is_some_and(Tile::is_walkable);
+                write!(f, "{}", if walkable { '.' } else { '@' })?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// A single problem of a `.scen` file.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScenarioEntry {
+    /// Groups problems of similar length, usually the optimal length divided by 4.
+    pub bucket: u32,
+    /// The path of the `.map` file the problem is on.
+    pub map: String,
+    /// The width of the map.
+    pub map_width: Unit,
+    /// The height of the map.
+    pub map_height: Unit,
+    /// Where the path starts.
+    pub start: Pos,
+    /// Where the path ends.
+    pub goal: Pos,
+    /// The cost of the shortest path.
+    pub optimal_length: f64,
+}
+
+impl ScenarioEntry {
+    /// Creates the grid of this problem on the map.
+    pub fn to_grid(&self, map: &MovingAiMap) -> Grid {
+        map.to_grid(self.start, self.goal)
+    }
+}
+
+/// The problems of a `.scen` file.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct Scenario {
+    /// The problems in the order of the file.
+    pub entries: Vec<ScenarioEntry>,
+}
+
+impl FromStr for Scenario {
+    type Err = MovingAiError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        const ENTRY: &str =
+            "'<bucket> <map> <width> <height> <start x> <start y> <goal x> <goal y> <optimal length>'";
+
+        let mut lines = text.lines().enumerate();
+        match lines
+            .next()
+            .map(|(_, line)| line.split_whitespace().collect::<Vec<_>>())
+        {
+            Some(version) if version.first() == Some(&"version") => {}
+            _ => {
+                return Err(MovingAiError::InvalidLine {
+                    line: 1,
+                    expected: "'version <version>'",
+                })
+            }
+        }
+
+        let mut entries = Vec::new();
+        for (i, line) in lines {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let invalid = || MovingAiError::InvalidLine {
+                line: i + 1,
+                expected: ENTRY,
+            };
+            // the columns are separated by tabs, so the map path can contain spaces
+            let columns = if line.contains('\t') {
+                line.split('\t').map(str::trim).collect::<Vec<_>>()
+            } else {
+                line.split_whitespace().collect()
+            };
+            let [bucket, map, width, height, start_x, start_y, goal_x, goal_y, optimal_length] =
+                columns[..]
+            else {
+                return Err(invalid());
+            };
+            let unit = |value: &str| value.parse::<Unit>().map_err(|_| invalid());
+
+            entries.push(ScenarioEntry {
+                bucket: bucket.parse().map_err(|_| invalid())?,
+                map: map.to_string(),
+                map_width: unit(width)?,
+                map_height: unit(height)?,
+                start: Pos {
+                    x: unit(start_x)?,
+                    y: unit(start_y)?,
+                },
+                goal: Pos {
+                    x: unit(goal_x)?,
+                    y: unit(goal_y)?,
+                },
+                optimal_length: optimal_length.parse().map_err(|_| invalid())?,
+            });
+        }
+        Ok(Self { entries })
+    }
+}
+
+impl Display for Scenario {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "version 1")?;
+        for entry in &self.entries {
+            writeln!(
+                f,
+                "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{:.8}",
+                entry.bucket,
+                entry.map,
+                entry.map_width,
+                entry.map_height,
+                entry.start.x,
+                entry.start.y,
+                entry.goal.x,
+                entry.goal.y,
+                entry.optimal_length
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,244 @@
+//! The sets of discovered positions which still have to be expanded.
+
+use crate::{Grid, Pos, Vec2d};
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, VecDeque};
+use std::fmt::Debug;
+
+/// The positions a search has discovered but not yet expanded.
+pub trait OpenSet: Clone + Debug {
+    /// Creates an empty set for positions of the grid.
+    fn new_open_set(grid: &Grid) -> Self;
+    /// Adds the position to the set.
+    /// If the position is already in the set, its priority is lowered if the new one is lower.
+    /// Lower priorities are popped first.
+    fn push(&mut self, pos: Pos, priority: f32);
+    /// Removes and returns the next position that should be expanded.
+    fn pop(&mut self) -> Option<Pos>;
+    /// Returns the priority the next popped position was pushed with.
+    fn peek_priority(&mut self) -> Option<f32>;
+    /// Returns whether the position is in the set.
+    fn contains(&self, pos: Pos) -> bool;
+}
+
+/// Pops positions in the order they were added, ignoring their priority.
+#[derive(Clone, Debug)]
+pub struct FifoOpenSet {
+    queue: VecDeque<(Pos, f32)>,
+    queued: Vec2d<bool>,
+}
+
+impl OpenSet for FifoOpenSet {
+    fn new_open_set(grid: &Grid) -> Self {
+        Self {
+            queue: VecDeque::new(),
+            queued: Vec2d::new(grid.width() as usize, grid.height() as usize, false),
+        }
+    }
+
+    fn push(&mut self, pos: Pos, priority: f32) {
+        if self.contains(pos) {
+            return;
+        }
+        self.queue.push_back((pos, priority));
+        self.queued.set(pos, true);
+    }
+
+    fn pop(&mut self) -> Option<Pos> {
+        let (pos, _) = self.queue.pop_front()?;
+        self.queued.set(pos, false);
+        Some(pos)
+    }
+
+    fn peek_priority(&mut self) -> Option<f32> {
+        self.queue.front().map(|(_, priority)| *priority)
+    }
+
+    fn contains(&self, pos: Pos) -> bool {
+        matches!(self.queued.get(pos), Some(true))
+    }
+}
+
+/// Pops the most recently added position first, ignoring its priority.
+#[derive(Clone, Debug)]
+pub struct LifoOpenSet {
+    stack: Vec<(Pos, f32)>,
+    queued: Vec2d<bool>,
+}
+
+impl OpenSet for LifoOpenSet {
+    fn new_open_set(grid: &Grid) -> Self {
+        Self {
+            stack: Vec::new(),
+            queued: Vec2d::new(grid.width() as usize, grid.height() as usize, false),
+        }
+    }
+
+    fn push(&mut self, pos: Pos, priority: f32) {
+        if self.contains(pos) {
+            return;
+        }
+        self.stack.push((pos, priority));
+        self.queued.set(pos, true);
+    }
+
+    fn pop(&mut self) -> Option<Pos> {
+        let (pos, _) = self.stack.pop()?;
+        self.queued.set(pos, false);
+        Some(pos)
+    }
+
+    fn peek_priority(&mut self) -> Option<f32> {
+        self.stack.last().map(|(_, priority)| *priority)
+    }
+
+    fn contains(&self, pos: Pos) -> bool {
+        matches!(self.queued.get(pos), Some(true))
+    }
+}
+
+/// Pops the position with the lowest priority first.
+/// Positions with the same priority are popped in the order they were added.
+/// Pushing a position which is already in the set only ever lowers its priority.
+#[derive(Clone, Debug)]
+pub struct PriorityOpenSet {
+    queue: PriorityQueue<f32>,
+}
+
+impl OpenSet for PriorityOpenSet {
+    fn new_open_set(grid: &Grid) -> Self {
+        Self {
+            queue: PriorityQueue::new(grid),
+        }
+    }
+
+    fn push(&mut self, pos: Pos, priority: f32) {
+        match self.queue.key(pos) {
+            Some(current) if current <= priority => {}
+            _ => self.queue.insert(pos, priority),
+        }
+    }
+
+    fn pop(&mut self) -> Option<Pos> {
+        self.queue.pop().map(|(pos, _)| pos)
+    }
+
+    fn peek_priority(&mut self) -> Option<f32> {
+        self.queue.peek().map(|(_, priority)| priority)
+    }
+
+    fn contains(&self, pos: Pos) -> bool {
+        self.queue.contains(pos)
+    }
+}
+
+/// An indexed priority queue over the positions of a grid, which pops the position with the lowest key first.
+/// Positions with the same key are popped in the order they were inserted.
+///
+/// The queue is backed by a binary heap and remembers the current key of every position.
+/// Changing or removing a key leaves the outdated heap entry behind, which is skipped once it reaches the top (lazy deletion).
+#[derive(Clone, Debug)]
+pub struct PriorityQueue<K> {
+    heap: BinaryHeap<PriorityEntry<K>>,
+    keys: Vec2d<Option<K>>,
+    inserted: u64,
+}
+
+impl<K> PriorityQueue<K>
+where
+    K: Copy + PartialOrd,
+{
+    /// Creates an empty queue for positions of the grid.
+    pub fn new(grid: &Grid) -> Self {
+        Self {
+            heap: BinaryHeap::new(),
+            keys: Vec2d::new(grid.width() as usize, grid.height() as usize, None),
+            inserted: 0,
+        }
+    }
+
+    /// Inserts the position or changes its key if it is already in the queue.
+    pub fn insert(&mut self, pos: Pos, key: K) {
+        if self.keys.get(pos).is_none() {
+            return;
+        }
+
+        self.keys.set(pos, Some(key));
+        self.heap.push(PriorityEntry {
+            pos,
+            key,
+            order: self.inserted,
+        });
+        self.inserted += 1;
+    }
+
+    /// Removes the position from the queue if it is in it.
+    pub fn remove(&mut self, pos: Pos) {
+        self.keys.set(pos, None);
+    }
+
+    /// Removes and returns the position with the lowest key.
+    /// Positions with equal keys are popped in the order they were inserted.
+    pub fn pop(&mut self) -> Option<(Pos, K)> {
+        self.remove_outdated();
+        let entry = self.heap.pop()?;
+
+        self.keys.set(entry.pos, None);
+        Some((entry.pos, entry.key))
+    }
+
+    /// Returns the position which would be popped next, without removing it.
+    pub fn peek(&mut self) -> Option<(Pos, K)> {
+        self.remove_outdated();
+        self.heap.peek().map(|entry| (entry.pos, entry.key))
+    }
+
+    /// Returns the key of the position if it is in the queue.
+    pub fn key(&self, pos: Pos) -> Option<K> {
+        self.keys.get(pos).copied().flatten()
+    }
+
+    /// Returns whether the position is in the queue.
+    pub fn contains(&self, pos: Pos) -> bool {
+        self.key(pos).is_some()
+    }
+
+    fn remove_outdated(&mut self) {
+        while let Some(entry) = self.heap.peek() {
+            if self.key(entry.pos) == Some(entry.key) {
+                return;
+            }
+            self.heap.pop();
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+struct PriorityEntry<K> {
+    pos: Pos,
+    key: K,
+    order: u64,
+}
+
+impl<K: PartialOrd> PartialEq for PriorityEntry<K> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+impl<K: PartialOrd> Eq for PriorityEntry<K> {}
+
+impl<K: PartialOrd> PartialOrd for PriorityEntry<K> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl<K: PartialOrd> Ord for PriorityEntry<K> {
+    // The BinaryHeap is a max heap, so the lowest key and the oldest entry have to compare as the greatest
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .key
+            .partial_cmp(&self.key)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| other.order.cmp(&self.order))
+    }
+}
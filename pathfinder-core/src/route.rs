@@ -0,0 +1,292 @@
+//! Searches on grids with waypoints or goals, which are put together from several searches of one algorithm.
+//!
+//! [PathFindAlgorithms::make_state] creates them on its own, so they rarely have to be created directly.
+
+use crate::heuristic::HeuristicOptions;
+use crate::iterative_deepening::DeepeningProgress;
+use crate::PathFindAlgorithmStepResult::{InProgress, NotFound};
+use crate::{Grid, PathFindAlgorithm, PathFindAlgorithmStepResult, PathFindAlgorithms, Pos, Vec2d};
+use std::iter::once;
+
+/// Searches the path from the start to the first waypoint, from there to the next waypoint and so on until the end.
+/// The legs are searched one after another and the last one stops at the closest of the end and the goals.
+pub struct WaypointSearch {
+    chain: SearchChain,
+    path: Vec<Pos>,
+}
+
+impl WaypointSearch {
+    /// Creates the search, running the algorithm once for every leg.
+    pub fn new(algorithm: PathFindAlgorithms, grid: Grid, heuristic: HeuristicOptions) -> Self {
+        let stops = once(grid.start())
+            .chain(grid.waypoints().iter().copied())
+            .chain(once(grid.end()))
+            .collect::<Vec<_>>();
+        let leg_count = stops.len() - 1;
+        let legs = stops
+            .windows(2)
+            .enumerate()
+            .map(|(i, stops)| {
+                let mut leg = grid.clone();
+                leg.clear_markers();
+                leg.set_start(stops[0]);
+                leg.set_end(stops[1]);
+                if i + 1 == leg_count {
+                    for goal in grid.goals() {
+                        leg.add_goal(*goal);
+                    }
+                }
+                leg
+            })
+            .collect();
+
+        Self {
+            chain: SearchChain::new(algorithm, heuristic, legs),
+            path: vec![],
+        }
+    }
+}
+
+impl PathFindAlgorithm for WaypointSearch {
+    fn next_step(&mut self) -> Result<Vec<Pos>, PathFindAlgorithmStepResult> {
+        let leg = self.chain.next_step()?;
+        // every leg starts where the one before ended
+        let skip = usize::from(!self.path.is_empty());
+        self.path.extend(leg.into_iter().skip(skip));
+
+        if self.chain.start_next() {
+            Err(InProgress)
+        } else {
+            Ok(std::mem::take(&mut self.path))
+        }
+    }
+
+    fn visited(&self, pos: Pos) -> bool {
+        self.chain.visited(pos)
+    }
+
+    fn visited_list(&self) -> &[Pos] {
+        self.chain.visited_list()
+    }
+
+    fn visited_from_end_list(&self) -> &[Pos] {
+        self.chain.visited_from_end_list()
+    }
+
+    fn expanded(&self) -> usize {
+        self.chain.expanded()
+    }
+
+    fn in_queue(&self, pos: Pos) -> bool {
+        self.chain.running().in_queue(pos)
+    }
+
+    fn is_any_angle(&self) -> bool {
+        self.chain.running().is_any_angle()
+    }
+
+    fn deepening_progress(&self) -> Option<DeepeningProgress> {
+        self.chain.running().deepening_progress()
+    }
+}
+
+/// Finds the path to the closest of the end and the goals for algorithms which can only search for a single end,
+/// see [PathFindAlgorithms::supports_goals].
+///
+/// It searches a path to every one of them in turn and keeps the cheapest,
+/// so it expands a lot more tiles than a search which stops at the first goal it reaches.
+pub struct NearestGoalSearch {
+    chain: SearchChain,
+    grid: Grid,
+    best: Option<(Vec<Pos>, f32)>,
+}
+
+impl NearestGoalSearch {
+    /// Creates the search, running the algorithm once for every target of the grid, see [Grid::targets].
+    pub fn new(algorithm: PathFindAlgorithms, grid: Grid, heuristic: HeuristicOptions) -> Self {
+        let searches = grid
+            .targets()
+            .into_iter()
+            .map(|target| {
+                let mut search = grid.clone();
+                search.clear_markers();
+                search.set_end(target);
+                search
+            })
+            .collect();
+
+        Self {
+            chain: SearchChain::new(algorithm, heuristic, searches),
+            grid,
+            best: None,
+        }
+    }
+}
+
+impl PathFindAlgorithm for NearestGoalSearch {
+    fn next_step(&mut self) -> Result<Vec<Pos>, PathFindAlgorithmStepResult> {
+        match self.chain.next_step() {
+            Ok(path) => {
+                let cost = self.chain.path_cost(&self.grid, &path);
+                if self.best.as_ref().is_none_or(|(_, best)| cost < *best) {
+                    self.best = Some((path, cost));
+                }
+            }
+            Err(InProgress) => return Err(InProgress),
+            Err(NotFound) => {}
+        }
+
+        if self.chain.start_next() {
+            Err(InProgress)
+        } else {
+            self.best.take().map(|(path, _)| path).ok_or(NotFound)
+        }
+    }
+
+    fn visited(&self, pos: Pos) -> bool {
+        self.chain.visited(pos)
+    }
+
+    fn visited_list(&self) -> &[Pos] {
+        self.chain.visited_list()
+    }
+
+    fn visited_from_end_list(&self) -> &[Pos] {
+        self.chain.visited_from_end_list()
+    }
+
+    fn expanded(&self) -> usize {
+        self.chain.expanded()
+    }
+
+    fn in_queue(&self, pos: Pos) -> bool {
+        self.chain.running().in_queue(pos)
+    }
+
+    fn is_any_angle(&self) -> bool {
+        self.chain.running().is_any_angle()
+    }
+
+    fn deepening_progress(&self) -> Option<DeepeningProgress> {
+        self.chain.running().deepening_progress()
+    }
+}
+
+/// Runs searches of one algorithm one after another and collects the tiles they visited.
+pub(crate) struct SearchChain {
+    algorithm: PathFindAlgorithms,
+    heuristic: HeuristicOptions,
+    grids: std::vec::IntoIter<Grid>,
+    running: Box<dyn PathFindAlgorithm>,
+    visited: VisitedTrail,
+    visited_from_end: VisitedTrail,
+    finished_visited: Vec2d<bool>,
+    finished_expanded: usize,
+}
+
+impl SearchChain {
+    /// Starts the search on the first grid, there has to be at least one.
+    pub(crate) fn new(
+        algorithm: PathFindAlgorithms,
+        heuristic: HeuristicOptions,
+        grids: Vec<Grid>,
+    ) -> Self {
+        let mut grids = grids.into_iter();
+        let first = grids.next().expect("a chain needs at least one search");
+        let (width, height) = (first.width() as usize, first.height() as usize);
+
+        Self {
+            running: algorithm.make_state(first, heuristic),
+            algorithm,
+            heuristic,
+            grids,
+            visited: VisitedTrail::default(),
+            visited_from_end: VisitedTrail::default(),
+            finished_visited: Vec2d::new(width, height, false),
+            finished_expanded: 0,
+        }
+    }
+
+    /// Does one step of the running search.
+    /// Once it finished, the next search has to be started with [SearchChain::start_next].
+    pub(crate) fn next_step(&mut self) -> Result<Vec<Pos>, PathFindAlgorithmStepResult> {
+        let result = self.running.next_step();
+        self.visited.update(self.running.visited_list());
+        self.visited_from_end
+            .update(self.running.visited_from_end_list());
+
+        if result != Err(InProgress) {
+            for trail in [&mut self.visited, &mut self.visited_from_end] {
+                for pos in &trail.list[trail.finished..] {
+                    self.finished_visited.set(*pos, true);
+                }
+                trail.finished = trail.list.len();
+            }
+        }
+        result
+    }
+
+    /// Starts the search on the next grid, returns false if there is none left.
+    pub(crate) fn start_next(&mut self) -> bool {
+        match self.grids.next() {
+            Some(grid) => {
+                self.finished_expanded += self.running.expanded();
+                self.running = self.algorithm.make_state(grid, self.heuristic);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the cost of a path found by the running search on the grid.
+    /// Paths the grid doesn't allow cost infinitely much.
+    pub(crate) fn path_cost(&self, grid: &Grid, path: &[Pos]) -> f32 {
+        if self.running.is_any_angle() {
+            grid.any_angle_path_cost(path)
+        } else {
+            grid.path_cost(path)
+        }
+        .unwrap_or(f32::INFINITY)
+    }
+
+    pub(crate) fn running(&self) -> &dyn PathFindAlgorithm {
+        self.running.as_ref()
+    }
+
+    pub(crate) fn visited(&self, pos: Pos) -> bool {
+        matches!(self.finished_visited.get(pos), Some(true)) || self.running.visited(pos)
+    }
+
+    pub(crate) fn visited_list(&self) -> &[Pos] {
+        &self.visited.list
+    }
+
+    pub(crate) fn visited_from_end_list(&self) -> &[Pos] {
+        &self.visited_from_end.list
+    }
+
+    /// Returns the expansions of the finished searches and the running one.
+    pub(crate) fn expanded(&self) -> usize {
+        self.finished_expanded + self.running.expanded()
+    }
+}
+
+/// The visited tiles of the finished searches followed by the ones of the running search.
+#[derive(Default)]
+struct VisitedTrail {
+    list: Vec<Pos>,
+    finished: usize,
+}
+
+impl VisitedTrail {
+    /// Copies the tiles the running search visited since the last update.
+    fn update(&mut self, running: &[Pos]) {
+        let copied = &self.list[self.finished..];
+        // iterative deepening starts its list over with every iteration
+        if running.len() < copied.len() || running.first() != copied.first() {
+            self.list.truncate(self.finished);
+        }
+        let copied = self.list.len() - self.finished;
+        self.list.extend_from_slice(&running[copied..]);
+    }
+}
@@ -0,0 +1,57 @@
+//! The outcome of a whole search, so it can be compared, reported or recorded with the json module.
+
+use crate::{Grid, PathFindAlgorithm, PathFindAlgorithmStepResult, Pos};
+
+/// What a search found and how much work it took.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SearchResult {
+    /// The path from the start to the end, None if the end can't be reached.
+    pub path: Option<Vec<Pos>>,
+    /// The visited tiles in the order they were visited.
+    pub visited: Vec<Pos>,
+    /// The tiles visited by the half of a bidirectional search which starts at the end.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Vec::is_empty")
+    )]
+    pub visited_from_end: Vec<Pos>,
+    /// The cost of the path, see [Grid::path_cost] and [Grid::any_angle_path_cost].
+    pub cost: Option<f32>,
+    /// How often the search expanded a tile, see [PathFindAlgorithm::expanded].
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub expanded: usize,
+    /// How often the search was stepped.
+    pub steps: usize,
+}
+
+impl SearchResult {
+    /// Steps the search on the grid until it found a path or gave up.
+    pub fn run(search: &mut dyn PathFindAlgorithm, grid: &Grid) -> Self {
+        let mut steps = 0;
+        let path = loop {
+            steps += 1;
+            match search.next_step() {
+                Ok(path) => break Some(path),
+                Err(PathFindAlgorithmStepResult::NotFound) => break None,
+                Err(PathFindAlgorithmStepResult::InProgress) => {}
+            }
+        };
+
+        let cost = path.as_ref().and_then(|path| {
+            if search.is_any_angle() {
+                grid.any_angle_path_cost(path)
+            } else {
+                grid.path_cost(path)
+            }
+        });
+        Self {
+            path,
+            visited: search.visited_list().to_vec(),
+            visited_from_end: search.visited_from_end_list().to_vec(),
+            cost,
+            expanded: search.expanded(),
+            steps,
+        }
+    }
+}
@@ -0,0 +1,1434 @@
+use crate::compact::{decode_grid, encode_grid, DecodeGridError};
+use crate::generators::terrain::{NoiseMapping, TerrainGenerators, TerrainOptions};
+use crate::generators::{connect, MazeGenerators, Rng};
+use crate::heuristic::HeuristicOptions;
+use crate::movingai::{MovingAiError, MovingAiMap, Scenario};
+use crate::text::ParseGridError;
+use crate::tour::{held_karp, nearest_neighbor, route_cost, two_opt, TourSearch};
+use crate::PathFindAlgorithmStepResult::NotFound;
+use crate::{Direction, Grid, Movement, PathFindAlgorithm, PathFindAlgorithms, Pos, Tile, Vec2d};
+
+const OPTIMAL_ALGORITHMS: [PathFindAlgorithms; 5] = [
+    PathFindAlgorithms::Dijkstra,
+    PathFindAlgorithms::AStar,
+    PathFindAlgorithms::BidirectionalDijkstra,
+    PathFindAlgorithms::BidirectionalAStar,
+    PathFindAlgorithms::LpaStar,
+];
+
+fn find_path(algorithm: PathFindAlgorithms, grid: &Grid) -> Option<Vec<Pos>> {
+    find_path_with_heuristic(algorithm, grid, HeuristicOptions::default())
+}
+
+fn find_path_with_heuristic(
+    algorithm: PathFindAlgorithms,
+    grid: &Grid,
+    heuristic: HeuristicOptions,
+) -> Option<Vec<Pos>> {
+    let mut state = algorithm.make_state(grid.clone(), heuristic);
+    run_to_end(state.as_mut())
+}
+
+fn run_to_end(state: &mut dyn PathFindAlgorithm) -> Option<Vec<Pos>> {
+    loop {
+        match state.next_step() {
+            Ok(path) => return Some(path),
+            Err(NotFound) => return None,
+            Err(_) => {}
+        }
+    }
+}
+
+/// Calculates the cost of the shortest path with Bellman-Ford, which is slow but obviously correct
+fn shortest_path_cost(grid: &Grid) -> Option<f32> {
+    let mut distances = Vec2d::new(grid.width() as usize, grid.height() as usize, f32::INFINITY);
+    distances.set(grid.start(), 0.0);
+
+    let positions = (0..grid.height())
+        .flat_map(|y| (0..grid.width()).map(move |x| Pos { x, y }))
+        .collect::<Vec<_>>();
+    loop {
+        let mut changed = false;
+        for pos in &positions {
+            let distance = *distances.get(*pos).unwrap();
+            for (neighbor, cost) in grid.neighbors(*pos) {
+                if distance + cost < *distances.get(neighbor).unwrap() - 1e-4 {
+                    distances.set(neighbor, distance + cost);
+                    changed = true;
+                }
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+
+    Some(*distances.get(grid.end()).unwrap()).filter(|distance| distance.is_finite())
+}
+
+fn assert_valid_path(grid: &Grid, path: &[Pos]) {
+    assert_eq!(path.first(), Some(&grid.start()));
+    assert_eq!(path.last(), Some(&grid.end()));
+    assert!(
+        grid.path_cost(path).is_some(),
+        "path contains an invalid step: {path:?}"
+    );
+}
+
+fn assert_optimal(grid: &Grid) {
+    let expected = shortest_path_cost(grid);
+
+    for algorithm in OPTIMAL_ALGORITHMS {
+        let path = find_path(algorithm, grid);
+        match (expected, path) {
+            (Some(expected), Some(path)) => {
+                assert_valid_path(grid, &path);
+                let cost = grid.path_cost(&path).unwrap();
+                assert!(
+                    (cost - expected).abs() < 1e-3,
+                    "{algorithm:?} found a path costing {cost} instead of {expected}"
+                );
+            }
+            (None, None) => {}
+            (expected, path) => {
+                panic!("{algorithm:?} found {path:?} but the shortest path costs {expected:?}")
+            }
+        }
+    }
+}
+
+/// Creates a grid with random walls and weights from a simple linear congruential generator
+fn random_grid(seed: u64, width: i32, height: i32, movement: Movement) -> Grid {
+    let mut state = seed;
+    let mut next = move || {
+        state = state
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (state >> 33) as u32
+    };
+
+    let mut grid = Grid::new(
+        width,
+        height,
+        Pos { x: 0, y: 0 },
+        Pos {
+            x: width - 1,
+            y: height - 1,
+        },
+    );
+    grid.set_movement(movement);
+    for y in 0..height {
+        for x in 0..width {
+            let tile = match next() % 10 {
+                0 | 1 => Tile::Wall,
+                2..=5 => Tile::Cost((next() % Tile::MAX_COST as u32) as u8 + 1),
+                _ => Tile::None,
+            };
+            grid.set_tile(Pos { x, y }, tile);
+        }
+    }
+    grid
+}
+
+#[test]
+fn takes_detour_around_expensive_tiles() {
+    let grid = "
+        S99E
+        ....
+    "
+    .parse::<Grid>()
+    .unwrap();
+
+    for algorithm in OPTIMAL_ALGORITHMS {
+        let path = find_path(algorithm, &grid).unwrap();
+        assert_eq!(grid.path_cost(&path), Some(5.0), "{algorithm:?}");
+    }
+}
+
+#[test]
+fn reparents_when_cheaper_route_is_found_later() {
+    // The expensive tile is first discovered diagonally from (0, 1),
+    // but the straight step from (1, 1) is cheaper and has to replace that parent.
+    let mut grid = "
+        S.
+        ..
+        #9
+        #E
+    "
+    .parse::<Grid>()
+    .unwrap();
+    grid.set_movement(Movement::EightWay);
+
+    assert_optimal(&grid);
+    for algorithm in OPTIMAL_ALGORITHMS {
+        let path = find_path(algorithm, &grid).unwrap();
+        assert_eq!(path[1], Pos { x: 1, y: 1 }, "{algorithm:?}");
+    }
+}
+
+#[test]
+fn diagonal_moves_cost_more_than_straight_moves() {
+    let mut grid = Grid::new(5, 5, Pos { x: 0, y: 0 }, Pos { x: 4, y: 2 });
+    grid.set_movement(Movement::EightWay);
+
+    for algorithm in OPTIMAL_ALGORITHMS {
+        let path = find_path(algorithm, &grid).unwrap();
+        let cost = grid.path_cost(&path).unwrap();
+        assert!((cost - (2.0 + 2.0 * std::f32::consts::SQRT_2)).abs() < 1e-4);
+    }
+}
+
+#[test]
+fn does_not_cut_corners() {
+    let mut grid = "
+        S#
+        #E
+    "
+    .parse::<Grid>()
+    .unwrap();
+
+    grid.set_movement(Movement::EightWay);
+    for algorithm in OPTIMAL_ALGORITHMS {
+        assert!(find_path(algorithm, &grid).is_some());
+    }
+    grid.set_movement(Movement::EightWayNoCornerCutting);
+    for algorithm in OPTIMAL_ALGORITHMS {
+        assert!(find_path(algorithm, &grid).is_none());
+    }
+}
+
+#[test]
+fn finds_optimal_paths_on_random_weighted_grids() {
+    let movements = [
+        Movement::FourWay,
+        Movement::EightWay,
+        Movement::EightWayNoCornerCutting,
+        Movement::Hex,
+    ];
+    for seed in 0..30 {
+        for movement in movements {
+            assert_optimal(&random_grid(seed, 12, 9, movement));
+        }
+    }
+}
+
+#[test]
+fn breadth_and_depth_first_find_valid_paths() {
+    for seed in 0..10 {
+        let grid = random_grid(seed, 10, 10, Movement::FourWay);
+        let reachable = shortest_path_cost(&grid).is_some();
+
+        for algorithm in [
+            PathFindAlgorithms::BreadthFirst,
+            PathFindAlgorithms::DepthFirst,
+            PathFindAlgorithms::BidirectionalBreadthFirst,
+        ] {
+            let path = find_path(algorithm, &grid);
+            assert_eq!(path.is_some(), reachable, "{algorithm:?}");
+            if let Some(path) = path {
+                assert_valid_path(&grid, &path);
+            }
+        }
+    }
+}
+
+#[test]
+fn bidirectional_search_visits_fewer_tiles() {
+    let grid = Grid::new(21, 21, Pos { x: 0, y: 10 }, Pos { x: 20, y: 10 });
+
+    let visited_count = |algorithm: PathFindAlgorithms| {
+        let mut state = algorithm.make_state(grid.clone(), HeuristicOptions::default());
+        assert!(run_to_end(state.as_mut()).is_some());
+        state.visited_list().len() + state.visited_from_end_list().len()
+    };
+
+    assert!(
+        visited_count(PathFindAlgorithms::BidirectionalBreadthFirst)
+            < visited_count(PathFindAlgorithms::BreadthFirst)
+    );
+    assert!(
+        visited_count(PathFindAlgorithms::BidirectionalDijkstra)
+            < visited_count(PathFindAlgorithms::Dijkstra)
+    );
+}
+
+#[test]
+fn expanded_counts_every_expansion() {
+    // a corridor, so every search expands each tile on the way to the end once per iteration
+    let grid = Grid::new(5, 1, Pos { x: 0, y: 0 }, Pos { x: 4, y: 0 });
+
+    for algorithm in [PathFindAlgorithms::Dijkstra, PathFindAlgorithms::ThetaStar] {
+        let mut state = algorithm.make_state(grid.clone(), HeuristicOptions::default());
+        assert!(run_to_end(state.as_mut()).is_some());
+        assert_eq!(state.expanded(), 5, "{algorithm:?}");
+    }
+
+    // later iterations expand the tiles of the earlier ones again
+    let mut state = PathFindAlgorithms::IterativeDeepeningDepthFirst
+        .make_state(grid.clone(), HeuristicOptions::default());
+    assert!(run_to_end(state.as_mut()).is_some());
+    assert_eq!(state.expanded(), 1 + 2 + 3 + 4 + 5);
+    assert_eq!(state.visited_list().len(), 5);
+}
+
+#[test]
+fn jump_point_search_finds_optimal_paths_on_uniform_grids() {
+    for seed in 0..40 {
+        let mut grid = random_grid(seed, 14, 11, Movement::EightWayNoCornerCutting);
+        for y in 0..grid.height() {
+            for x in 0..grid.width() {
+                if let Tile::Cost(_) = grid.tile(Pos { x, y }) {
+                    grid.set_tile(Pos { x, y }, Tile::None);
+                }
+            }
+        }
+        let expected = shortest_path_cost(&grid);
+
+        for algorithm in [
+            PathFindAlgorithms::JumpPointSearch,
+            PathFindAlgorithms::JumpPointSearchPlus,
+        ] {
+            let path = find_path(algorithm, &grid);
+            assert_eq!(path.is_some(), expected.is_some(), "{algorithm:?} {seed}");
+            if let (Some(path), Some(expected)) = (path, expected) {
+                assert_valid_path(&grid, &path);
+                let cost = grid.path_cost(&path).unwrap();
+                assert!(
+                    (cost - expected).abs() < 1e-3,
+                    "{algorithm:?} found a path costing {cost} instead of {expected} on seed {seed}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn admissible_heuristics_keep_a_star_optimal() {
+    use crate::heuristic::Heuristics;
+
+    for seed in 0..10 {
+        let grid = random_grid(seed, 12, 9, Movement::EightWay);
+        let expected = shortest_path_cost(&grid);
+
+        for heuristic in [
+            Heuristics::Auto,
+            Heuristics::Euclidean,
+            Heuristics::Chebyshev,
+            Heuristics::Octile,
+            Heuristics::Zero,
+        ] {
+            let options = HeuristicOptions {
+                heuristic,
+                weight: 1.0,
+            };
+            let path = find_path_with_heuristic(PathFindAlgorithms::AStar, &grid, options);
+            let cost = path.map(|path| grid.path_cost(&path).unwrap());
+            match (cost, expected) {
+                (Some(cost), Some(expected)) => assert!((cost - expected).abs() < 1e-3),
+                (cost, expected) => assert_eq!(cost, expected),
+            }
+        }
+    }
+}
+
+#[test]
+fn hex_tiles_have_six_neighbors() {
+    use crate::heuristic::{Heuristic, Hex};
+
+    let mut grid = Grid::new(7, 7, Pos { x: 3, y: 3 }, Pos { x: 0, y: 0 });
+    grid.set_movement(Movement::Hex);
+    let neighbors = |pos: Pos| {
+        let mut neighbors = grid
+            .neighbors(pos)
+            .into_iter()
+            .map(|(neighbor, _)| (neighbor.x, neighbor.y))
+            .collect::<Vec<_>>();
+        neighbors.sort();
+        neighbors
+    };
+    // odd rows are shifted to the right, so their diagonal neighbors are the ones to the right
+    assert_eq!(
+        neighbors(Pos { x: 3, y: 3 }),
+        [(2, 3), (3, 2), (3, 4), (4, 2), (4, 3), (4, 4)]
+    );
+    assert_eq!(
+        neighbors(Pos { x: 3, y: 2 }),
+        [(2, 1), (2, 2), (2, 3), (3, 1), (3, 3), (4, 2)]
+    );
+    assert_eq!(grid.step_cost(Pos { x: 3, y: 2 }, Pos { x: 4, y: 3 }), None);
+
+    // on an empty grid the hex distance is exactly the number of steps
+    for y in 0..grid.height() {
+        for x in 0..grid.width() {
+            let mut grid = grid.clone();
+            grid.set_end(Pos { x, y });
+            let steps = find_path(PathFindAlgorithms::BreadthFirst, &grid)
+                .unwrap()
+                .len()
+                - 1;
+            assert_eq!(Hex.estimate(grid.start(), grid.end()), steps as f32);
+        }
+    }
+
+    for seed in 0..10 {
+        let grid = random_grid(seed, 11, 8, Movement::Hex);
+        let expected = shortest_path_cost(&grid);
+        let path = find_path(PathFindAlgorithms::IdaStar, &grid);
+        assert_eq!(path.is_some(), expected.is_some());
+        if let (Some(path), Some(expected)) = (path, expected) {
+            assert!((grid.path_cost(&path).unwrap() - expected).abs() < 1e-3);
+        }
+    }
+}
+
+#[test]
+fn wrapping_grids_connect_opposite_edges() {
+    let mut grid = "S#E".parse::<Grid>().unwrap();
+    assert_eq!(find_path(PathFindAlgorithms::AStar, &grid), None);
+    grid.set_wraps(true);
+    assert_eq!(
+        grid.step_offset(grid.start(), grid.end()),
+        Pos { x: -1, y: 0 }
+    );
+    assert!(grid.crosses_edge(grid.start(), grid.end()));
+    for algorithm in OPTIMAL_ALGORITHMS {
+        let path = find_path(algorithm, &grid).unwrap();
+        assert_eq!(path, [grid.start(), grid.end()], "{algorithm:?}");
+    }
+
+    // hex grids with an odd number of rows only wrap horizontally
+    let mut grid = random_grid(1, 6, 5, Movement::Hex);
+    grid.set_wraps(true);
+    assert_eq!(grid.wrapping_axes(), (true, false));
+    assert_eq!(grid.wrap(Pos { x: -1, y: 5 }), Pos { x: 5, y: 5 });
+
+    for seed in 0..15 {
+        for movement in [
+            Movement::FourWay,
+            Movement::EightWayNoCornerCutting,
+            Movement::Hex,
+        ] {
+            let mut grid = random_grid(seed, 11, 8, movement);
+            grid.set_wraps(true);
+            grid.set_end(Pos { x: 9, y: 6 });
+            assert_optimal(&grid);
+
+            let expected = shortest_path_cost(&grid);
+            let path = find_path(PathFindAlgorithms::IdaStar, &grid);
+            assert_eq!(path.is_some(), expected.is_some());
+            if let (Some(path), Some(expected)) = (path, expected) {
+                assert!((grid.path_cost(&path).unwrap() - expected).abs() < 1e-3);
+            }
+        }
+
+        // searches which don't wrap still find paths which are valid on the wrapping grid
+        let mut grid = random_grid(seed, 11, 8, Movement::EightWayNoCornerCutting);
+        grid.set_wraps(true);
+        if let Some(path) = find_path(PathFindAlgorithms::JumpPointSearch, &grid) {
+            assert!(grid.path_cost(&path).is_some());
+        }
+        if let Some(path) = find_path(PathFindAlgorithms::ThetaStar, &grid) {
+            assert!(grid.any_angle_path_cost(&path).is_some());
+        }
+    }
+}
+
+#[test]
+fn greedy_best_first_finds_valid_paths() {
+    for seed in 0..10 {
+        let grid = random_grid(seed, 10, 10, Movement::EightWay);
+
+        let path = find_path(PathFindAlgorithms::GreedyBestFirst, &grid);
+        assert_eq!(path.is_some(), shortest_path_cost(&grid).is_some());
+        if let Some(path) = path {
+            assert_valid_path(&grid, &path);
+        }
+    }
+}
+
+#[test]
+fn lpa_star_repairs_path_after_grid_changes() {
+    for (movement, wraps) in [
+        (Movement::FourWay, false),
+        (Movement::EightWayNoCornerCutting, false),
+        (Movement::EightWayNoCornerCutting, true),
+    ] {
+        for seed in 0..20 {
+            let mut grid = random_grid(seed, 12, 9, movement);
+            grid.set_wraps(wraps);
+            let mut state =
+                PathFindAlgorithms::LpaStar.make_state(grid.clone(), HeuristicOptions::default());
+            run_to_end(state.as_mut());
+
+            let edits = random_grid(seed + 1000, 12, 9, movement);
+            let changes = (0..grid.height())
+                .flat_map(|y| (0..grid.width()).map(move |x| Pos { x, y }))
+                .filter(|pos| edits.tile(*pos) != grid.tile(*pos))
+                .collect::<Vec<_>>();
+            for (i, pos) in changes.into_iter().enumerate() {
+                grid.set_tile(pos, edits.tile(pos));
+                assert!(state.grid_changed(&grid, &[pos]));
+
+                // repair after every few edits, so changes also pile up between repairs
+                if i % 3 == 0 {
+                    let path = run_to_end(state.as_mut());
+                    match (shortest_path_cost(&grid), path) {
+                        (Some(expected), Some(path)) => {
+                            assert_valid_path(&grid, &path);
+                            let cost = grid.path_cost(&path).unwrap();
+                            assert!((cost - expected).abs() < 1e-3, "repaired path costs {cost} instead of {expected}");
+                        }
+                        (None, None) => {}
+                        (expected, path) => panic!("repaired search found {path:?} but the shortest path costs {expected:?}"),
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn lpa_star_only_expands_affected_tiles_after_a_change() {
+    let mut grid = Grid::new(20, 20, Pos { x: 0, y: 0 }, Pos { x: 19, y: 0 });
+    let mut state =
+        PathFindAlgorithms::LpaStar.make_state(grid.clone(), HeuristicOptions::default());
+    run_to_end(state.as_mut());
+
+    // a wall far away from the path doesn't change anything
+    grid.set_tile(Pos { x: 10, y: 18 }, Tile::Wall);
+    assert!(state.grid_changed(&grid, &[Pos { x: 10, y: 18 }]));
+    assert!(run_to_end(state.as_mut()).is_some());
+    assert!(state.reexpanded_list().is_empty());
+
+    // a wall on the path has to be walked around
+    grid.set_tile(Pos { x: 10, y: 0 }, Tile::Wall);
+    assert!(state.grid_changed(&grid, &[Pos { x: 10, y: 0 }]));
+    let path = run_to_end(state.as_mut()).unwrap();
+    assert_eq!(grid.path_cost(&path), Some(21.0));
+    assert!(!state.reexpanded_list().is_empty());
+
+    // moving the end can't be repaired
+    grid.set_end(Pos { x: 19, y: 19 });
+    assert!(!state.grid_changed(&grid, &[Pos { x: 19, y: 19 }]));
+}
+
+#[test]
+fn ida_star_finds_optimal_paths() {
+    // every iteration only raises the threshold to the next higher path cost, which takes long on big weighted grids
+    for seed in 0..30 {
+        for movement in [Movement::FourWay, Movement::EightWayNoCornerCutting] {
+            let grid = random_grid(seed, 7, 6, movement);
+            let path = find_path(PathFindAlgorithms::IdaStar, &grid);
+            match (shortest_path_cost(&grid), path) {
+                (Some(expected), Some(path)) => {
+                    assert_valid_path(&grid, &path);
+                    let cost = grid.path_cost(&path).unwrap();
+                    assert!(
+                        (cost - expected).abs() < 1e-3,
+                        "IDA* found a path costing {cost} instead of {expected}"
+                    );
+                }
+                (None, None) => {}
+                (expected, path) => {
+                    panic!("IDA* found {path:?} but the shortest path costs {expected:?}")
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn iterative_deepening_depth_first_finds_paths_with_fewest_steps() {
+    for movement in [Movement::FourWay, Movement::EightWay] {
+        for seed in 0..20 {
+            let grid = random_grid(seed, 12, 9, movement);
+            let breadth_first = find_path(PathFindAlgorithms::BreadthFirst, &grid);
+            let iterative_deepening =
+                find_path(PathFindAlgorithms::IterativeDeepeningDepthFirst, &grid);
+
+            if let Some(path) = &iterative_deepening {
+                assert_valid_path(&grid, path);
+            }
+            assert_eq!(
+                breadth_first.map(|path| path.len()),
+                iterative_deepening.map(|path| path.len())
+            );
+        }
+    }
+}
+
+#[test]
+fn iterative_deepening_raises_threshold_until_end_is_reached() {
+    let mut grid = Grid::new(5, 5, Pos { x: 0, y: 0 }, Pos { x: 4, y: 0 });
+    for y in 0..4 {
+        grid.set_tile(Pos { x: 2, y }, Tile::Wall);
+    }
+
+    let mut state =
+        PathFindAlgorithms::IdaStar.make_state(grid.clone(), HeuristicOptions::default());
+    let mut thresholds = vec![state.deepening_progress().unwrap().threshold];
+    let path = loop {
+        match state.next_step() {
+            Ok(path) => break path,
+            Err(NotFound) => panic!("no path found"),
+            Err(_) => {}
+        }
+        let progress = state.deepening_progress().unwrap();
+        if progress.iteration > thresholds.len() {
+            thresholds.push(progress.threshold);
+        }
+    };
+
+    // the wall forces a detour of 8 steps, which the manhattan distance of 4 has to grow into
+    assert_eq!(grid.path_cost(&path), Some(12.0));
+    assert_eq!(thresholds, vec![4.0, 6.0, 8.0, 10.0, 12.0]);
+}
+
+#[test]
+fn theta_star_walks_straight_lines_on_open_grids() {
+    let grid = Grid::new(10, 4, Pos { x: 0, y: 0 }, Pos { x: 9, y: 3 });
+
+    for algorithm in [
+        PathFindAlgorithms::ThetaStar,
+        PathFindAlgorithms::LazyThetaStar,
+    ] {
+        let path = find_path(algorithm, &grid).unwrap();
+        assert_eq!(path, vec![grid.start(), grid.end()], "{algorithm:?}");
+    }
+}
+
+#[test]
+fn theta_star_paths_are_in_line_of_sight_and_shorter_than_grid_paths() {
+    for seed in 0..60 {
+        let mut grid = random_grid(seed, 14, 10, Movement::EightWayNoCornerCutting);
+        // any angle searches ignore the cost of tiles
+        for y in 0..grid.height() {
+            for x in 0..grid.width() {
+                if let Tile::Cost(_) = grid.tile(Pos { x, y }) {
+                    grid.set_tile(Pos { x, y }, Tile::None);
+                }
+            }
+        }
+        let grid_path_cost = shortest_path_cost(&grid);
+
+        for algorithm in [
+            PathFindAlgorithms::ThetaStar,
+            PathFindAlgorithms::LazyThetaStar,
+        ] {
+            let path = find_path(algorithm, &grid);
+            assert_eq!(path.is_some(), grid_path_cost.is_some(), "{algorithm:?}");
+            if let (Some(path), Some(grid_path_cost)) = (path, grid_path_cost) {
+                assert_eq!(path.first(), Some(&grid.start()));
+                assert_eq!(path.last(), Some(&grid.end()));
+                let cost = grid
+                    .any_angle_path_cost(&path)
+                    .unwrap_or_else(|| panic!("{algorithm:?} path is blocked: {path:?}"));
+                assert!(
+                    cost <= grid_path_cost + 1e-3,
+                    "{algorithm:?} path costs {cost}, more than {grid_path_cost}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn line_of_sight_is_blocked_by_walls_and_corners() {
+    // S . .
+    // . # .
+    // . . E
+    let mut grid = Grid::new(3, 3, Pos { x: 0, y: 0 }, Pos { x: 2, y: 2 });
+    assert!(grid.line_of_sight(Pos { x: 0, y: 0 }, Pos { x: 2, y: 2 }));
+    assert_eq!(
+        grid.line(Pos { x: 0, y: 0 }, Pos { x: 2, y: 1 }),
+        vec![
+            Pos { x: 0, y: 0 },
+            Pos { x: 1, y: 0 },
+            Pos { x: 1, y: 1 },
+            Pos { x: 2, y: 1 }
+        ]
+    );
+
+    grid.set_tile(Pos { x: 1, y: 1 }, Tile::Wall);
+    assert!(!grid.line_of_sight(Pos { x: 0, y: 0 }, Pos { x: 2, y: 2 }));
+    assert!(!grid.line_of_sight(Pos { x: 0, y: 0 }, Pos { x: 2, y: 1 }));
+    // the line from (0, 1) to (1, 2) passes the corner of the wall
+    assert!(!grid.line_of_sight(Pos { x: 0, y: 1 }, Pos { x: 1, y: 2 }));
+    assert!(grid.line_of_sight(Pos { x: 0, y: 2 }, Pos { x: 2, y: 2 }));
+}
+
+#[test]
+fn finds_optimal_paths_of_movingai_scenarios() {
+    let benchmarks = [
+        (
+            include_str!("../benchmarks/maze-25-25.map"),
+            include_str!("../benchmarks/maze-25-25.map.scen"),
+        ),
+        (
+            include_str!("../benchmarks/random-32-32-20.map"),
+            include_str!("../benchmarks/random-32-32-20.map.scen"),
+        ),
+        (
+            include_str!("../benchmarks/room-33-33.map"),
+            include_str!("../benchmarks/room-33-33.map.scen"),
+        ),
+    ];
+
+    for (map, scenario) in benchmarks {
+        let map = map.parse::<MovingAiMap>().unwrap();
+        let scenario = scenario.parse::<Scenario>().unwrap();
+        assert!(!scenario.entries.is_empty());
+
+        for entry in &scenario.entries {
+            assert_eq!(
+                (entry.map_width, entry.map_height),
+                (map.width(), map.height())
+            );
+            let grid = entry.to_grid(&map);
+            let algorithms = OPTIMAL_ALGORITHMS
+                .into_iter()
+                .chain([PathFindAlgorithms::JumpPointSearch]);
+            for algorithm in algorithms {
+                let path = find_path(algorithm, &grid).unwrap();
+                assert_valid_path(&grid, &path);
+                let cost = grid.path_cost(&path).unwrap() as f64;
+                assert!(
+                    (cost - entry.optimal_length).abs() < 1e-3,
+                    "{algorithm:?} found a path costing {cost} instead of {} on {entry:?}",
+                    entry.optimal_length
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn movingai_files_can_be_written_and_read_again() {
+    let grid = random_grid(3, 12, 7, Movement::EightWayNoCornerCutting);
+    let map = MovingAiMap::from_grid(&grid);
+    assert_eq!(map.to_string().parse::<MovingAiMap>(), Ok(map.clone()));
+    assert_eq!(
+        map.to_grid(grid.start(), grid.end()).movement(),
+        Movement::EightWayNoCornerCutting
+    );
+
+    let scenario = include_str!("../benchmarks/room-33-33.map.scen")
+        .parse::<Scenario>()
+        .unwrap();
+    assert_eq!(scenario.to_string().parse::<Scenario>(), Ok(scenario));
+
+    assert_eq!(
+        "type octile\nheight 1\nwidth 2\nmap\n.X\n".parse::<MovingAiMap>(),
+        Err(MovingAiError::InvalidTile {
+            pos: Pos { x: 1, y: 0 },
+            character: 'X'
+        })
+    );
+    assert_eq!(
+        "type octile\nheight 2\nwidth 2\nmap\n..\n".parse::<MovingAiMap>(),
+        Err(MovingAiError::WrongSize {
+            width: 2,
+            height: 2
+        })
+    );
+    assert_eq!(
+        "type octile\nwidth 2\nheight 1\nmap\n..\n".parse::<MovingAiMap>(),
+        Err(MovingAiError::InvalidLine {
+            line: 2,
+            expected: "'height <rows>'"
+        })
+    );
+}
+
+#[test]
+fn grids_can_be_written_as_text_and_read_again() {
+    for seed in 0..10 {
+        let grid = random_grid(seed, 9, 6, Movement::FourWay);
+        assert_eq!(grid.to_string().parse::<Grid>(), Ok(grid));
+    }
+
+    // waypoints are ordered by their letters, not by where they are
+    let grid = "S.b#\nG.a.\n..#E".parse::<Grid>().unwrap();
+    assert_eq!(grid.waypoints(), [Pos { x: 2, y: 1 }, Pos { x: 2, y: 0 }]);
+    assert_eq!(grid.goals(), [Pos { x: 0, y: 1 }]);
+    assert_eq!(grid.to_string(), "S.b#\nG.a.\n..#E\n");
+
+    let grid = "SA^V\n<>.A\nB.BE".parse::<Grid>().unwrap();
+    assert_eq!(grid.tile(Pos { x: 1, y: 0 }), Tile::Portal(0));
+    assert_eq!(grid.tile(Pos { x: 0, y: 1 }), Tile::OneWay(Direction::Left));
+    assert_eq!(
+        grid.portal_partner(Pos { x: 1, y: 0 }),
+        Some(Pos { x: 3, y: 1 })
+    );
+    assert_eq!(grid.to_string(), "SA^V\n<>.A\nB.BE\n");
+
+    let errors = [
+        ("", ParseGridError::Empty),
+        (
+            "S..\n.E",
+            ParseGridError::RaggedRow {
+                row: 1,
+                width: 2,
+                expected: 3,
+            },
+        ),
+        (
+            "S.?\n..E",
+            ParseGridError::InvalidTile {
+                pos: Pos { x: 2, y: 0 },
+                character: '?',
+            },
+        ),
+        (
+            "S.E\n.S.",
+            ParseGridError::Duplicate {
+                pos: Pos { x: 1, y: 1 },
+                character: 'S',
+            },
+        ),
+        (
+            "SaE\n..a",
+            ParseGridError::Duplicate {
+                pos: Pos { x: 2, y: 1 },
+                character: 'a',
+            },
+        ),
+        ("..E", ParseGridError::MissingStart),
+        ("S..", ParseGridError::MissingEnd),
+    ];
+    for (text, error) in errors {
+        assert_eq!(text.parse::<Grid>(), Err(error), "{text:?}");
+    }
+}
+
+#[test]
+fn compact_encoding_restores_grids() {
+    let movements = [
+        Movement::FourWay,
+        Movement::EightWay,
+        Movement::EightWayNoCornerCutting,
+    ];
+    for seed in 0..20 {
+        let grid = random_grid(
+            seed,
+            3 + seed as i32,
+            40 - seed as i32,
+            movements[seed as usize % 3],
+        );
+        assert_eq!(decode_grid(&encode_grid(&grid)), Ok(grid));
+    }
+
+    // grids with waypoints or goals need the second version, the others keep their old encoding
+    let mut grid = random_grid(3, 12, 7, Movement::EightWay);
+    assert!(encode_grid(&grid).starts_with("AQ"));
+    grid.add_waypoint(Pos { x: 5, y: 3 });
+    grid.add_waypoint(Pos { x: 2, y: 6 });
+    grid.add_goal(Pos { x: 11, y: 0 });
+    assert!(encode_grid(&grid).starts_with("Ag"));
+    assert_eq!(decode_grid(&encode_grid(&grid)), Ok(grid.clone()));
+    // and portals or one-way tiles the third
+    grid.set_tile(Pos { x: 0, y: 1 }, Tile::Portal(7));
+    grid.set_tile(Pos { x: 9, y: 5 }, Tile::Portal(7));
+    grid.set_tile(Pos { x: 4, y: 2 }, Tile::OneWay(Direction::Down));
+    grid.set_tile(Pos { x: 4, y: 3 }, Tile::Cost(200));
+    assert!(encode_grid(&grid).starts_with("Aw"));
+    assert_eq!(decode_grid(&encode_grid(&grid)), Ok(grid));
+    // as do costs the older versions can't hold
+    for cost in [0, u8::MAX] {
+        let mut grid = random_grid(4, 6, 5, Movement::FourWay);
+        grid.set_tile(Pos { x: 2, y: 2 }, Tile::Cost(cost));
+        assert!(encode_grid(&grid).starts_with("Aw"));
+        assert_eq!(decode_grid(&encode_grid(&grid)), Ok(grid));
+    }
+    // as do hex grids
+    let mut grid = random_grid(5, 9, 8, Movement::Hex);
+    assert!(encode_grid(&grid).starts_with("Aw"));
+    assert_eq!(decode_grid(&encode_grid(&grid)), Ok(grid.clone()));
+    // and wrapping grids the fourth
+    grid.set_wraps(true);
+    assert!(encode_grid(&grid).starts_with("BA"));
+    assert_eq!(decode_grid(&encode_grid(&grid)), Ok(grid));
+
+    // runs of equal tiles only take a few bytes
+    let grid = Grid::new(200, 150, Pos { x: 0, y: 0 }, Pos { x: 199, y: 149 });
+    assert!(encode_grid(&grid).len() < 30);
+
+    assert_eq!(
+        decode_grid("not base64!"),
+        Err(DecodeGridError::InvalidBase64)
+    );
+    assert_eq!(
+        decode_grid("BQ"),
+        Err(DecodeGridError::UnsupportedVersion(5))
+    );
+    let encoded = encode_grid(&grid);
+    assert_eq!(
+        decode_grid(&encoded[..encoded.len() - 2]),
+        Err(DecodeGridError::InvalidLength)
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn json_documents_keep_grids_and_runs() {
+    use crate::json::{Document, JsonError, RecordedRun};
+    use crate::search_result::SearchResult;
+
+    let mut grid = random_grid(7, 12, 9, Movement::EightWayNoCornerCutting);
+    grid.set_tile(Pos { x: 1, y: 0 }, Tile::Cost(4));
+    grid.set_tile(Pos { x: 2, y: 0 }, Tile::Portal(1));
+    grid.set_tile(Pos { x: 3, y: 0 }, Tile::OneWay(Direction::Right));
+    grid.add_waypoint(Pos { x: 6, y: 4 });
+    grid.add_goal(Pos { x: 0, y: 8 });
+    let mut document = Document::new(grid.clone());
+    for algorithm in [
+        PathFindAlgorithms::AStar,
+        PathFindAlgorithms::BidirectionalDijkstra,
+    ] {
+        let heuristic = HeuristicOptions::default();
+        let mut search = algorithm.make_state(grid.clone(), heuristic);
+        document.runs.push(RecordedRun {
+            algorithm,
+            heuristic,
+            result: SearchResult::run(search.as_mut(), &grid),
+        });
+    }
+    assert!(!document.runs[1].result.visited_from_end.is_empty());
+
+    let json = document.to_json();
+    assert!(json.contains("\"algorithm\": \"a_star\""));
+    assert!(json.contains("\"movement\": \"eight_way_no_corner_cutting\""));
+    assert!(!json.contains("\"wraps\""));
+    assert_eq!(Document::from_json(&json), Ok(document));
+    grid.set_wraps(true);
+    let wrapping = Document::new(grid).to_json();
+    assert!(wrapping.contains("\"wraps\": true"));
+    assert!(Document::from_json(&wrapping).unwrap().grid.wraps());
+
+    let newer = json.replacen("\"version\": 1", "\"version\": 2", 1);
+    assert_eq!(
+        Document::from_json(&newer),
+        Err(JsonError::UnsupportedVersion(2))
+    );
+    let ragged = r#"{"version": 1, "grid": {"tiles": {"width": 2, "height": 2, "values": ["none"]},
+        "start": {"x": 0, "y": 0}, "end": {"x": 1, "y": 1}}}"#;
+    assert!(matches!(
+        Document::from_json(ragged),
+        Err(JsonError::Invalid(_))
+    ));
+    let outside = r#"{"version": 1, "grid": {"tiles": {"width": 1, "height": 1, "values": ["none"]},
+        "start": {"x": 0, "y": 0}, "end": {"x": 1, "y": 1}}}"#;
+    assert!(matches!(
+        Document::from_json(outside),
+        Err(JsonError::Invalid(_))
+    ));
+}
+
+#[test]
+fn generated_mazes_are_perfect() {
+    for generator in MazeGenerators::ALL {
+        for (seed, (width, height)) in [(1, 1), (2, 2), (1, 9), (10, 7), (21, 15), (30, 31)]
+            .into_iter()
+            .enumerate()
+        {
+            let template = Grid::new(
+                width,
+                height,
+                Pos { x: 1, y: 1 },
+                Pos {
+                    x: width - 1,
+                    y: height - 1,
+                },
+            );
+            let maze = generator.generate(&template, seed as u64);
+            assert_eq!(maze, generator.generate(&template, seed as u64));
+
+            // every cell is open and connected by exactly one passage less than there are cells
+            let cells = ((width + 1) / 2 * ((height + 1) / 2)) as usize;
+            let open = (0..height)
+                .flat_map(|y| (0..width).map(move |x| Pos { x, y }))
+                .filter(|pos| maze.walkable(*pos))
+                .count();
+            assert_eq!(open, 2 * cells - 1, "{generator:?} {width}x{height}");
+            for y in (0..height).step_by(2) {
+                for x in (0..width).step_by(2) {
+                    assert!(
+                        maze.walkable(Pos { x, y }),
+                        "{generator:?} {width}x{height}"
+                    );
+                }
+            }
+            assert_eq!(maze.start().x % 2 + maze.start().y % 2, 0);
+            assert!(find_path(PathFindAlgorithms::BreadthFirst, &maze).is_some());
+        }
+
+        // markers which would snap onto the same cell are spread over the closest free cells
+        let mut template = Grid::new(9, 9, Pos { x: 0, y: 0 }, Pos { x: 1, y: 1 });
+        template.add_waypoint(Pos { x: 1, y: 0 });
+        let maze = generator.generate(&template, 0);
+        let markers = [maze.start(), maze.end(), maze.waypoints()[0]];
+        assert_eq!(maze.start(), Pos { x: 0, y: 0 });
+        for (i, marker) in markers.iter().enumerate() {
+            assert_eq!(marker.x % 2 + marker.y % 2, 0, "{generator:?}");
+            assert!(!markers[..i].contains(marker), "{generator:?} {markers:?}");
+        }
+        assert!(find_path(PathFindAlgorithms::BreadthFirst, &maze).is_some());
+    }
+}
+
+#[test]
+fn generated_terrain_is_reproducible_and_connected() {
+    for generator in TerrainGenerators::ALL {
+        for (seed, density) in [0.0, 0.3, 0.6, 0.9].into_iter().enumerate() {
+            for mapping in [NoiseMapping::Walls, NoiseMapping::Costs] {
+                let template = Grid::new(37, 23, Pos { x: 2, y: 20 }, Pos { x: 35, y: 1 });
+                let options = TerrainOptions {
+                    density,
+                    scale: 5.0,
+                    mapping,
+                };
+                let terrain = generator.generate(&template, options, seed as u64);
+                assert_eq!(terrain, generator.generate(&template, options, seed as u64));
+                // very dense caves fill up completely, so only a connecting corridor is left
+                if density > 0.0 && density < 0.8 {
+                    assert_ne!(
+                        terrain,
+                        generator.generate(&template, options, seed as u64 + 1),
+                        "{generator:?} {density}"
+                    );
+                }
+                assert!(
+                    find_path(PathFindAlgorithms::BreadthFirst, &terrain).is_some(),
+                    "{generator:?} {density}"
+                );
+            }
+        }
+    }
+
+    // noise mapped to costs creates weighted terrain without walls
+    let template = Grid::new(30, 30, Pos { x: 0, y: 0 }, Pos { x: 29, y: 29 });
+    let options = TerrainOptions {
+        mapping: NoiseMapping::Costs,
+        ..TerrainOptions::default()
+    };
+    let terrain = TerrainGenerators::PerlinNoise.generate(&template, options, 3);
+    let tiles = (0..30)
+        .flat_map(|y| (0..30).map(move |x| Pos { x, y }))
+        .map(|pos| terrain.tile(pos))
+        .collect::<Vec<_>>();
+    assert!(!tiles.contains(&Tile::Wall));
+    assert!(tiles.contains(&Tile::Cost(2)) && tiles.contains(&Tile::Cost(8)));
+}
+
+#[test]
+fn connecting_opens_the_fewest_walls() {
+    let mut grid = "
+        S.#..
+        ###..
+        ..#.#
+        ..#.E
+    "
+    .parse::<Grid>()
+    .unwrap();
+    connect(&mut grid);
+    assert_eq!(grid.to_string(), "S....\n###..\n..#.#\n..#.E\n");
+
+    // grids which are already connected stay the same
+    let connected = grid.clone();
+    connect(&mut grid);
+    assert_eq!(grid, connected);
+}
+
+/// Returns the shortest path cost from the start to each of the targets
+fn shortest_target_costs(grid: &Grid, targets: &[Pos]) -> Vec<Option<f32>> {
+    targets
+        .iter()
+        .map(|target| {
+            let mut leg = grid.clone();
+            leg.clear_markers();
+            leg.set_end(*target);
+            shortest_path_cost(&leg)
+        })
+        .collect()
+}
+
+#[test]
+fn paths_pass_the_waypoints_in_order() {
+    for seed in 0..10 {
+        let mut grid = random_grid(seed, 9, 7, Movement::EightWayNoCornerCutting);
+        let waypoints = [Pos { x: 7, y: 1 }, Pos { x: 1, y: 5 }, Pos { x: 4, y: 3 }];
+        for waypoint in waypoints {
+            grid.add_waypoint(waypoint);
+        }
+
+        // the cheapest route is made of the cheapest path between every two stops
+        let stops = [
+            grid.start(),
+            waypoints[0],
+            waypoints[1],
+            waypoints[2],
+            grid.end(),
+        ];
+        let expected = stops
+            .windows(2)
+            .map(|leg| {
+                let mut leg_grid = grid.clone();
+                leg_grid.clear_markers();
+                leg_grid.set_start(leg[0]);
+                shortest_target_costs(&leg_grid, &[leg[1]])[0]
+            })
+            .sum::<Option<f32>>();
+
+        for algorithm in PathFindAlgorithms::ALL {
+            let path = find_path(algorithm, &grid);
+            assert_eq!(path.is_some(), expected.is_some(), "{algorithm:?}");
+            let Some(path) = path else {
+                continue;
+            };
+            assert_eq!(path.first(), Some(&grid.start()), "{algorithm:?}");
+            assert_eq!(path.last(), Some(&grid.end()), "{algorithm:?}");
+            let mut rest = path.as_slice();
+            for waypoint in waypoints {
+                let index = rest.iter().position(|pos| *pos == waypoint);
+                let index = index.unwrap_or_else(|| panic!("{algorithm:?} missed {waypoint:?}"));
+                rest = &rest[index..];
+            }
+
+            if OPTIMAL_ALGORITHMS.contains(&algorithm) {
+                let cost = grid.path_cost(&path).unwrap();
+                let expected = expected.unwrap();
+                assert!(
+                    (cost - expected).abs() < 1e-3,
+                    "{algorithm:?} found a route costing {cost} instead of {expected}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn searches_stop_at_the_nearest_goal() {
+    for seed in 0..20 {
+        let mut grid = random_grid(seed, 14, 9, Movement::FourWay);
+        grid.add_goal(Pos { x: 13, y: 0 });
+        grid.add_goal(Pos { x: 3, y: 8 });
+        let targets = grid.targets();
+        let costs = shortest_target_costs(&grid, &targets);
+        let nearest = costs.iter().flatten().copied().reduce(f32::min);
+
+        for algorithm in PathFindAlgorithms::ALL {
+            let path = find_path(algorithm, &grid);
+            assert_eq!(path.is_some(), nearest.is_some(), "{algorithm:?}");
+            let Some(path) = path else {
+                continue;
+            };
+            assert_eq!(path.first(), Some(&grid.start()), "{algorithm:?}");
+            assert!(grid.is_goal(*path.last().unwrap()), "{algorithm:?}");
+
+            if OPTIMAL_ALGORITHMS.contains(&algorithm) {
+                let cost = grid.path_cost(&path).unwrap();
+                let nearest = nearest.unwrap();
+                assert!(
+                    (cost - nearest).abs() < 1e-3,
+                    "{algorithm:?} found a path costing {cost} instead of {nearest}"
+                );
+            }
+        }
+    }
+}
+
+/// Returns every order of the items, for checking orders by trying all of them
+fn permutations(items: &[usize]) -> Vec<Vec<usize>> {
+    if items.is_empty() {
+        return vec![vec![]];
+    }
+    (0..items.len())
+        .flat_map(|i| {
+            let mut rest = items.to_vec();
+            let first = rest.remove(i);
+            permutations(&rest).into_iter().map(move |mut order| {
+                order.insert(0, first);
+                order
+            })
+        })
+        .collect()
+}
+
+fn cheapest_route(costs: &[Vec<f32>]) -> f32 {
+    let targets = (1..costs.len() - 1).collect::<Vec<_>>();
+    permutations(&targets)
+        .iter()
+        .map(|order| route_cost(costs, order))
+        .fold(f32::INFINITY, f32::min)
+}
+
+#[test]
+fn tour_orders_visit_every_target_as_cheap_as_possible() {
+    let mut rng = Rng::new(5);
+    for targets in 0..8 {
+        let stops = targets + 2;
+        let costs = (0..stops)
+            .map(|_| (0..stops).map(|_| rng.below(20) as f32 + 1.0).collect())
+            .collect::<Vec<Vec<f32>>>();
+        let cheapest = cheapest_route(&costs);
+
+        let exact = held_karp(&costs);
+        let mut sorted = exact.clone();
+        sorted.sort();
+        assert_eq!(sorted, (1..=targets).collect::<Vec<_>>());
+        assert_eq!(route_cost(&costs, &exact), cheapest, "{targets} targets");
+
+        let nearest = nearest_neighbor(&costs);
+        let improved = two_opt(&costs, nearest.clone());
+        let mut sorted = improved.clone();
+        sorted.sort();
+        assert_eq!(sorted, (1..=targets).collect::<Vec<_>>());
+        assert!(route_cost(&costs, &improved) <= route_cost(&costs, &nearest));
+        assert!(route_cost(&costs, &improved) >= cheapest);
+    }
+}
+
+#[test]
+fn tours_pass_every_waypoint() {
+    for seed in 0..5 {
+        let mut grid = random_grid(seed, 10, 8, Movement::EightWay);
+        let waypoints = [
+            Pos { x: 8, y: 1 },
+            Pos { x: 1, y: 6 },
+            Pos { x: 5, y: 3 },
+            Pos { x: 2, y: 1 },
+        ];
+        for waypoint in waypoints {
+            grid.add_waypoint(waypoint);
+        }
+
+        // the cheapest tour is the cheapest order of the cheapest paths between the stops
+        let stops = [
+            grid.start(),
+            waypoints[0],
+            waypoints[1],
+            waypoints[2],
+            waypoints[3],
+            grid.end(),
+        ];
+        let costs = stops
+            .iter()
+            .map(|from| {
+                let mut from_grid = grid.clone();
+                from_grid.set_start(*from);
+                shortest_target_costs(&from_grid, &stops)
+                    .into_iter()
+                    .map(|cost| cost.unwrap_or(f32::INFINITY))
+                    .collect()
+            })
+            .collect::<Vec<Vec<f32>>>();
+        let cheapest = cheapest_route(&costs);
+
+        let mut tour = TourSearch::new(
+            PathFindAlgorithms::Dijkstra,
+            grid.clone(),
+            HeuristicOptions::default(),
+        );
+        let path = run_to_end(&mut tour);
+        assert_eq!(path.is_some(), cheapest.is_finite());
+        let Some(path) = path else {
+            continue;
+        };
+        assert_eq!(path.first(), Some(&grid.start()));
+        assert_eq!(path.last(), Some(&grid.end()));
+        let mut order = tour.order().unwrap().to_vec();
+        let mut rest = path.as_slice();
+        for waypoint in order.iter().map(|index| waypoints[*index]) {
+            let index = rest.iter().position(|pos| *pos == waypoint).unwrap();
+            rest = &rest[index..];
+        }
+        order.sort();
+        assert_eq!(order, [0, 1, 2, 3]);
+
+        let cost = grid.path_cost(&path).unwrap();
+        assert!(
+            (cost - cheapest).abs() < 1e-3,
+            "the tour costs {cost} instead of {cheapest}"
+        );
+    }
+}
+
+/// Replaces random tiles with a few pairs of portals and with one-way tiles, keeping the markers free
+fn add_portals_and_one_way_tiles(grid: &mut Grid, seed: u64) {
+    let mut rng = Rng::new(seed);
+    let (width, height) = (grid.width() as usize, grid.height() as usize);
+    let random_pos = |rng: &mut Rng| Pos {
+        x: rng.below(width) as i32,
+        y: rng.below(height) as i32,
+    };
+
+    for number in 0..3 {
+        for _ in 0..2 {
+            let pos = random_pos(&mut rng);
+            grid.set_tile(pos, Tile::Portal(number));
+        }
+    }
+    for _ in 0..width {
+        let pos = random_pos(&mut rng);
+        let direction = *rng.choose(&Direction::ALL).unwrap();
+        grid.set_tile(pos, Tile::OneWay(direction));
+    }
+}
+
+#[test]
+fn portals_and_one_way_tiles_change_the_moves() {
+    // the wall can only be passed through the portals
+    let grid = "S#A\n.#.\nA#E".parse::<Grid>().unwrap();
+    let portal = Pos { x: 0, y: 2 };
+    assert_eq!(grid.portal_partner(portal), Some(Pos { x: 2, y: 0 }));
+    assert!(grid.neighbors(portal).contains(&(Pos { x: 2, y: 0 }, 0.0)));
+    assert!(grid
+        .predecessors(portal)
+        .contains(&(Pos { x: 2, y: 0 }, 0.0)));
+    assert_eq!(shortest_path_cost(&grid), Some(4.0));
+    assert_optimal(&grid);
+    let path = find_path(PathFindAlgorithms::AStar, &grid).unwrap();
+    assert!(path
+        .windows(2)
+        .any(|step| step == [portal, Pos { x: 2, y: 0 }]));
+
+    // a third portal with the same letter leaves all of them without a partner
+    let grid = "SA#A\n..#A\n..#E".parse::<Grid>().unwrap();
+    assert_eq!(grid.portal_partner(Pos { x: 1, y: 0 }), None);
+    assert_eq!(shortest_path_cost(&grid), None);
+
+    // one-way tiles can only be crossed along their arrow
+    let grid = "S.<.E".parse::<Grid>().unwrap();
+    assert_eq!(grid.step_cost(Pos { x: 1, y: 0 }, Pos { x: 2, y: 0 }), None);
+    assert_eq!(shortest_path_cost(&grid), None);
+    assert_optimal(&grid);
+    let grid = "E.<.S".parse::<Grid>().unwrap();
+    assert_eq!(shortest_path_cost(&grid), Some(4.0));
+    assert_optimal(&grid);
+    let mut grid = "S^.\n...\n..E".parse::<Grid>().unwrap();
+    grid.set_movement(Movement::EightWay);
+    assert_eq!(grid.step_cost(Pos { x: 1, y: 0 }, Pos { x: 2, y: 1 }), None);
+    assert!(grid
+        .step_cost(Pos { x: 1, y: 1 }, Pos { x: 1, y: 0 })
+        .is_some());
+    assert!(grid
+        .step_cost(Pos { x: 0, y: 1 }, Pos { x: 1, y: 0 })
+        .is_some());
+}
+
+#[test]
+fn searches_honor_portals_and_one_way_tiles() {
+    let movements = [
+        Movement::FourWay,
+        Movement::EightWay,
+        Movement::EightWayNoCornerCutting,
+    ];
+    for seed in 0..30 {
+        let mut grid = random_grid(seed, 12, 9, movements[seed as usize % 3]);
+        add_portals_and_one_way_tiles(&mut grid, seed);
+        assert_optimal(&grid);
+
+        let expected = shortest_path_cost(&grid);
+        for algorithm in [
+            PathFindAlgorithms::DepthFirst,
+            PathFindAlgorithms::BreadthFirst,
+            PathFindAlgorithms::GreedyBestFirst,
+            PathFindAlgorithms::BidirectionalBreadthFirst,
+            PathFindAlgorithms::IterativeDeepeningDepthFirst,
+        ] {
+            let path = find_path(algorithm, &grid);
+            assert_eq!(path.is_some(), expected.is_some(), "{algorithm:?} {seed}");
+            if let Some(path) = path {
+                assert_valid_path(&grid, &path);
+            }
+        }
+    }
+
+    // IDA* and the searches which ignore the cost of tiles
+    for seed in 0..30 {
+        let mut grid = random_grid(seed, 8, 7, Movement::EightWayNoCornerCutting);
+        for y in 0..grid.height() {
+            for x in 0..grid.width() {
+                if let Tile::Cost(_) = grid.tile(Pos { x, y }) {
+                    grid.set_tile(Pos { x, y }, Tile::None);
+                }
+            }
+        }
+        add_portals_and_one_way_tiles(&mut grid, seed);
+        let expected = shortest_path_cost(&grid);
+
+        for algorithm in [
+            PathFindAlgorithms::IdaStar,
+            PathFindAlgorithms::JumpPointSearch,
+            PathFindAlgorithms::JumpPointSearchPlus,
+        ] {
+            let path = find_path(algorithm, &grid);
+            assert_eq!(path.is_some(), expected.is_some(), "{algorithm:?} {seed}");
+            if let (Some(path), Some(expected)) = (path, expected) {
+                assert_valid_path(&grid, &path);
+                let cost = grid.path_cost(&path).unwrap();
+                assert!(
+                    (cost - expected).abs() < 1e-3,
+                    "{algorithm:?} found a path costing {cost} instead of {expected} on seed {seed}"
+                );
+            }
+        }
+        for algorithm in [
+            PathFindAlgorithms::ThetaStar,
+            PathFindAlgorithms::LazyThetaStar,
+        ] {
+            let path = find_path(algorithm, &grid);
+            assert_eq!(path.is_some(), expected.is_some(), "{algorithm:?} {seed}");
+            if let Some(path) = path {
+                assert_eq!(path.last(), Some(&grid.end()));
+                assert!(
+                    grid.any_angle_path_cost(&path).is_some(),
+                    "{algorithm:?} path is blocked on seed {seed}: {path:?}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn goal_estimates_through_portals_never_overestimate() {
+    use crate::heuristic::GoalEstimate;
+
+    for seed in 0..20 {
+        let mut grid = random_grid(seed, 12, 9, Movement::EightWay);
+        add_portals_and_one_way_tiles(&mut grid, seed);
+        let estimate = GoalEstimate::new(&grid, &grid.targets(), HeuristicOptions::default());
+
+        // the cheapest costs to the end, found backwards from it
+        let mut distances =
+            Vec2d::new(grid.width() as usize, grid.height() as usize, f32::INFINITY);
+        distances.set(grid.end(), 0.0);
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for y in 0..grid.height() {
+                for x in 0..grid.width() {
+                    let pos = Pos { x, y };
+                    let distance = *distances.get(pos).unwrap();
+                    for (predecessor, cost) in grid.predecessors(pos) {
+                        if distance + cost < *distances.get(predecessor).unwrap() - 1e-4 {
+                            distances.set(predecessor, distance + cost);
+                            changed = true;
+                        }
+                    }
+                }
+            }
+        }
+
+        for y in 0..grid.height() {
+            for x in 0..grid.width() {
+                let pos = Pos { x, y };
+                let distance = *distances.get(pos).unwrap();
+                assert!(
+                    estimate.estimate(pos) <= distance + 1e-3,
+                    "the estimate at {pos:?} is above {distance} on seed {seed}"
+                );
+            }
+        }
+    }
+}
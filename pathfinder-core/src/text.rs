@@ -0,0 +1,246 @@
+//! A plain text format for grids, in which every line is a row of tiles:
+//!
+//! | Character | Tile |
+//! |---|---|
+//! | `.` | an empty tile |
+//! | `#` | a wall |
+//! | `1` to `9` | a tile with that cost |
+//! | `S` | the start |
+//! | `E` | the end |
+//! | `a` to `z` | the waypoints, which are passed in the order of their letters |
+//! | `G` | a goal |
+//! | `^`, `V`, `<`, `>` | a one-way tile in the direction of the arrow |
+//! | the other capital letters | a portal, which is paired with the other portal of the same letter |
+//!
+//! The movement isn't part of the format, parsed grids use [Movement::FourWay](crate::Movement::FourWay).
+//! As there are only 26 letters, waypoints after the 26th are left out when a grid is written.
+//! Portals are numbered in the order of their letters in [PORTAL_LETTERS]
+//! and portals with higher numbers than there are letters are written as empty tiles.
+//!
+//! ```
+//! use pathfinder_core::{Grid, Pos, Tile};
+//!
+//! let grid = "S.#\n.9E".parse::<Grid>().unwrap();
+//! assert_eq!(grid.tile(Pos { x: 2, y: 0 }), Tile::Wall);
+//! assert_eq!(grid.tile(Pos { x: 1, y: 1 }), Tile::Cost(9));
+//! assert_eq!(grid.end(), Pos { x: 2, y: 1 });
+//! assert_eq!(grid.to_string(), "S.#\n.9E\n");
+//! ```
+
+use crate::{Direction, Grid, Pos, Tile, Unit};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// The letters of the portals, the first one stands for [Tile::Portal] `0`.
+pub const PORTAL_LETTERS: &str = "ABCDFHIJKLMNOPQRTUWXYZ";
+
+const ONE_WAY_ARROWS: [(char, Direction); 4] = [
+    ('^', Direction::Up),
+    ('V', Direction::Down),
+    ('<', Direction::Left),
+    ('>', Direction::Right),
+];
+
+/// Why a text couldn't be parsed into a [Grid].
+#[derive(Clone, Debug, PartialEq)]
+pub enum ParseGridError {
+    /// The text doesn't contain a single tile.
+    Empty,
+    /// A row doesn't have as many tiles as the first row.
+    RaggedRow {
+        /// The row, starting at 0.
+        row: Unit,
+        /// The number of tiles in the row.
+        width: Unit,
+        /// The number of tiles in the first row.
+        expected: Unit,
+    },
+    /// A character which doesn't stand for a tile.
+    InvalidTile {
+        /// The position of the character.
+        pos: Pos,
+        /// The character.
+        character: char,
+    },
+    /// A second start, end or waypoint with the same letter.
+    Duplicate {
+        /// The position of the second marker.
+        pos: Pos,
+        /// `S`, `E` or the letter of the waypoint.
+        character: char,
+    },
+    /// No tile is marked as the start.
+    MissingStart,
+    /// No tile is marked as the end.
+    MissingEnd,
+}
+
+impl Display for ParseGridError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseGridError::Empty => write!(f, "the map is empty"),
+            ParseGridError::RaggedRow {
+                row,
+                width,
+                expected,
+            } => write!(
+                f,
+                "row {} has {} tiles, but the first row has {}",
+                row + 1,
+                width,
+                expected
+            ),
+            ParseGridError::InvalidTile { pos, character } => write!(
+                f,
+                "invalid tile '{}' in row {}, column {}",
+                character,
+                pos.y + 1,
+                pos.x + 1
+            ),
+            ParseGridError::Duplicate { pos, character } => write!(
+                f,
+                "second '{}' in row {}, column {}",
+                character,
+                pos.y + 1,
+                pos.x + 1
+            ),
+            ParseGridError::MissingStart => write!(f, "the map has no start 'S'"),
+            ParseGridError::MissingEnd => write!(f, "the map has no end 'E'"),
+        }
+    }
+}
+
+impl std::error::Error for ParseGridError {}
+
+impl FromStr for Grid {
+    type Err = ParseGridError;
+
+    /// Parses the text format, see the [module documentation](self).
+    /// Empty lines and whitespace around the rows are ignored, so grids can be written indented inside of code.
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let rows = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>();
+        let width = rows.first().ok_or(ParseGridError::Empty)?.chars().count() as Unit;
+
+        let mut tiles = Vec::with_capacity(rows.len() * width as usize);
+        let (mut start, mut end) = (None, None);
+        let mut waypoints = Vec::new();
+        let mut goals = Vec::new();
+        for (y, row) in rows.iter().enumerate() {
+            let row_width = row.chars().count() as Unit;
+            if row_width != width {
+                return Err(ParseGridError::RaggedRow {
+                    row: y as Unit,
+                    width: row_width,
+                    expected: width,
+                });
+            }
+
+            for (x, character) in row.chars().enumerate() {
+                let pos = Pos {
+                    x: x as Unit,
+                    y: y as Unit,
+                };
+                let tile = match character {
+                    '.' => Tile::None,
+                    '#' => Tile::Wall,
+                    '1'..='9' => Tile::Cost(character as u8 - b'0'),
+                    'S' | 'E' => {
+                        let marker = if character == 'S' {
+                            &mut start
+                        } else {
+                            &mut end
+                        };
+                        if marker.replace(pos).is_some() {
+                            return Err(ParseGridError::Duplicate { pos, character });
+                        }
+                        Tile::None
+                    }
+                    'a'..='z' => {
+                        if waypoints.iter().any(|(letter, _)| *letter == character) {
+                            return Err(ParseGridError::Duplicate { pos, character });
+                        }
+                        waypoints.push((character, pos));
+                        Tile::None
+                    }
+                    'G' => {
+                        goals.push(pos);
+                        Tile::None
+                    }
+                    _ => {
+                        let arrow = ONE_WAY_ARROWS.iter().find(|(arrow, _)| *arrow == character);
+                        if let Some((_, direction)) = arrow {
+                            Tile::OneWay(*direction)
+                        } else if let Some(number) = PORTAL_LETTERS.find(character) {
+                            Tile::Portal(number as u8)
+                        } else {
+                            return Err(ParseGridError::InvalidTile { pos, character });
+                        }
+                    }
+                };
+                tiles.push((pos, tile));
+            }
+        }
+
+        let start = start.ok_or(ParseGridError::MissingStart)?;
+        let end = end.ok_or(ParseGridError::MissingEnd)?;
+        let mut grid = Grid::new(width, rows.len() as Unit, start, end);
+        for (pos, tile) in tiles {
+            grid.set_tile(pos, tile);
+        }
+        waypoints.sort_by_key(|(letter, _)| *letter);
+        for (_, pos) in waypoints {
+            grid.add_waypoint(pos);
+        }
+        for pos in goals {
+            grid.add_goal(pos);
+        }
+        Ok(grid)
+    }
+}
+
+impl Display for Grid {
+    /// Writes the grid in the text format, see the [module documentation](self).
+    /// Every row ends with a line break.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for y in 0..self.height() {
+            for x in 0..self.width() {
+                let pos = Pos { x, y };
+                let waypoint = self
+                    .waypoints()
+                    .iter()
+                    .take(26)
+                    .position(|waypoint| *waypoint == pos);
+                let character = if pos == self.start() {
+                    'S'
+                } else if pos == self.end() {
+                    'E'
+                } else if let Some(index) = waypoint {
+                    (b'a' + index as u8) as char
+                } else if self.goals().contains(&pos) {
+                    'G'
+                } else {
+                    match self.tile(pos) {
+                        Tile::None => '.',
+                        Tile::Wall => '#',
+                        Tile::Cost(cost) => (b'0' + cost.clamp(1, Tile::MAX_COST)) as char,
+                        Tile::Portal(number) => {
+                            PORTAL_LETTERS.chars().nth(number as usize).unwrap_or('.')
+                        }
+                        Tile::OneWay(direction) => ONE_WAY_ARROWS
+                            .iter()
+                            .find(|(_, arrow_direction)| *arrow_direction == direction)
+                            .map(|(arrow, _)| *arrow)
+                            .expect("every direction has an arrow"),
+                    }
+                };
+                write!(f, "{}", character)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
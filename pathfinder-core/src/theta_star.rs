@@ -0,0 +1,140 @@
+//! Theta* and Lazy Theta*, which find any-angle paths.
+
+use crate::a_star::AStarPrioritizer;
+use crate::best_first::SearchFrontier;
+use crate::heuristic::{Euclidean, Heuristic, HeuristicOptions, Heuristics};
+use crate::PathFindAlgorithmStepResult::{InProgress, NotFound};
+use crate::{
+    Grid, Movement, PathFindAlgorithm, PathFindAlgorithmConstructor, PathFindAlgorithmStepResult,
+    Pos,
+};
+
+/// Theta*, an A* whose paths can move at any angle instead of just in the eight directions of the grid.
+///
+/// When a neighbor is discovered, it is connected directly to the parent of the expanded position
+/// if there is a line of sight between them. The path is returned as the corners of its straight lines.
+/// Like Jump Point Search it moves in eight directions without cutting corners, treats weighted tiles like normal tiles
+/// and doesn't wrap around the edges of the grid.
+/// Straight lines don't cross portals and one-way tiles, see [Grid::line_cost].
+///
+/// Lazy Theta* assumes every line of sight exists when discovering neighbors
+/// and only checks it once a position is expanded, which saves most of the checks.
+#[derive(Clone, Debug)]
+pub struct ThetaStar {
+    grid: Grid,
+    frontier: SearchFrontier<AStarPrioritizer>,
+    lazy: bool,
+}
+
+impl ThetaStar {
+    /// Creates a Lazy Theta* search.
+    pub fn lazy(grid: Grid, heuristic: HeuristicOptions) -> Self {
+        Self {
+            lazy: true,
+            ..Self::make_state(grid, heuristic)
+        }
+    }
+
+    /// Connects the position to its cheapest expanded predecessor, if there is no line to its assumed parent.
+    fn check_parent(&mut self, pos: Pos) {
+        match self.frontier.parent(pos) {
+            Some(parent) if self.grid.line_cost(parent, pos).is_none() => {}
+            _ => return,
+        }
+
+        let cheapest = self
+            .grid
+            .predecessors(pos)
+            .into_iter()
+            .filter(|(predecessor, _)| self.frontier.closed(*predecessor))
+            .map(|(predecessor, _)| {
+                let distance = self.frontier.distance(predecessor) + self.length(predecessor, pos);
+                (predecessor, distance)
+            })
+            .min_by(|(_, a), (_, b)| a.total_cmp(b));
+        if let Some((predecessor, distance)) = cheapest {
+            self.frontier.reparent(pos, predecessor, distance);
+        }
+    }
+
+    /// Returns the length of a step, which is free through a portal.
+    fn length(&self, from: Pos, to: Pos) -> f32 {
+        if self.grid.portal_partner(from) == Some(to) {
+            0.0
+        } else {
+            Euclidean.estimate(from, to)
+        }
+    }
+}
+
+impl PathFindAlgorithmConstructor for ThetaStar {
+    fn make_state(mut grid: Grid, mut heuristic: HeuristicOptions) -> Self {
+        grid.set_movement(Movement::EightWayNoCornerCutting);
+        // straight lines can't go over the edges
+        grid.set_wraps(false);
+        // the octile distance would overestimate lines which don't follow the eight directions
+        if heuristic.heuristic == Heuristics::Auto {
+            heuristic.heuristic = Heuristics::Euclidean;
+        }
+
+        Self {
+            frontier: SearchFrontier::new(&grid, grid.start(), &grid.targets(), heuristic),
+            grid,
+            lazy: false,
+        }
+    }
+}
+
+impl PathFindAlgorithm for ThetaStar {
+    fn next_step(&mut self) -> Result<Vec<Pos>, PathFindAlgorithmStepResult> {
+        let pos = self.frontier.pop().ok_or(NotFound)?;
+        if self.lazy {
+            self.check_parent(pos);
+        }
+
+        if self.grid.is_goal(pos) {
+            let mut path = self.frontier.path_to_root(pos).ok_or(NotFound)?;
+            path.reverse();
+            return Ok(path);
+        }
+
+        let parent = self.frontier.parent(pos);
+        for (neighbor, _) in self.grid.neighbors(pos) {
+            // going through a portal can't be cut short
+            let through_portal = self.grid.portal_partner(pos) == Some(neighbor);
+            let from = match parent {
+                Some(parent)
+                    if !through_portal
+                        && (self.lazy || self.grid.line_cost(parent, neighbor).is_some()) =>
+                {
+                    parent
+                }
+                _ => pos,
+            };
+            let distance = self.frontier.distance(from) + self.length(from, neighbor);
+            self.frontier.discover(neighbor, from, distance);
+        }
+
+        Err(InProgress)
+    }
+
+    fn visited(&self, pos: Pos) -> bool {
+        self.frontier.discovered(pos)
+    }
+
+    fn visited_list(&self) -> &[Pos] {
+        self.frontier.visited_list()
+    }
+
+    fn expanded(&self) -> usize {
+        self.frontier.expanded()
+    }
+
+    fn in_queue(&self, pos: Pos) -> bool {
+        self.frontier.in_queue(pos)
+    }
+
+    fn is_any_angle(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,274 @@
+//! Tours which visit several targets in the cheapest order, like the traveling salesman problem.
+//!
+//! A [TourSearch] treats the waypoints of the grid as targets, which can be visited in any order
+//! on the way from the start to the end. It first searches the path between every two stops
+//! with one of the [PathFindAlgorithms] and then decides the order with [visiting_order]:
+//! exactly with Held-Karp for up to [HELD_KARP_LIMIT] targets, otherwise with the nearest neighbor
+//! heuristic improved by 2-opt. Goals are ignored, the tour always ends at the end.
+//!
+//! The costs between the stops are given as a matrix, where `costs[from][to]` is the cost of the path
+//! between two stops. Stop `0` is the start, the last stop the end and the stops in between the targets.
+//! Costs don't have to be symmetric, as a path costs the tiles it steps onto.
+//!
+//! ```
+//! use pathfinder_core::tour::{route_cost, visiting_order};
+//!
+//! let costs = vec![
+//!     vec![0.0, 5.0, 1.0, 9.0],
+//!     vec![5.0, 0.0, 2.0, 1.0],
+//!     vec![1.0, 2.0, 0.0, 6.0],
+//!     vec![9.0, 1.0, 6.0, 0.0],
+//! ];
+//! let order = visiting_order(&costs);
+//! assert_eq!(order, [2, 1]);
+//! assert_eq!(route_cost(&costs, &order), 4.0);
+//! ```
+
+use crate::heuristic::HeuristicOptions;
+use crate::iterative_deepening::DeepeningProgress;
+use crate::route::SearchChain;
+use crate::PathFindAlgorithmStepResult::{InProgress, NotFound};
+use crate::{Grid, PathFindAlgorithm, PathFindAlgorithmStepResult, PathFindAlgorithms, Pos};
+use std::iter::once;
+
+/// The largest number of targets whose order is found exactly.
+/// Held-Karp takes time and memory exponential in the number of targets.
+pub const HELD_KARP_LIMIT: usize = 12;
+
+/// Searches the paths between all stops one after another and then puts the cheapest tour together from them.
+pub struct TourSearch {
+    grid: Grid,
+    stops: Vec<Pos>,
+    /// The stops of every searched path, in the order of the searches
+    pairs: Vec<(usize, usize)>,
+    searched: usize,
+    chain: SearchChain,
+    costs: Vec<Vec<f32>>,
+    paths: Vec<Vec<Vec<Pos>>>,
+    order: Option<Vec<usize>>,
+}
+
+impl TourSearch {
+    /// Creates the tour, running the algorithm once for every path between two stops.
+    pub fn new(algorithm: PathFindAlgorithms, grid: Grid, heuristic: HeuristicOptions) -> Self {
+        let stops = once(grid.start())
+            .chain(grid.waypoints().iter().copied())
+            .chain(once(grid.end()))
+            .collect::<Vec<_>>();
+        let end = stops.len() - 1;
+
+        // the tour never goes back to the start and never leaves the end,
+        // and it only goes straight from the start to the end if there are no targets
+        let pairs = (0..end)
+            .flat_map(|from| (1..=end).map(move |to| (from, to)))
+            .filter(|&(from, to)| (from != to && (from, to) != (0, end)) || end == 1)
+            .collect::<Vec<_>>();
+        let searches = pairs
+            .iter()
+            .map(|&(from, to)| {
+                let mut search = grid.clone();
+                search.clear_markers();
+                search.set_start(stops[from]);
+                search.set_end(stops[to]);
+                search
+            })
+            .collect();
+
+        Self {
+            chain: SearchChain::new(algorithm, heuristic, searches),
+            costs: vec![vec![f32::INFINITY; stops.len()]; stops.len()],
+            paths: vec![vec![vec![]; stops.len()]; stops.len()],
+            grid,
+            stops,
+            pairs,
+            searched: 0,
+            order: None,
+        }
+    }
+
+    /// Returns the order in which the targets are visited once the tour is found,
+    /// as indices into the waypoints of the grid.
+    pub fn order(&self) -> Option<&[usize]> {
+        self.order.as_deref()
+    }
+}
+
+impl PathFindAlgorithm for TourSearch {
+    fn next_step(&mut self) -> Result<Vec<Pos>, PathFindAlgorithmStepResult> {
+        match self.chain.next_step() {
+            Ok(path) => {
+                let (from, to) = self.pairs[self.searched];
+                self.costs[from][to] = self.chain.path_cost(&self.grid, &path);
+                self.paths[from][to] = path;
+            }
+            Err(InProgress) => return Err(InProgress),
+            Err(NotFound) => {}
+        }
+        self.searched += 1;
+        if self.chain.start_next() {
+            return Err(InProgress);
+        }
+
+        let order = visiting_order(&self.costs);
+        if !route_cost(&self.costs, &order).is_finite() {
+            return Err(NotFound);
+        }
+
+        let end = self.stops.len() - 1;
+        let route = once(0).chain(order.iter().copied()).chain(once(end));
+        let mut path: Vec<Pos> = vec![self.stops[0]];
+        let mut from = 0;
+        for to in route.skip(1) {
+            // every path starts where the one before ended
+            path.extend(self.paths[from][to].iter().skip(1));
+            from = to;
+        }
+        self.order = Some(order.iter().map(|target| target - 1).collect());
+        Ok(path)
+    }
+
+    fn visited(&self, pos: Pos) -> bool {
+        self.chain.visited(pos)
+    }
+
+    fn visited_list(&self) -> &[Pos] {
+        self.chain.visited_list()
+    }
+
+    fn visited_from_end_list(&self) -> &[Pos] {
+        self.chain.visited_from_end_list()
+    }
+
+    fn expanded(&self) -> usize {
+        self.chain.expanded()
+    }
+
+    fn in_queue(&self, pos: Pos) -> bool {
+        self.chain.running().in_queue(pos)
+    }
+
+    fn is_any_angle(&self) -> bool {
+        self.chain.running().is_any_angle()
+    }
+
+    fn deepening_progress(&self) -> Option<DeepeningProgress> {
+        self.chain.running().deepening_progress()
+    }
+}
+
+/// Returns the cost of going from the start through the targets in the order to the end.
+pub fn route_cost(costs: &[Vec<f32>], order: &[usize]) -> f32 {
+    let end = costs.len() - 1;
+    let route = once(0).chain(order.iter().copied()).chain(once(end));
+    let mut from = 0;
+    route
+        .skip(1)
+        .map(|to| {
+            let cost = costs[from][to];
+            from = to;
+            cost
+        })
+        .sum()
+}
+
+/// Returns the order in which the targets should be visited, see the [module documentation](self).
+pub fn visiting_order(costs: &[Vec<f32>]) -> Vec<usize> {
+    if costs.len() - 2 <= HELD_KARP_LIMIT {
+        held_karp(costs)
+    } else {
+        two_opt(costs, nearest_neighbor(costs))
+    }
+}
+
+/// Finds the cheapest order with dynamic programming over the subsets of the visited targets.
+pub fn held_karp(costs: &[Vec<f32>]) -> Vec<usize> {
+    let targets = costs.len() - 2;
+    if targets == 0 {
+        return vec![];
+    }
+    let full = (1usize << targets) - 1;
+
+    // the cheapest cost of starting at the start, visiting the targets of the set and stopping at the last one
+    let mut cheapest = vec![vec![f32::INFINITY; targets]; full + 1];
+    let mut previous = vec![vec![None; targets]; full + 1];
+    for last in 0..targets {
+        cheapest[1 << last][last] = costs[0][last + 1];
+    }
+    for set in 1..=full {
+        for last in (0..targets).filter(|last| set & 1 << last != 0) {
+            let cost = cheapest[set][last];
+            if !cost.is_finite() {
+                continue;
+            }
+            for next in (0..targets).filter(|next| set & 1 << next == 0) {
+                let next_cost = cost + costs[last + 1][next + 1];
+                if next_cost < cheapest[set | 1 << next][next] {
+                    cheapest[set | 1 << next][next] = next_cost;
+                    previous[set | 1 << next][next] = Some(last);
+                }
+            }
+        }
+    }
+
+    let end = costs.len() - 1;
+    let last = (0..targets)
+        .min_by(|a, b| {
+            let a = cheapest[full][*a] + costs[a + 1][end];
+            let b = cheapest[full][*b] + costs[b + 1][end];
+            a.total_cmp(&b)
+        })
+        .expect("there is at least one target");
+
+    let mut order = vec![last + 1];
+    let (mut set, mut last) = (full, last);
+    while let Some(before) = previous[set][last] {
+        set &= !(1 << last);
+        last = before;
+        order.push(last + 1);
+    }
+    // unreachable targets have no predecessor, they are visited in the order of their indices
+    let unreachable = (1..=targets)
+        .filter(|target| !order.contains(target))
+        .collect::<Vec<_>>();
+    order.extend(unreachable);
+    order.reverse();
+    order
+}
+
+/// Always goes to the closest target which wasn't visited yet.
+pub fn nearest_neighbor(costs: &[Vec<f32>]) -> Vec<usize> {
+    let end = costs.len() - 1;
+    let mut unvisited = (1..end).collect::<Vec<_>>();
+    let mut order = Vec::with_capacity(unvisited.len());
+    let mut from = 0;
+    while !unvisited.is_empty() {
+        let closest = (0..unvisited.len())
+            .min_by(|a, b| costs[from][unvisited[*a]].total_cmp(&costs[from][unvisited[*b]]))
+            .expect("there is an unvisited target");
+        from = unvisited.remove(closest);
+        order.push(from);
+    }
+    order
+}
+
+/// Improves the order by reversing parts of it as long as that makes the route cheaper.
+pub fn two_opt(costs: &[Vec<f32>], mut order: Vec<usize>) -> Vec<usize> {
+    let mut cost = route_cost(costs, &order);
+    let mut improved = true;
+    while improved {
+        improved = false;
+        for first in 0..order.len() {
+            for last in first + 1..order.len() {
+                order[first..=last].reverse();
+                let reversed_cost = route_cost(costs, &order);
+                if reversed_cost < cost - 1e-4 {
+                    cost = reversed_cost;
+                    improved = true;
+                } else {
+                    order[first..=last].reverse();
+                }
+            }
+        }
+    }
+    order
+}
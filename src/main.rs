@@ -1,63 +1,141 @@
-use crate::pathfinders::{Grid, PathFindAlgorithm, PathFindAlgorithms, Pos, Tile};
-use crate::ui::grid::GridRenderMode;
+use crate::playback::{Playback, PlaybackSpeed};
+use crate::storage::Settings;
+use crate::ui::grid::{GridRenderMode, Marker};
 use crate::ui::options::{GridOptions, Options};
+use crate::url_hash::UrlHash;
 use gloo::timers::callback::Interval;
+use pathfinder_core::generators::terrain::{TerrainGenerators, TerrainOptions};
+use pathfinder_core::generators::{MazeGenerator, MazeGenerators};
+use pathfinder_core::heuristic::HeuristicOptions;
+use pathfinder_core::tour::TourSearch;
+use pathfinder_core::{
+    Grid, Movement, PathFindAlgorithm, PathFindAlgorithmStepResult, PathFindAlgorithms, Pos, Tile,
+};
+use std::cell::RefCell;
 use std::ops::Deref;
+use std::rc::Rc;
 use ui::grid::GridComponent;
 use yew::prelude::*;
 
-mod pathfinders;
+mod playback;
+mod storage;
 mod ui;
+mod url_hash;
 
 #[function_component]
 fn App() -> Html {
-    let default_grid_options = GridOptions {
-        rows: 10,
-        columns: 10,
-        start_pos: Pos { x: 0, y: 0 },
-        end_pos: Pos { x: 9, y: 9 },
+    // a shared link restores the grid, algorithm and renderer it was created with
+    let restored = use_memo(|_| UrlHash::load(), ());
+    // otherwise the options which were used last are restored
+    let settings = use_memo(|_| Settings::load(), ());
+    let default_grid_options = match &restored.grid {
+        Some(grid) => GridOptions::from(grid),
+        None => GridOptions {
+            rows: 10,
+            columns: 10,
+            start_pos: Pos { x: 0, y: 0 },
+            end_pos: Pos { x: 9, y: 9 },
+        },
     };
-    let default_render_mode = GridRenderMode::WebGL2;
+    let default_render_mode = restored
+        .render_mode
+        .or(settings.render_mode)
+        .unwrap_or(GridRenderMode::WebGL2);
+    let default_path_finder = restored
+        .path_finder
+        .or(settings.path_finder)
+        .unwrap_or(PathFindAlgorithms::BreadthFirst);
+    let default_heuristic = settings.heuristic.unwrap_or_default();
 
     let rerender = use_state(|| 0);
-    let grid: UseStateHandle<Grid> = use_state(|| GridOptions::into(default_grid_options));
+    let grid: UseStateHandle<Grid> = use_state(|| {
+        restored
+            .grid
+            .clone()
+            .unwrap_or_else(|| default_grid_options.into())
+    });
+    let selected_path_finder = use_state(|| default_path_finder);
+    let selected_heuristic = use_state(|| default_heuristic);
     let path_finder_state = use_mut_ref::<Option<Box<dyn PathFindAlgorithm>>, _>(|| None);
+    // incremental searches are kept after they found a path, so they can repair it when the grid changes
+    let path_finder_finished = use_mut_ref(|| false);
     let cached_path: UseStateHandle<Vec<Pos>> = use_state(|| Vec::with_capacity(0));
+    let cached_path_any_angle = use_mut_ref(|| false);
     let grid_render_mode: UseStateHandle<GridRenderMode> = use_state(|| default_render_mode);
+    let brush = use_mut_ref(|| Tile::Wall);
+    // tours visit the waypoints in the cheapest order instead of their own order
+    let tour = use_mut_ref(|| false);
+    let maze_generator = use_mut_ref::<Option<Box<dyn MazeGenerator>>, _>(|| None);
+    let playback = use_mut_ref(|| Playback::new(settings.playback_speed.unwrap_or_default()));
 
     // Grid Events
     let on_tile_click = {
         let grid = grid.clone();
+        let brush = brush.clone();
+        let path_finder_state = path_finder_state.clone();
+        let path_finder_finished = path_finder_finished.clone();
         Callback::from(move |pos| {
             let mut grid_mut = grid.deref().clone();
 
+            // painting over a tile with the same brush erases it again
+            let brush = *brush.borrow();
             let tile = grid_mut.tile(pos);
-            let tile = match tile {
-                Tile::None => Tile::Wall,
-                Tile::Wall => Tile::None,
-            };
+            let tile = if tile == brush { Tile::None } else { brush };
             grid_mut.set_tile(pos, tile);
 
+            notify_grid_change(&path_finder_state, &path_finder_finished, &grid_mut, &[pos]);
             grid.set(grid_mut);
         })
     };
-    let on_start_move = {
+    let on_marker_move = {
         let grid = grid.clone();
-        Callback::from(move |pos| {
+        let path_finder_state = path_finder_state.clone();
+        let path_finder_finished = path_finder_finished.clone();
+        Callback::from(move |(marker, pos): (Marker, Pos)| {
             let mut grid_mut = grid.deref().clone();
+            // markers can't be stacked on top of each other
+            if grid_mut.is_marker(pos) {
+                return;
+            }
 
-            grid_mut.set_start(pos);
+            match marker {
+                Marker::Start => grid_mut.set_start(pos),
+                Marker::End => grid_mut.set_end(pos),
+                Marker::Waypoint(index) => grid_mut.move_waypoint(index, pos),
+                Marker::Goal(index) => grid_mut.move_goal(index, pos),
+            }
 
+            notify_grid_change(&path_finder_state, &path_finder_finished, &grid_mut, &[pos]);
             grid.set(grid_mut);
         })
     };
-    let on_end_move = {
+    // new markers are put on a free tile close to the center, from where they can be dragged
+    let on_add_marker = |add: fn(&mut Grid, Pos)| {
         let grid = grid.clone();
-        Callback::from(move |pos| {
+        let path_finder_state = path_finder_state.clone();
+        let path_finder_finished = path_finder_finished.clone();
+        Callback::from(move |_| {
             let mut grid_mut = grid.deref().clone();
+            let Some(pos) = free_tile_near_center(&grid_mut) else {
+                return;
+            };
+            add(&mut grid_mut, pos);
 
-            grid_mut.set_end(pos);
+            notify_grid_change(&path_finder_state, &path_finder_finished, &grid_mut, &[pos]);
+            grid.set(grid_mut);
+        })
+    };
+    let on_add_waypoint = on_add_marker(Grid::add_waypoint);
+    let on_add_goal = on_add_marker(Grid::add_goal);
+    let on_clear_markers = {
+        let grid = grid.clone();
+        let path_finder_state = path_finder_state.clone();
+        let path_finder_finished = path_finder_finished.clone();
+        Callback::from(move |_| {
+            let mut grid_mut = grid.deref().clone();
+            grid_mut.clear_markers();
 
+            notify_grid_change(&path_finder_state, &path_finder_finished, &grid_mut, &[]);
             grid.set(grid_mut);
         })
     };
@@ -65,44 +143,44 @@ fn App() -> Html {
     // PathFinder searching
     let on_find_path = {
         let grid = grid.clone();
+        let tour = tour.clone();
         let path_finder_state = path_finder_state.clone();
+        let path_finder_finished = path_finder_finished.clone();
 
-        Callback::from(move |pathfinder: PathFindAlgorithms| {
-            let grid = grid.deref();
+        Callback::from(
+            move |(pathfinder, heuristic): (PathFindAlgorithms, HeuristicOptions)| {
+                let grid = grid.deref();
 
-            let new_state = pathfinder.make_state(grid.clone());
+                let new_state: Box<dyn PathFindAlgorithm> = if *tour.borrow() {
+                    Box::new(TourSearch::new(pathfinder, grid.clone(), heuristic))
+                } else {
+                    pathfinder.make_state(grid.clone(), heuristic)
+                };
 
-            path_finder_state.replace_with(|_| Some(new_state));
-        })
+                path_finder_state.replace_with(|_| Some(new_state));
+                path_finder_finished.replace(false);
+            },
+        )
     };
 
+    let search_player = SearchPlayer {
+        state: path_finder_state.clone(),
+        finished: path_finder_finished.clone(),
+        cached_path: cached_path.clone(),
+        cached_path_any_angle: cached_path_any_angle.clone(),
+        rerender: rerender.clone(),
+    };
     {
-        let path_finder_state = path_finder_state.clone();
-        let cached_path = cached_path.clone();
-        let rerender = rerender;
+        let search_player = search_player.clone();
+        let playback = playback.clone();
 
         use_effect_with_deps(
             move |_| {
-                let interval = Interval::new(50, move || {
-                    let path_finder_state_rc = path_finder_state.clone();
-                    let mut path_finder_state_ref = path_finder_state_rc.borrow_mut();
-                    let path_finder_state = match path_finder_state_ref.as_mut() {
-                        Some(state) => state,
-                        None => return,
-                    };
-
-                    match path_finder_state.next_step() {
-                        Ok(path) => {
-                            cached_path.set(path);
-                            // drop the reference to the state, because it would be still in use when we replace it (leading to a panic)
-                            drop(path_finder_state_ref);
-                            path_finder_state_rc.replace_with(|_| None);
-                        }
-                        Err(_) => {
-                            cached_path.set(Vec::with_capacity(0));
-                            rerender.set(0);
-                        }
-                    };
+                let interval = Interval::new(PlaybackSpeed::TICK_MILLIS, move || {
+                    let steps = playback.borrow_mut().tick();
+                    if steps > 0 {
+                        search_player.advance(steps);
+                    }
                 });
 
                 // while we still own the interval, it will keep running, for cleanup we need to drop it
@@ -111,6 +189,46 @@ fn App() -> Html {
             grid.clone(),
         );
     }
+
+    // Playback
+    let on_playing_change = {
+        let playback = playback.clone();
+        let rerender = rerender.clone();
+
+        Callback::from(move |playing: bool| {
+            playback.borrow_mut().playing = playing;
+            rerender.set(0);
+        })
+    };
+    let on_step = {
+        let search_player = search_player.clone();
+        let playback = playback.clone();
+        let rerender = rerender.clone();
+
+        // stepping pauses the playback, otherwise the step would be lost among the played ones
+        Callback::from(move |_| {
+            playback.borrow_mut().playing = false;
+            search_player.advance(1);
+            rerender.set(0);
+        })
+    };
+    let on_finish = {
+        let search_player = search_player.clone();
+
+        Callback::from(move |_| {
+            search_player.advance(u32::MAX);
+        })
+    };
+    let on_speed_change = {
+        let playback = playback.clone();
+        let rerender = rerender.clone();
+
+        Callback::from(move |speed: PlaybackSpeed| {
+            playback.borrow_mut().speed = speed;
+            rerender.set(0);
+        })
+    };
+
     // PathFinder visited
     let path_finder_visited = path_finder_state
         .borrow()
@@ -118,22 +236,133 @@ fn App() -> Html {
         .as_ref()
         .map(|state| state.visited_list().to_owned())
         .unwrap_or(Vec::with_capacity(0));
+    let path_finder_visited_from_end = path_finder_state
+        .borrow()
+        .deref()
+        .as_ref()
+        .map(|state| state.visited_from_end_list().to_owned())
+        .unwrap_or(Vec::with_capacity(0));
+    let deepening_progress = path_finder_state
+        .borrow()
+        .deref()
+        .as_ref()
+        .and_then(|state| state.deepening_progress());
+    let path_finder_reexpanded = path_finder_state
+        .borrow()
+        .deref()
+        .as_ref()
+        .map(|state| state.reexpanded_list().to_owned())
+        .unwrap_or(Vec::with_capacity(0));
+
+    // Maze and terrain generation
+    let on_generate_maze = {
+        let grid = grid.clone();
+        let cached_path = cached_path.clone();
+        let path_finder_state = path_finder_state.clone();
+        let maze_generator = maze_generator.clone();
+
+        Callback::from(move |(generator, seed): (MazeGenerators, u64)| {
+            let state = generator.make_state(grid.deref(), seed);
+
+            path_finder_state.replace_with(|_| None);
+            grid.set(state.grid().clone());
+            cached_path.set(Vec::with_capacity(0));
+            maze_generator.replace(Some(state));
+        })
+    };
+
+    let on_generate_terrain = {
+        let grid = grid.clone();
+        let cached_path = cached_path.clone();
+        let path_finder_state = path_finder_state.clone();
+        let maze_generator = maze_generator.clone();
+
+        Callback::from(
+            move |(generator, options, seed): (TerrainGenerators, TerrainOptions, u64)| {
+                maze_generator.replace(None);
+                path_finder_state.replace_with(|_| None);
+                grid.set(generator.generate(grid.deref(), options, seed));
+                cached_path.set(Vec::with_capacity(0));
+            },
+        )
+    };
+
+    {
+        let grid = grid.clone();
+        let maze_generator = maze_generator.clone();
+
+        use_effect_with_deps(
+            move |_| {
+                // the maze is built up step by step, like a search
+                let interval = Interval::new(50, move || {
+                    let mut maze_generator = maze_generator.borrow_mut();
+                    let state = match maze_generator.as_mut() {
+                        Some(state) => state,
+                        None => return,
+                    };
+
+                    let finished = state.next_step();
+                    grid.set(state.grid().clone());
+                    if finished {
+                        *maze_generator = None;
+                    }
+                });
+
+                move || drop(interval)
+            },
+            (),
+        );
+    }
 
     // Grid options
     let on_grid_options_change = {
         let grid = grid.clone();
         let cached_path = cached_path.clone();
         let path_finder_state = path_finder_state.clone();
+        let maze_generator = maze_generator.clone();
 
         Callback::from(move |new_options: GridOptions| {
-            let new_grid: Grid = new_options.into();
+            let mut new_grid: Grid = new_options.into();
+            new_grid.set_movement(grid.movement());
+            new_grid.set_wraps(grid.wraps());
+
+            maze_generator.replace(None);
+            path_finder_state.replace_with(|_| None);
+            grid.set(new_grid);
+            cached_path.set(Vec::with_capacity(0));
+        })
+    };
+
+    let on_grid_import = {
+        let grid = grid.clone();
+        let cached_path = cached_path.clone();
+        let path_finder_state = path_finder_state.clone();
+        let maze_generator = maze_generator.clone();
 
+        Callback::from(move |new_grid: Grid| {
+            maze_generator.replace(None);
             path_finder_state.replace_with(|_| None);
             grid.set(new_grid);
             cached_path.set(Vec::with_capacity(0));
         })
     };
 
+    let on_path_finder_change = {
+        let selected_path_finder = selected_path_finder.clone();
+
+        Callback::from(move |path_finder: PathFindAlgorithms| {
+            selected_path_finder.set(path_finder);
+        })
+    };
+
+    let on_heuristic_change = {
+        let selected_heuristic = selected_heuristic.clone();
+
+        Callback::from(move |heuristic: HeuristicOptions| {
+            selected_heuristic.set(heuristic);
+        })
+    };
+
     let on_grid_renderer_change = {
         let grid_render_mode = grid_render_mode.clone();
 
@@ -142,14 +371,186 @@ fn App() -> Html {
         })
     };
 
+    let on_movement_change = {
+        let grid = grid.clone();
+        let path_finder_state = path_finder_state.clone();
+        let maze_generator = maze_generator.clone();
+
+        Callback::from(move |movement: Movement| {
+            let mut grid_mut = grid.deref().clone();
+
+            grid_mut.set_movement(movement);
+            maze_generator.replace(None);
+
+            path_finder_state.replace_with(|_| None);
+            grid.set(grid_mut);
+        })
+    };
+
+    let on_wraps_change = {
+        let grid = grid.clone();
+        let path_finder_state = path_finder_state.clone();
+        let maze_generator = maze_generator.clone();
+
+        Callback::from(move |wraps: bool| {
+            let mut grid_mut = grid.deref().clone();
+
+            grid_mut.set_wraps(wraps);
+            maze_generator.replace(None);
+
+            path_finder_state.replace_with(|_| None);
+            grid.set(grid_mut);
+        })
+    };
+
+    let on_tour_change = {
+        let tour = tour.clone();
+
+        Callback::from(move |new_tour: bool| {
+            tour.replace(new_tour);
+        })
+    };
+    let on_brush_change = {
+        let brush = brush.clone();
+
+        Callback::from(move |new_brush: Tile| {
+            brush.replace(new_brush);
+        })
+    };
+
+    // keep the link in the address bar up to date, so it can be shared at any time
+    use_effect_with_deps(
+        |(grid, path_finder, render_mode)| {
+            UrlHash {
+                grid: Some(grid.clone()),
+                path_finder: Some(*path_finder),
+                render_mode: Some(*render_mode),
+            }
+            .store();
+        },
+        (
+            grid.deref().clone(),
+            *selected_path_finder,
+            *grid_render_mode,
+        ),
+    );
+
+    let playing = playback.borrow().playing;
+    let playback_speed = playback.borrow().speed;
+    use_effect_with_deps(
+        |(path_finder, heuristic, render_mode, playback_speed)| {
+            Settings {
+                path_finder: Some(*path_finder),
+                heuristic: Some(*heuristic),
+                render_mode: Some(*render_mode),
+                playback_speed: Some(*playback_speed),
+            }
+            .store();
+        },
+        (
+            *selected_path_finder,
+            *selected_heuristic,
+            *grid_render_mode,
+            playback_speed,
+        ),
+    );
+
+    let path_cost = if cached_path.is_empty() {
+        None
+    } else if *cached_path_any_angle.borrow() {
+        grid.any_angle_path_cost(&cached_path)
+    } else {
+        grid.path_cost(&cached_path)
+    };
+
     html!(
         <>
-          <Options on_find_path={on_find_path} default_grid_options={default_grid_options} on_grid_options_change={on_grid_options_change} default_grid_renderer={default_render_mode} on_grid_renderer_change={on_grid_renderer_change} />
-          <GridComponent mode={*grid_render_mode} grid={grid.deref().clone()} path={cached_path.deref().clone()} visited={path_finder_visited} on_tile_click={on_tile_click} on_start_move={on_start_move} on_end_move={on_end_move} />
+          <Options default_path_finder={default_path_finder} on_path_finder_change={on_path_finder_change} default_heuristic={default_heuristic} on_heuristic_change={on_heuristic_change} on_find_path={on_find_path} default_playing={playing} on_playing_change={on_playing_change} on_step={on_step} on_finish={on_finish} default_speed={playback_speed} on_speed_change={on_speed_change} default_grid_options={default_grid_options} on_grid_options_change={on_grid_options_change} default_movement={grid.movement()} on_movement_change={on_movement_change} default_wraps={grid.wraps()} on_wraps_change={on_wraps_change} default_grid_renderer={default_render_mode} on_grid_renderer_change={on_grid_renderer_change} on_generate_maze={on_generate_maze} on_generate_terrain={on_generate_terrain} default_brush={*brush.borrow()} on_brush_change={on_brush_change} on_add_waypoint={on_add_waypoint} on_add_goal={on_add_goal} on_clear_markers={on_clear_markers} default_tour={*tour.borrow()} on_tour_change={on_tour_change} path_cost={path_cost} deepening_progress={deepening_progress} grid={grid.deref().clone()} on_grid_import={on_grid_import} />
+          <GridComponent mode={*grid_render_mode} grid={grid.deref().clone()} path={cached_path.deref().clone()} visited={path_finder_visited} visited_from_end={path_finder_visited_from_end} reexpanded={path_finder_reexpanded} on_tile_click={on_tile_click} on_marker_move={on_marker_move} />
         </>
     )
 }
 
+/// The running search together with the state which shows its progress.
+#[derive(Clone)]
+struct SearchPlayer {
+    state: Rc<RefCell<Option<Box<dyn PathFindAlgorithm>>>>,
+    /// Whether an incremental search found its path and only waits for changes of the grid
+    finished: Rc<RefCell<bool>>,
+    cached_path: UseStateHandle<Vec<Pos>>,
+    cached_path_any_angle: Rc<RefCell<bool>>,
+    rerender: UseStateHandle<i32>,
+}
+
+impl SearchPlayer {
+    /// Does up to the given number of steps of the search and shows the path once it finished.
+    fn advance(&self, steps: u32) {
+        if *self.finished.borrow() {
+            return;
+        }
+        let mut state_ref = self.state.borrow_mut();
+        let state = match state_ref.as_mut() {
+            Some(state) => state,
+            None => return,
+        };
+
+        for _ in 0..steps {
+            match state.next_step() {
+                Ok(path) if state.is_incremental() => {
+                    self.cached_path_any_angle.replace(state.is_any_angle());
+                    self.cached_path.set(path);
+                    self.finished.replace(true);
+                    return;
+                }
+                Ok(path) => {
+                    self.cached_path_any_angle.replace(state.is_any_angle());
+                    self.cached_path.set(path);
+                    // drop the reference to the state, because it would be still in use when we replace it (leading to a panic)
+                    drop(state_ref);
+                    self.state.replace_with(|_| None);
+                    return;
+                }
+                Err(PathFindAlgorithmStepResult::InProgress) => {}
+                Err(PathFindAlgorithmStepResult::NotFound) => break,
+            }
+        }
+        self.cached_path.set(Vec::with_capacity(0));
+        self.rerender.set(0);
+    }
+}
+
+/// Lets the search repair itself after tiles of the grid changed or stops it, if it can't.
+fn notify_grid_change(
+    path_finder_state: &RefCell<Option<Box<dyn PathFindAlgorithm>>>,
+    path_finder_finished: &RefCell<bool>,
+    grid: &Grid,
+    changed: &[Pos],
+) {
+    let mut path_finder_state = path_finder_state.borrow_mut();
+    let repaired = match path_finder_state.as_mut() {
+        Some(state) => state.grid_changed(grid, changed),
+        None => return,
+    };
+
+    if repaired {
+        path_finder_finished.replace(false);
+    } else {
+        *path_finder_state = None;
+    }
+}
+
+/// Returns the tile closest to the center of the grid which is walkable and has no marker on it.
+fn free_tile_near_center(grid: &Grid) -> Option<Pos> {
+    let center = Pos {
+        x: grid.width() / 2,
+        y: grid.height() / 2,
+    };
+    (0..grid.height())
+        .flat_map(|y| (0..grid.width()).map(move |x| Pos { x, y }))
+        .filter(|pos| grid.walkable(*pos) && !grid.is_marker(*pos))
+        .min_by_key(|pos| (pos.x - center.x).abs() + (pos.y - center.y).abs())
+}
+
 fn main() {
     wasm_logger::init(wasm_logger::Config::default());
 
@@ -0,0 +1,85 @@
+/// How fast a search is played back, as the level of the speed slider.
+///
+/// The slow levels wait some ticks between two steps and the fast ones do several steps in every tick,
+/// so searches on big grids don't take ages to finish.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct PlaybackSpeed(u32);
+
+impl PlaybackSpeed {
+    /// The time between two ticks of the playback
+    pub const TICK_MILLIS: u32 = 50;
+    /// The fastest level, which does 512 steps every tick
+    pub const MAX: u32 = 12;
+    /// The level which does a single step every tick
+    const ONE_STEP_PER_TICK: u32 = 3;
+
+    pub fn new(level: u32) -> Self {
+        Self(level.min(Self::MAX))
+    }
+
+    pub fn level(self) -> u32 {
+        self.0
+    }
+
+    pub fn ticks_per_step(self) -> u32 {
+        1 << Self::ONE_STEP_PER_TICK.saturating_sub(self.0)
+    }
+
+    pub fn steps_per_tick(self) -> u32 {
+        1 << self.0.saturating_sub(Self::ONE_STEP_PER_TICK)
+    }
+
+    /// Returns how many steps are done in a second.
+    pub fn steps_per_second(self) -> f32 {
+        let millis_per_step = (self.ticks_per_step() * Self::TICK_MILLIS) as f32;
+        self.steps_per_tick() as f32 * 1000.0 / millis_per_step
+    }
+
+    /// Describes the speed for the slider.
+    /// The fast levels batch several steps into every frame, the slow ones are given in steps per second.
+    pub fn label(self) -> String {
+        match self.steps_per_tick() {
+            1 => format!("{} steps per second", self.steps_per_second()),
+            steps => format!("{steps} steps per frame"),
+        }
+    }
+}
+
+impl Default for PlaybackSpeed {
+    fn default() -> Self {
+        Self(Self::ONE_STEP_PER_TICK)
+    }
+}
+
+/// Whether the search is running and how fast, the interval of the app asks it how many steps to do.
+#[derive(Debug)]
+pub struct Playback {
+    pub playing: bool,
+    pub speed: PlaybackSpeed,
+    /// The ticks since the last step, for speeds slower than a step every tick
+    waited: u32,
+}
+
+impl Playback {
+    pub fn new(speed: PlaybackSpeed) -> Self {
+        Self {
+            playing: true,
+            speed,
+            waited: 0,
+        }
+    }
+
+    /// Returns the number of steps the search should do in this tick.
+    pub fn tick(&mut self) -> u32 {
+        if !self.playing {
+            return 0;
+        }
+
+        self.waited += 1;
+        if self.waited < self.speed.ticks_per_step() {
+            return 0;
+        }
+        self.waited = 0;
+        self.speed.steps_per_tick()
+    }
+}
@@ -0,0 +1,155 @@
+use crate::playback::PlaybackSpeed;
+use crate::ui::grid::GridRenderMode;
+use gloo::storage::{LocalStorage, Storage};
+use pathfinder_core::compact::{decode_grid, encode_grid};
+use pathfinder_core::heuristic::{HeuristicOptions, Heuristics};
+use pathfinder_core::{Grid, PathFindAlgorithms};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// The version of the data in the local storage.
+/// Raise it whenever the stored format changes and convert the older data in [migrate].
+pub const SCHEMA_VERSION: u32 = 1;
+
+const SETTINGS_KEY: &str = "wasm-pathfinder.settings";
+const SLOTS_KEY: &str = "wasm-pathfinder.slots";
+
+/// Everything is stored together with the version of its format.
+#[derive(Serialize, Deserialize)]
+struct Versioned<T> {
+    version: u32,
+    data: T,
+}
+
+/// Converts data stored by an older version of the app to the current format.
+fn migrate(version: u32, data: Value) -> Option<Value> {
+    match version {
+        SCHEMA_VERSION => Some(data),
+        _ => None,
+    }
+}
+
+fn load<T: DeserializeOwned>(key: &str) -> Option<T> {
+    let stored = LocalStorage::get::<Versioned<Value>>(key).ok()?;
+    let Some(data) = migrate(stored.version, stored.data) else {
+        log::warn!(
+            "Unable to load {} with schema version {}",
+            key,
+            stored.version
+        );
+        return None;
+    };
+
+    serde_json::from_value(data)
+        .map_err(|error| log::warn!("Unable to load {}: {}", key, error))
+        .ok()
+}
+
+fn store<T: Serialize>(key: &str, data: T) {
+    let versioned = Versioned {
+        version: SCHEMA_VERSION,
+        data,
+    };
+    if let Err(error) = LocalStorage::set(key, versioned) {
+        log::warn!("Unable to store {}: {}", key, error);
+    }
+}
+
+/// The options that were used last. Options which weren't stored or are unknown are None.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct Settings {
+    pub path_finder: Option<PathFindAlgorithms>,
+    pub heuristic: Option<HeuristicOptions>,
+    pub render_mode: Option<GridRenderMode>,
+    pub playback_speed: Option<PlaybackSpeed>,
+}
+
+/// The stored form of [Settings], which uses the ids of the options so they survive reordering.
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+struct StoredSettings {
+    algorithm: Option<String>,
+    heuristic: Option<String>,
+    heuristic_weight: Option<f32>,
+    renderer: Option<String>,
+    /// The level of the speed slider
+    playback_speed: Option<u32>,
+}
+
+impl Settings {
+    pub fn load() -> Self {
+        let stored = load::<StoredSettings>(SETTINGS_KEY).unwrap_or_default();
+
+        let heuristic = stored
+            .heuristic
+            .as_deref()
+            .and_then(Heuristics::from_id)
+            .map(|heuristic| HeuristicOptions {
+                heuristic,
+                weight: stored
+                    .heuristic_weight
+                    .unwrap_or(HeuristicOptions::default().weight),
+            });
+        Self {
+            path_finder: stored
+                .algorithm
+                .as_deref()
+                .and_then(PathFindAlgorithms::from_id),
+            heuristic,
+            render_mode: stored.renderer.as_deref().and_then(GridRenderMode::from_id),
+            playback_speed: stored.playback_speed.map(PlaybackSpeed::new),
+        }
+    }
+
+    pub fn store(&self) {
+        store(
+            SETTINGS_KEY,
+            StoredSettings {
+                algorithm: self
+                    .path_finder
+                    .map(|path_finder| path_finder.id().to_string()),
+                heuristic: self
+                    .heuristic
+                    .map(|heuristic| heuristic.heuristic.id().to_string()),
+                heuristic_weight: self.heuristic.map(|heuristic| heuristic.weight),
+                renderer: self
+                    .render_mode
+                    .map(|render_mode| render_mode.id().to_string()),
+                playback_speed: self.playback_speed.map(PlaybackSpeed::level),
+            },
+        );
+    }
+}
+
+/// A grid saved under a name.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GridSlot {
+    pub name: String,
+    /// The grid in the compact encoding, which is also used in links
+    grid: String,
+}
+
+impl GridSlot {
+    pub fn new(name: String, grid: &Grid) -> Self {
+        Self {
+            name,
+            grid: encode_grid(grid),
+        }
+    }
+
+    /// Returns the saved grid or None if it can't be decoded anymore.
+    pub fn grid(&self) -> Option<Grid> {
+        decode_grid(&self.grid)
+            .map_err(|error| log::warn!("Unable to load the slot {}: {}", self.name, error))
+            .ok()
+    }
+
+    pub fn load_all() -> Vec<Self> {
+        load(SLOTS_KEY).unwrap_or_default()
+    }
+
+    pub fn store_all(slots: &[Self]) {
+        store(SLOTS_KEY, slots);
+    }
+}
@@ -1,27 +1,19 @@
-use crate::pathfinders::{Pos, Tile};
-use crate::ui::grid::GridProps;
+use crate::ui::grid::{portal_color, tile_cost_color, GridProps, Marker};
+use pathfinder_core::text::PORTAL_LETTERS;
+use pathfinder_core::{Direction, Pos, Tile};
 use std::ops::{BitAnd, Range};
 use yew::{
     classes, function_component, html, Callback, Classes, Component, Context, DragEvent, Html,
     MouseEvent, Properties,
 };
 
-#[derive(Clone, Copy, PartialEq)]
-enum DragState {
-    None,
-    Start,
-    End,
-}
-
 pub struct DOMGridComponent {
-    drag_state: DragState,
+    /// The marker which is being dragged
+    dragging: Option<Marker>,
 }
 
-#[allow(clippy::enum_variant_names)]
 pub enum GridMsg {
-    DragStart,
-    DragEnd,
-    DragNone,
+    Drag(Option<Marker>),
 }
 
 impl Component for DOMGridComponent {
@@ -29,23 +21,13 @@ impl Component for DOMGridComponent {
     type Properties = GridProps;
 
     fn create(_ctx: &Context<Self>) -> Self {
-        Self {
-            drag_state: DragState::None,
-        }
+        Self { dragging: None }
     }
 
     fn update(&mut self, _ctx: &Context<Self>, msg: Self::Message) -> bool {
         match msg {
-            GridMsg::DragStart => {
-                self.drag_state = DragState::Start;
-                false
-            }
-            GridMsg::DragEnd => {
-                self.drag_state = DragState::End;
-                false
-            }
-            GridMsg::DragNone => {
-                self.drag_state = DragState::None;
+            GridMsg::Drag(marker) => {
+                self.dragging = marker;
                 false
             }
         }
@@ -54,14 +36,10 @@ impl Component for DOMGridComponent {
     fn view(&self, ctx: &Context<Self>) -> Html {
         let grid = &ctx.props().grid;
         let props = ctx.props();
-        let drag_state = &self.drag_state;
-        let drag_state_change = ctx.link().callback(|new: DragState| match new {
-            DragState::None => GridMsg::DragNone,
-            DragState::Start => GridMsg::DragStart,
-            DragState::End => GridMsg::DragEnd,
-        });
-        let start = grid.start();
-        let end = grid.end();
+        let dragging = self.dragging;
+        let drag_change = ctx.link().callback(GridMsg::Drag);
+        let hex = grid.movement().is_hex();
+        let (wraps_horizontally, wraps_vertically) = grid.wrapping_axes();
 
         let style = {
             format!(
@@ -73,55 +51,69 @@ impl Component for DOMGridComponent {
             )
         };
 
+        // the path might be made of straight lines, so the tiles between its positions have to be filled in,
+        // except where it goes through a portal or over the edge of a wrapping grid
+        let path_tiles = props
+            .path
+            .windows(2)
+            .flat_map(|line| match grid.portal_partner(line[0]) {
+                Some(partner) if partner == line[1] => line.to_vec(),
+                _ if grid.crosses_edge(line[0], line[1]) => line.to_vec(),
+                _ => grid.line(line[0], line[1]),
+            })
+            .chain(props.path.first().copied())
+            .collect::<Vec<_>>();
+
         html!(
-            <div style={style} class={classes!("grid", "dom-grid")}>
+            <div style={style} class={classes!("grid", "dom-grid", hex.then_some("hex-grid"))}>
                 {for gen_2d_iter(0..grid.height(), 0..grid.width()).map(|(y, x)| {
                     let pos = Pos { x, y };
                     let tile = grid.tile(pos);
-                    let is_tile_start= pos == start;
-                    let is_tile_end = pos == end;
-                    let is_tile_path = props.path.contains(&pos);
+                    let marker = Marker::at(grid, pos);
+                    let is_tile_path = path_tiles.contains(&pos);
                     let is_visited = props.visited.contains(&pos);
+                    let is_visited_from_end = props.visited_from_end.contains(&pos);
+                    let is_reexpanded = props.reexpanded.contains(&pos);
 
                     let is_line_end = pos.x == grid.width() - 1;
+                    let is_shifted = hex && y % 2 == 1;
+                    // the edges which lead to the opposite side are dashed
+                    let wrap_edges = classes!(
+                        (wraps_horizontally && x == 0).then_some("tile-wrap-left"),
+                        (wraps_horizontally && x == grid.width() - 1).then_some("tile-wrap-right"),
+                        (wraps_vertically && y == 0).then_some("tile-wrap-top"),
+                        (wraps_vertically && y == grid.height() - 1).then_some("tile-wrap-bottom"),
+                    );
 
                     let tile_on_tile_click = {
                         let on_tile_click = props.on_tile_click.clone();
-                        let on_start_move = props.on_start_move.clone();
-                        let on_end_move = props.on_end_move.clone();
-                        let drag_state = *drag_state;
-                        let drag_state_change = drag_state_change.clone();
+                        let on_marker_move = props.on_marker_move.clone();
+                        let drag_change = drag_change.clone();
 
                         Callback::from(move |_e| {
-                            if is_tile_start {
-                                drag_state_change.emit(DragState::Start);
-                            } else if is_tile_end {
-                                drag_state_change.emit(DragState::End);
+                            if marker.is_some() {
+                                drag_change.emit(marker);
                             } else {
-                                match drag_state {
-                                    DragState::Start => on_start_move.emit(pos),
-                                    DragState::End => on_end_move.emit(pos),
-                                    DragState::None => on_tile_click.emit(pos)
+                                match dragging {
+                                    Some(dragging) => on_marker_move.emit((dragging, pos)),
+                                    None => on_tile_click.emit(pos)
                                 }
                             }
                         })
                     };
                     let on_tile_mouse_enter = {
-                        let on_start_move = props.on_start_move.clone();
-                        let on_end_move = props.on_end_move.clone();
+                        let on_marker_move = props.on_marker_move.clone();
                         let on_tile_click = props.on_tile_click.clone();
-                        let drag_state = *drag_state;
-                        let drag_state_change = drag_state_change.clone();
+                        let drag_change = drag_change.clone();
 
                         Callback::from(move |mouse_down| {
                             if mouse_down {
-                                match drag_state {
-                                    DragState::Start => on_start_move.emit(pos),
-                                    DragState::End => on_end_move.emit(pos),
-                                    DragState::None => on_tile_click.emit(pos),
+                                match dragging {
+                                    Some(dragging) => on_marker_move.emit((dragging, pos)),
+                                    None => on_tile_click.emit(pos),
                                 }
                             } else {
-                                drag_state_change.emit(DragState::None);
+                                drag_change.emit(None);
                             }
                         })
                     };
@@ -130,10 +122,13 @@ impl Component for DOMGridComponent {
                         <>
                             <TileComponent
                                 tile={tile}
-                                is_start={is_tile_start}
-                                is_end={is_tile_end}
+                                marker={marker}
                                 is_path={is_tile_path}
                                 is_visited={is_visited}
+                                is_visited_from_end={is_visited_from_end}
+                                is_reexpanded={is_reexpanded}
+                                is_shifted={is_shifted}
+                                wrap_edges={wrap_edges}
                                 on_tile_click={tile_on_tile_click}
                                 on_tile_mouse_enter={on_tile_mouse_enter}
                                 tile_key={pos}
@@ -161,10 +156,15 @@ where
 #[derive(Properties, PartialEq)]
 struct TileProps {
     pub tile: Tile,
-    pub is_start: bool,
-    pub is_end: bool,
+    pub marker: Option<Marker>,
     pub is_path: bool,
     pub is_visited: bool,
+    pub is_visited_from_end: bool,
+    pub is_reexpanded: bool,
+    /// Whether the tile is in an odd row of a hex grid, which is drawn half a tile further right
+    pub is_shifted: bool,
+    /// The classes of the edges of the tile which wrap around to the opposite side of the grid
+    pub wrap_edges: Classes,
     pub on_tile_click: Callback<()>,
     pub on_tile_mouse_enter: Callback<bool>,
     pub tile_key: Pos,
@@ -172,21 +172,58 @@ struct TileProps {
 #[function_component]
 fn TileComponent(props: &TileProps) -> Html {
     let tile = &props.tile;
-    let class = match (
-        tile,
-        props.is_start,
-        props.is_end,
-        props.is_path,
-        props.is_visited,
-    ) {
-        (_, true, _, _, _) => "tile-start",
-        (_, _, true, _, _) => "tile-end",
-        (Tile::Wall, _, _, _, _) => "tile-wall",
-        (_, _, _, _, true) => "tile-visited",
-        (_, _, _, true, _) => "tile-path",
-        (Tile::None, _, _, _, _) => "tile-none",
+    let class = match (tile, props.marker, props.is_path, props.is_visited) {
+        (_, Some(Marker::Start), _, _) => "tile-start",
+        (_, Some(Marker::End), _, _) => "tile-end",
+        (_, Some(Marker::Waypoint(_)), _, _) => "tile-waypoint",
+        (_, Some(Marker::Goal(_)), _, _) => "tile-goal",
+        (Tile::Wall, _, _, _) => "tile-wall",
+        (_, _, true, _) => "tile-path",
+        _ if props.is_reexpanded => "tile-reexpanded",
+        (_, _, _, true) => "tile-visited",
+        _ if props.is_visited_from_end => "tile-visited-from-end",
+        (Tile::None, _, _, _) => "tile-none",
+        (Tile::Cost(_), _, _, _) => "tile-cost",
+        (Tile::Portal(_), _, _, _) => "tile-portal",
+        (Tile::OneWay(_), _, _, _) => "tile-one-way",
+    };
+    // waypoints show their number, so the order in which they are passed can be seen,
+    // portals the letter they have in maps and one-way tiles their direction
+    let label = match (props.marker, tile) {
+        (Some(Marker::Waypoint(index)), _) => Some((index + 1).to_string()),
+        (Some(_), _) => None,
+        (None, Tile::Portal(number)) => PORTAL_LETTERS
+            .chars()
+            .nth(*number as usize)
+            .map(String::from),
+        (None, Tile::OneWay(direction)) => Some(
+            match direction {
+                Direction::Up => "↑",
+                Direction::Down => "↓",
+                Direction::Left => "←",
+                Direction::Right => "→",
+            }
+            .to_string(),
+        ),
+        _ => None,
+    };
+    let tile_classes: Classes = classes!(
+        "tile",
+        class,
+        props.is_shifted.then_some("tile-shifted"),
+        props.wrap_edges.clone()
+    );
+    let style = match tile {
+        Tile::Cost(cost) if class == "tile-cost" => {
+            let (r, g, b) = tile_cost_color(*cost);
+            Some(format!("background-color: rgb({r}, {g}, {b});"))
+        }
+        Tile::Portal(number) if class == "tile-portal" => {
+            let (r, g, b) = portal_color(*number);
+            Some(format!("background-color: rgb({r}, {g}, {b});"))
+        }
+        _ => None,
     };
-    let tile_classes: Classes = classes!("tile", class);
 
     const LEFT_MOUSE_BUTTON_BITMASK: u16 = 1;
     let on_mouse_down = {
@@ -213,6 +250,6 @@ fn TileComponent(props: &TileProps) -> Html {
     let prevent_drag = { Callback::from(move |e: DragEvent| e.prevent_default()) };
 
     html!(
-        <div class={tile_classes} key={format!("{}-{}", props.tile_key.x, props.tile_key.x)} onmousedown={on_mouse_down} onmouseenter={on_mouse_enter} ondragstart={prevent_drag} />
+        <div class={tile_classes} style={style} key={format!("{}-{}", props.tile_key.x, props.tile_key.x)} onmousedown={on_mouse_down} onmouseenter={on_mouse_enter} ondragstart={prevent_drag}>{label}</div>
     )
 }
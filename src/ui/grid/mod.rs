@@ -1,34 +1,118 @@
-use crate::pathfinders::{Grid, Pos};
 use crate::ui::grid::dom::DOMGridComponent;
 use crate::ui::grid::webgl2::WebGL2GridComponent;
+use pathfinder_core::{Grid, Pos, Tile};
 use yew::{function_component, html, Callback, Html, Properties};
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum GridRenderMode {
     WebGL2,
     Dom,
 }
 
+impl GridRenderMode {
+    /// Returns the identifier of the renderer, which is used to select it in the options and in links.
+    pub fn id(&self) -> &'static str {
+        match self {
+            GridRenderMode::Dom => "dom",
+            GridRenderMode::WebGL2 => "webgl_2",
+        }
+    }
+
+    /// Returns the renderer with the identifier, see [GridRenderMode::id].
+    pub fn from_id(id: &str) -> Option<Self> {
+        match id {
+            "dom" => Some(GridRenderMode::Dom),
+            "webgl_2" => Some(GridRenderMode::WebGL2),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Properties, Clone, PartialEq)]
 pub struct GridProps {
     #[prop_or(GridRenderMode::WebGL2)]
     pub mode: GridRenderMode,
     pub grid: Grid,
+    /// The found path, either as neighboring tiles or as the corners of straight lines between them
     #[prop_or_default]
     pub path: Vec<Pos>,
     #[prop_or_default]
     pub visited: Vec<Pos>,
+    /// Tiles visited by the half of a bidirectional search which starts at the end
     #[prop_or_default]
-    pub on_tile_click: Callback<Pos>,
+    pub visited_from_end: Vec<Pos>,
+    /// Tiles an incremental search expanded again after the grid changed
+    #[prop_or_default]
+    pub reexpanded: Vec<Pos>,
     #[prop_or_default]
-    pub on_start_move: Callback<Pos>,
+    pub on_tile_click: Callback<Pos>,
+    /// Called with the new position while a marker is dragged
     #[prop_or_default]
-    pub on_end_move: Callback<Pos>,
+    pub on_marker_move: Callback<(Marker, Pos)>,
+}
+
+/// A tile of the grid with a special meaning, which can be dragged onto another tile.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Marker {
+    Start,
+    End,
+    /// The waypoint with the index, see [Grid::waypoints]
+    Waypoint(usize),
+    /// The goal with the index, see [Grid::goals]
+    Goal(usize),
+}
+
+impl Marker {
+    /// Returns the marker at the position, the start and the end are on top of waypoints and goals.
+    pub fn at(grid: &Grid, pos: Pos) -> Option<Self> {
+        if pos == grid.start() {
+            Some(Marker::Start)
+        } else if pos == grid.end() {
+            Some(Marker::End)
+        } else if let Some(index) = grid
+            .waypoints()
+            .iter()
+            .position(|waypoint| *waypoint == pos)
+        {
+            Some(Marker::Waypoint(index))
+        } else {
+            let index = grid.goals().iter().position(|goal| *goal == pos)?;
+            Some(Marker::Goal(index))
+        }
+    }
 }
 
 pub mod dom;
 pub mod webgl2;
 
+/// Returns the rgb color of a weighted tile, ramping from a light green for cheap tiles to a dark brown for expensive tiles.
+pub fn tile_cost_color(cost: u8) -> (u8, u8, u8) {
+    const CHEAP: (f32, f32, f32) = (170.0, 230.0, 130.0);
+    const EXPENSIVE: (f32, f32, f32) = (110.0, 65.0, 25.0);
+
+    let t = (cost.clamp(1, Tile::MAX_COST) - 1) as f32 / (Tile::MAX_COST - 1) as f32;
+    let lerp = |a: f32, b: f32| (a + (b - a) * t).round() as u8;
+
+    (
+        lerp(CHEAP.0, EXPENSIVE.0),
+        lerp(CHEAP.1, EXPENSIVE.1),
+        lerp(CHEAP.2, EXPENSIVE.2),
+    )
+}
+
+/// Returns the rgb color of a portal, portals with the same number share a color so pairs can be told apart.
+pub fn portal_color(number: u8) -> (u8, u8, u8) {
+    const COLORS: [(u8, u8, u8); 6] = [
+        (150, 60, 220),
+        (230, 120, 0),
+        (0, 150, 150),
+        (200, 40, 140),
+        (110, 110, 30),
+        (60, 90, 160),
+    ];
+    COLORS[number as usize % COLORS.len()]
+}
+
 #[function_component]
 pub fn GridComponent(props: &GridProps) -> Html {
     let props = props.clone();
@@ -1,6 +1,6 @@
-use crate::pathfinders::{Grid, Pos, Tile};
-use crate::ui::grid::GridProps;
+use crate::ui::grid::{portal_color, tile_cost_color, GridProps, Marker};
 use js_sys::Float32Array;
+use pathfinder_core::{Grid, Pos, Tile};
 use std::cell::RefCell;
 
 use std::rc::Rc;
@@ -19,8 +19,7 @@ pub struct WebGL2GridComponent {
     grid_sender: Sender<VisualState>,
     grid_receiver: Option<Receiver<VisualState>>,
     old_pos: Option<Pos>,
-    is_dragging_start: bool, // Old tile is start
-    is_dragging_end: bool,   // Old tile is end
+    dragging: Option<Marker>,
 }
 
 pub enum WebGL2GridMsg {
@@ -42,8 +41,7 @@ impl Component for WebGL2GridComponent {
             grid_sender: sender,
             grid_receiver: Some(receiver),
             old_pos: None,
-            is_dragging_start: false,
-            is_dragging_end: false,
+            dragging: None,
         }
     }
 
@@ -52,7 +50,7 @@ impl Component for WebGL2GridComponent {
             WebGL2GridMsg::MouseEvent { event } => {
                 let grid = &ctx.props().grid;
 
-                let new_pos = match Self::mouse_event_to_tile(&event, grid.width(), grid.height()) {
+                let new_pos = match Self::mouse_event_to_tile(&event, grid) {
                     Some(pos) => pos,
                     None => return false,
                 };
@@ -60,19 +58,12 @@ impl Component for WebGL2GridComponent {
                 let mouse_down = event.buttons() & LEFT_MOUSE_BUTTON != 0;
 
                 if mouse_down {
-                    if ctx.props().grid.start() == new_pos || self.is_dragging_start {
-                        self.is_dragging_start = true;
+                    // keep dragging the same marker, even if the mouse passes over another one
+                    if let Some(marker) = self.dragging.or_else(|| Marker::at(grid, new_pos)) {
+                        self.dragging = Some(marker);
                         if let Some(old_pos) = self.old_pos {
                             if old_pos != new_pos {
-                                ctx.props().on_start_move.emit(new_pos);
-                            }
-                        }
-                        self.old_pos = Some(new_pos);
-                    } else if ctx.props().grid.end() == new_pos || self.is_dragging_end {
-                        self.is_dragging_end = true;
-                        if let Some(old_pos) = self.old_pos {
-                            if old_pos != new_pos {
-                                ctx.props().on_end_move.emit(new_pos);
+                                ctx.props().on_marker_move.emit((marker, new_pos));
                             }
                         }
                         self.old_pos = Some(new_pos);
@@ -88,8 +79,7 @@ impl Component for WebGL2GridComponent {
                     }
                 } else {
                     self.old_pos = None;
-                    self.is_dragging_start = false;
-                    self.is_dragging_end = false;
+                    self.dragging = None;
                 }
             }
         }
@@ -145,15 +135,20 @@ impl Component for WebGL2GridComponent {
 }
 
 impl WebGL2GridComponent {
-    fn tile_size(canvas_width: i32, canvas_height: i32, grid_width: i32, grid_height: i32) -> f32 {
+    /// Returns the width of a tile in pixels, the grid size is given in tile widths, see [TileLayout::size].
+    fn tile_size(
+        canvas_width: i32,
+        canvas_height: i32,
+        (grid_width, grid_height): (f32, f32),
+    ) -> f32 {
         let canvas_shortest_side = canvas_width.min(canvas_height);
 
         let grid_longest_side = grid_width.max(grid_height);
 
-        canvas_shortest_side as f32 / grid_longest_side as f32
+        canvas_shortest_side as f32 / grid_longest_side
     }
 
-    fn mouse_event_to_tile(event: &MouseEvent, grid_width: i32, grid_height: i32) -> Option<Pos> {
+    fn mouse_event_to_tile(event: &MouseEvent, grid: &Grid) -> Option<Pos> {
         let canvas_element = event
             .target()
             .expect("Unable to get target")
@@ -161,27 +156,15 @@ impl WebGL2GridComponent {
             .expect("Unable to cast target");
         let rect = canvas_element.get_bounding_client_rect();
 
-        let tile_size = Self::tile_size(
-            rect.width() as i32,
-            rect.height() as i32,
-            grid_width,
-            grid_height,
-        );
+        let layout = TileLayout::of(grid);
+        let tile_size =
+            Self::tile_size(rect.width() as i32, rect.height() as i32, layout.size(grid));
 
         let mouse_x = event.client_x() as f32 - rect.left() as f32;
         let mouse_y = event.client_y() as f32 - rect.top() as f32;
 
-        let tile_x = (mouse_x / tile_size) as i32;
-        let tile_y = (mouse_y / tile_size) as i32;
-
-        if tile_x >= 0 && tile_x < grid_width && tile_y >= 0 && tile_y < grid_height {
-            Some(Pos {
-                x: tile_x,
-                y: tile_y,
-            })
-        } else {
-            None
-        }
+        let pos = layout.tile_at(mouse_x / tile_size, mouse_y / tile_size);
+        grid.tile_opt(pos).map(|_| pos)
     }
 
     fn start_render_loop(&mut self, gl: GL) {
@@ -235,6 +218,8 @@ struct VisualState {
     grid: Grid,
     path: Vec<Pos>,
     visited: Vec<Pos>,
+    visited_from_end: Vec<Pos>,
+    reexpanded: Vec<Pos>,
 }
 
 impl From<&GridProps> for VisualState {
@@ -242,15 +227,116 @@ impl From<&GridProps> for VisualState {
         let grid = props.grid.clone();
         let path = props.path.clone();
         let visited = props.visited.clone();
+        let visited_from_end = props.visited_from_end.clone();
+        let reexpanded = props.reexpanded.clone();
 
         Self {
             grid,
             path,
             visited,
+            visited_from_end,
+            reexpanded,
         }
     }
 }
 
+/// The distance between the rows of hexagons which are one tile wide, √3 / 2.
+const HEX_ROW_HEIGHT: f32 = 0.866_025_4;
+/// The distance between the center and the corners of hexagons which are one tile wide, 1 / √3.
+const HEX_RADIUS: f32 = 0.577_350_3;
+
+/// Where the tiles are drawn, in tile widths.
+/// Square tiles are placed in rows and columns, hexagons with their odd rows shifted half a tile to the right,
+/// see [Movement::Hex](pathfinder_core::Movement::Hex).
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct TileLayout {
+    hex: bool,
+}
+
+impl TileLayout {
+    fn of(grid: &Grid) -> Self {
+        Self {
+            hex: grid.movement().is_hex(),
+        }
+    }
+
+    /// Returns the width and height of the whole grid.
+    fn size(&self, grid: &Grid) -> (f32, f32) {
+        let (width, height) = (grid.width() as f32, grid.height() as f32);
+        if self.hex {
+            let shift = if grid.height() > 1 { 0.5 } else { 0.0 };
+            (
+                width + shift,
+                (height - 1.0) * HEX_ROW_HEIGHT + 2.0 * HEX_RADIUS,
+            )
+        } else {
+            (width, height)
+        }
+    }
+
+    /// Returns how far the tiles of the row are shifted to the right.
+    fn shift(&self, row: i32) -> f32 {
+        if self.hex && row.rem_euclid(2) == 1 {
+            0.5
+        } else {
+            0.0
+        }
+    }
+
+    /// Returns the top left corner of the box around the tile.
+    fn corner(&self, pos: Pos) -> (f32, f32) {
+        if self.hex {
+            (
+                pos.x as f32 + self.shift(pos.y),
+                pos.y as f32 * HEX_ROW_HEIGHT,
+            )
+        } else {
+            (pos.x as f32, pos.y as f32)
+        }
+    }
+
+    /// Returns half the height of the box around a tile.
+    fn half_height(&self) -> f32 {
+        if self.hex {
+            HEX_RADIUS
+        } else {
+            0.5
+        }
+    }
+
+    fn center(&self, pos: Pos) -> (f32, f32) {
+        let (x, y) = self.corner(pos);
+        (x + 0.5, y + self.half_height())
+    }
+
+    /// Returns the tile under the point, which might be outside of the grid.
+    fn tile_at(&self, x: f32, y: f32) -> Pos {
+        if !self.hex {
+            return Pos {
+                x: x.floor() as i32,
+                y: y.floor() as i32,
+            };
+        }
+
+        // hexagons are the areas closest to their centers, so the closest center of the tiles around the point wins
+        let row = ((y - HEX_RADIUS) / HEX_ROW_HEIGHT).round() as i32;
+        let distance = |pos: &Pos| {
+            let (center_x, center_y) = self.center(*pos);
+            (center_x - x).hypot(center_y - y)
+        };
+        (row - 1..=row + 1)
+            .flat_map(|y_candidate| {
+                let column = (x - self.shift(y_candidate) - 0.5).round() as i32;
+                (column - 1..=column + 1).map(move |x_candidate| Pos {
+                    x: x_candidate,
+                    y: y_candidate,
+                })
+            })
+            .min_by(|a, b| distance(a).total_cmp(&distance(b)))
+            .expect("there are tiles around every point")
+    }
+}
+
 struct GlGridRenderer {
     state: Option<VisualState>,
     tile_size: f32,
@@ -258,8 +344,19 @@ struct GlGridRenderer {
     gl: GL,
     shader_program: Option<WebGlProgram>,
     vao: Option<WebGlVertexArrayObject>,
+    hex_vao: Option<WebGlVertexArrayObject>,
+    path_vao: Option<WebGlVertexArrayObject>,
+    arrow_vao: Option<WebGlVertexArrayObject>,
+    edge_vao: Option<WebGlVertexArrayObject>,
     // buffers
     position_buffer: Option<WebGlBuffer>,
+    hex_buffer: Option<WebGlBuffer>,
+    path_buffer: Option<WebGlBuffer>,
+    path_vertex_count: i32,
+    arrow_buffer: Option<WebGlBuffer>,
+    arrow_vertex_count: i32,
+    edge_buffer: Option<WebGlBuffer>,
+    edge_vertex_count: i32,
     // shader program locations
     resolution_location: Option<WebGlUniformLocation>,
     tile_size_location: Option<WebGlUniformLocation>,
@@ -275,7 +372,18 @@ impl GlGridRenderer {
             gl,
             shader_program: None,
             vao: None,
+            hex_vao: None,
+            path_vao: None,
+            arrow_vao: None,
+            edge_vao: None,
             position_buffer: None,
+            hex_buffer: None,
+            path_buffer: None,
+            path_vertex_count: 0,
+            arrow_buffer: None,
+            arrow_vertex_count: 0,
+            edge_buffer: None,
+            edge_vertex_count: 0,
             resolution_location: None,
             tile_size_location: None,
             position_location: None,
@@ -383,6 +491,65 @@ impl GlGridRenderer {
 
         // Save position buffer
         self.position_buffer = Some(position_buffer);
+
+        // Create/Load the hexagon, which is drawn instead of the square on hex grids
+        let (hex_vao, hex_buffer) = Self::create_overlay(gl, position_location as u32);
+        gl.bind_buffer(GL::ARRAY_BUFFER, Some(&hex_buffer));
+        gl.buffer_data_with_array_buffer_view(
+            GL::ARRAY_BUFFER,
+            &Float32Array::from(Self::hex_vertices().as_slice()),
+            GL::STATIC_DRAW,
+        );
+        self.hex_vao = Some(hex_vao);
+        self.hex_buffer = Some(hex_buffer);
+
+        // Create the path, arrow and edge buffers, which are filled once the state changes
+        let (path_vao, path_buffer) = Self::create_overlay(gl, position_location as u32);
+        self.path_vao = Some(path_vao);
+        self.path_buffer = Some(path_buffer);
+        let (arrow_vao, arrow_buffer) = Self::create_overlay(gl, position_location as u32);
+        self.arrow_vao = Some(arrow_vao);
+        self.arrow_buffer = Some(arrow_buffer);
+        let (edge_vao, edge_buffer) = Self::create_overlay(gl, position_location as u32);
+        self.edge_vao = Some(edge_vao);
+        self.edge_buffer = Some(edge_buffer);
+    }
+
+    /// Creates a buffer for triangles in tile coordinates together with the vertex array which draws them.
+    fn create_overlay(gl: &GL, position_location: u32) -> (WebGlVertexArrayObject, WebGlBuffer) {
+        let buffer = gl.create_buffer().expect("Unable to create buffer");
+        let vao = gl
+            .create_vertex_array()
+            .expect("Unable to create vertex array");
+        gl.bind_vertex_array(Some(&vao));
+        gl.bind_buffer(GL::ARRAY_BUFFER, Some(&buffer));
+        gl.vertex_attrib_pointer_with_i32(position_location, 2, GL::FLOAT, false, 0, 0);
+        gl.enable_vertex_attrib_array(position_location);
+        gl.bind_vertex_array(None);
+
+        (vao, buffer)
+    }
+
+    /// Creates the triangles of a pointy topped hexagon in the box around a tile of a hex grid, see [TileLayout].
+    fn hex_vertices() -> Vec<f32> {
+        // like the squares, the hexagons are a bit smaller to have lines between the tiles
+        const RADIUS: f32 = HEX_RADIUS * 0.95;
+        let (center_x, center_y) = (0.5, HEX_RADIUS);
+        let corner = |index: usize| {
+            let angle = (30.0 + 60.0 * index as f32).to_radians();
+            (
+                center_x + RADIUS * angle.cos(),
+                center_y + RADIUS * angle.sin(),
+            )
+        };
+
+        (0..6)
+            .flat_map(|index| {
+                let (from_x, from_y) = corner(index);
+                let (to_x, to_y) = corner(index + 1);
+                [center_x, center_y, from_x, from_y, to_x, to_y]
+            })
+            .collect()
     }
 
     fn update_state(&mut self, state: VisualState) {
@@ -392,13 +559,182 @@ impl GlGridRenderer {
         self.tile_size = WebGL2GridComponent::tile_size(
             gl.drawing_buffer_width(),
             gl.drawing_buffer_height(),
-            grid.height(),
-            grid.width(),
+            TileLayout::of(grid).size(grid),
+        );
+
+        let path_vertices = Self::path_vertices(grid, &state.path);
+        gl.bind_buffer(GL::ARRAY_BUFFER, self.path_buffer.as_ref());
+        gl.buffer_data_with_array_buffer_view(
+            GL::ARRAY_BUFFER,
+            &Float32Array::from(path_vertices.as_slice()),
+            GL::DYNAMIC_DRAW,
+        );
+        self.path_vertex_count = (path_vertices.len() / 2) as i32;
+
+        let arrow_vertices = Self::arrow_vertices(grid);
+        gl.bind_buffer(GL::ARRAY_BUFFER, self.arrow_buffer.as_ref());
+        gl.buffer_data_with_array_buffer_view(
+            GL::ARRAY_BUFFER,
+            &Float32Array::from(arrow_vertices.as_slice()),
+            GL::DYNAMIC_DRAW,
         );
+        self.arrow_vertex_count = (arrow_vertices.len() / 2) as i32;
+
+        let edge_vertices = Self::edge_vertices(grid);
+        gl.bind_buffer(GL::ARRAY_BUFFER, self.edge_buffer.as_ref());
+        gl.buffer_data_with_array_buffer_view(
+            GL::ARRAY_BUFFER,
+            &Float32Array::from(edge_vertices.as_slice()),
+            GL::DYNAMIC_DRAW,
+        );
+        self.edge_vertex_count = (edge_vertices.len() / 2) as i32;
 
         self.state = Some(state);
     }
 
+    /// Creates the triangles of a line through the centers of the path's tiles, in tile coordinates.
+    /// Where the path goes through a portal, the line jumps to its partner.
+    /// Where it goes over the edge of a wrapping grid, the line leaves the grid and comes back in on the other side.
+    fn path_vertices(grid: &Grid, path: &[Pos]) -> Vec<f32> {
+        const HALF_WIDTH: f32 = 0.1;
+        let layout = TileLayout::of(grid);
+        let center = |pos: &Pos| layout.center(*pos);
+        let halfway = |(from_x, from_y): (f32, f32), (to_x, to_y): (f32, f32)| {
+            ((from_x + to_x) / 2.0, (from_y + to_y) / 2.0)
+        };
+        let mut lines = Vec::new();
+
+        for step in path.windows(2) {
+            if grid.portal_partner(step[0]) == Some(step[1]) {
+                continue;
+            }
+            if grid.crosses_edge(step[0], step[1]) {
+                // the tiles the step would reach if the grid didn't wrap lie outside of it
+                let offset = grid.step_offset(step[0], step[1]);
+                let (from, to) = (center(&step[0]), center(&step[1]));
+                lines.push((from, halfway(from, center(&(step[0] + offset)))));
+                lines.push((halfway(to, center(&(step[1] - offset))), to));
+            } else {
+                lines.push((center(&step[0]), center(&step[1])));
+            }
+        }
+
+        let mut vertices = Vec::new();
+        for ((from_x, from_y), (to_x, to_y)) in lines {
+            // move the corners of the line sideways by half its width
+            let length = (to_x - from_x).hypot(to_y - from_y);
+            let side_x = -(to_y - from_y) / length * HALF_WIDTH;
+            let side_y = (to_x - from_x) / length * HALF_WIDTH;
+
+            vertices.extend([
+                from_x + side_x,
+                from_y + side_y,
+                from_x - side_x,
+                from_y - side_y,
+                to_x + side_x,
+                to_y + side_y,
+                to_x + side_x,
+                to_y + side_y,
+                from_x - side_x,
+                from_y - side_y,
+                to_x - side_x,
+                to_y - side_y,
+            ]);
+        }
+        // squares on the corners, so the lines are joined without gaps
+        for (x, y) in path.iter().map(center) {
+            let (left, right) = (x - HALF_WIDTH, x + HALF_WIDTH);
+            let (top, bottom) = (y - HALF_WIDTH, y + HALF_WIDTH);
+            vertices.extend([
+                left, top, left, bottom, right, top, // First triangle
+                right, top, left, bottom, right, bottom, // Second triangle
+            ]);
+        }
+
+        vertices
+    }
+
+    /// Creates a triangle on every one-way tile which points in its direction, in tile coordinates.
+    fn arrow_vertices(grid: &Grid) -> Vec<f32> {
+        let layout = TileLayout::of(grid);
+        let mut vertices = Vec::new();
+        for y in 0..grid.height() {
+            for x in 0..grid.width() {
+                let direction = match grid.tile(Pos { x, y }) {
+                    Tile::OneWay(direction) => direction.offset(),
+                    _ => continue,
+                };
+                let (center_x, center_y) = layout.center(Pos { x, y });
+                let (dir_x, dir_y) = (direction.x as f32, direction.y as f32);
+                // the side of the arrow is perpendicular to its direction
+                let (side_x, side_y) = (-dir_y, dir_x);
+
+                vertices.extend([
+                    center_x + dir_x * 0.3,
+                    center_y + dir_y * 0.3,
+                    center_x - dir_x * 0.2 + side_x * 0.25,
+                    center_y - dir_y * 0.2 + side_y * 0.25,
+                    center_x - dir_x * 0.2 - side_x * 0.25,
+                    center_y - dir_y * 0.2 - side_y * 0.25,
+                ]);
+            }
+        }
+        vertices
+    }
+
+    /// Creates dashes along the edges a wrapping grid wraps around, in tile coordinates.
+    fn edge_vertices(grid: &Grid) -> Vec<f32> {
+        const THICKNESS: f32 = 0.08;
+        const HALF_LENGTH: f32 = 0.25;
+        let layout = TileLayout::of(grid);
+        let (horizontally, vertically) = grid.wrapping_axes();
+        let (last_x, last_y) = (grid.width() - 1, grid.height() - 1);
+
+        // every dash is a rectangle given by its left, top, right and bottom side
+        let mut dashes = Vec::new();
+        if horizontally {
+            for y in 0..grid.height() {
+                for (x, side) in [(0, 0.0), (last_x, 1.0 - THICKNESS)] {
+                    let (corner_x, _) = layout.corner(Pos { x, y });
+                    let (_, center_y) = layout.center(Pos { x, y });
+                    let left = corner_x + side;
+                    dashes.push((
+                        left,
+                        center_y - HALF_LENGTH,
+                        left + THICKNESS,
+                        center_y + HALF_LENGTH,
+                    ));
+                }
+            }
+        }
+        if vertically {
+            let bottom = 2.0 * layout.half_height() - THICKNESS;
+            for x in 0..grid.width() {
+                for (y, side) in [(0, 0.0), (last_y, bottom)] {
+                    let (_, corner_y) = layout.corner(Pos { x, y });
+                    let (center_x, _) = layout.center(Pos { x, y });
+                    let top = corner_y + side;
+                    dashes.push((
+                        center_x - HALF_LENGTH,
+                        top,
+                        center_x + HALF_LENGTH,
+                        top + THICKNESS,
+                    ));
+                }
+            }
+        }
+
+        dashes
+            .into_iter()
+            .flat_map(|(left, top, right, bottom)| {
+                [
+                    left, top, left, bottom, right, top, // First triangle
+                    right, top, left, bottom, right, bottom, // Second triangle
+                ]
+            })
+            .collect()
+    }
+
     fn render(&self) {
         let gl = &self.gl;
 
@@ -411,6 +747,17 @@ impl GlGridRenderer {
 
         // Draw
         self.draw_grid();
+        self.draw_overlay(
+            self.edge_vao.as_ref(),
+            self.edge_vertex_count,
+            Color::WRAP_EDGE,
+        );
+        self.draw_overlay(
+            self.arrow_vao.as_ref(),
+            self.arrow_vertex_count,
+            Color::ARROW,
+        );
+        self.draw_overlay(self.path_vao.as_ref(), self.path_vertex_count, Color::PATH);
     }
 
     fn draw_grid(&self) {
@@ -420,40 +767,84 @@ impl GlGridRenderer {
         }
         let state = state.as_ref().unwrap();
         let grid = &state.grid;
-        let start = grid.start();
-        let end = grid.end();
+        let layout = TileLayout::of(grid);
 
         for x in 0..grid.width() {
             for y in 0..grid.height() {
                 let pos = Pos { x, y };
 
                 let tile = grid.tile(pos);
-                let is_start = pos == start;
-                let is_end = pos == end;
-                let is_path = state.path.contains(&pos);
+                let marker = Marker::at(grid, pos);
                 let is_visited = state.visited.contains(&pos);
-
-                let color = match (tile, is_start, is_end, is_path, is_visited) {
-                    (_, true, _, _, _) => Color::TILE_START,
-                    (_, _, true, _, _) => Color::TILE_END,
-                    (Tile::Wall, _, _, _, _) => Color::TILE_WALL,
-                    (_, _, _, _, true) => Color::TILE_VISITED,
-                    (_, _, _, true, _) => Color::TILE_PATH,
-                    (Tile::None, _, _, _, _) => Color::TILE_NONE,
+                let is_visited_from_end = state.visited_from_end.contains(&pos);
+                let is_reexpanded = state.reexpanded.contains(&pos);
+
+                // the path is drawn as a line over the tiles
+                let color = match (tile, marker, is_visited) {
+                    (_, Some(Marker::Start), _) => Color::TILE_START,
+                    (_, Some(Marker::End), _) => Color::TILE_END,
+                    (_, Some(Marker::Waypoint(_)), _) => Color::TILE_WAYPOINT,
+                    (_, Some(Marker::Goal(_)), _) => Color::TILE_GOAL,
+                    (Tile::Wall, _, _) => Color::TILE_WALL,
+                    _ if is_reexpanded => Color::TILE_REEXPANDED,
+                    (_, _, true) => Color::TILE_VISITED,
+                    _ if is_visited_from_end => Color::TILE_VISITED_FROM_END,
+                    (Tile::None, _, _) => Color::TILE_NONE,
+                    (Tile::Cost(cost), _, _) => Color::from_rgb_255(tile_cost_color(cost)),
+                    (Tile::Portal(number), _, _) => Color::from_rgb_255(portal_color(number)),
+                    (Tile::OneWay(_), _, _) => Color::TILE_ONE_WAY,
                 };
-                self.draw_tile(x as f32, y as f32, color);
+                let (x, y) = layout.corner(pos);
+                self.draw_tile(layout.hex, x, y, color);
             }
         }
     }
 
-    fn draw_tile(&self, x: f32, y: f32, color: Color) {
+    /// Draws triangles which are positioned in tiles, like the path.
+    fn draw_overlay(&self, vao: Option<&WebGlVertexArrayObject>, vertex_count: i32, color: Color) {
+        if vertex_count == 0 {
+            return;
+        }
         let gl = &self.gl;
 
         // Bind shader program
         gl.use_program(self.shader_program.as_ref());
 
         // Bind VAO
-        gl.bind_vertex_array(self.vao.as_ref());
+        gl.bind_vertex_array(vao);
+
+        // Set uniforms, the vertices are already positioned in tiles
+        gl.uniform2f(
+            self.resolution_location.as_ref(),
+            gl.drawing_buffer_width() as f32,
+            gl.drawing_buffer_height() as f32,
+        );
+        gl.uniform2f(
+            self.tile_size_location.as_ref(),
+            self.tile_size,
+            self.tile_size,
+        );
+        gl.uniform2f(self.position_location.as_ref(), 0.0, 0.0);
+        gl.uniform3f(self.color_location.as_ref(), color.r, color.g, color.b);
+
+        // Draw
+        gl.draw_arrays(GL::TRIANGLES, 0, vertex_count);
+    }
+
+    /// Draws a square or, on hex grids, a hexagon whose box has its top left corner at the position.
+    fn draw_tile(&self, hex: bool, x: f32, y: f32, color: Color) {
+        let gl = &self.gl;
+        let (vao, vertex_count) = if hex {
+            (self.hex_vao.as_ref(), 18)
+        } else {
+            (self.vao.as_ref(), 6)
+        };
+
+        // Bind shader program
+        gl.use_program(self.shader_program.as_ref());
+
+        // Bind VAO
+        gl.bind_vertex_array(vao);
 
         // Set uniforms
         gl.uniform2f(
@@ -470,14 +861,22 @@ impl GlGridRenderer {
         gl.uniform3f(self.color_location.as_ref(), color.r, color.g, color.b);
 
         // Draw
-        gl.draw_arrays(GL::TRIANGLES, 0, 6);
+        gl.draw_arrays(GL::TRIANGLES, 0, vertex_count);
     }
     /// After this function is called, no other function on this should be called
     fn cleanup(&self) {
         let gl = &self.gl;
         gl.delete_program(Some(self.shader_program.as_ref().unwrap()));
         gl.delete_vertex_array(Some(self.vao.as_ref().unwrap()));
+        gl.delete_vertex_array(Some(self.hex_vao.as_ref().unwrap()));
+        gl.delete_vertex_array(Some(self.path_vao.as_ref().unwrap()));
+        gl.delete_vertex_array(Some(self.arrow_vao.as_ref().unwrap()));
+        gl.delete_vertex_array(Some(self.edge_vao.as_ref().unwrap()));
         gl.delete_buffer(self.position_buffer.as_ref());
+        gl.delete_buffer(self.hex_buffer.as_ref());
+        gl.delete_buffer(self.path_buffer.as_ref());
+        gl.delete_buffer(self.arrow_buffer.as_ref());
+        gl.delete_buffer(self.edge_buffer.as_ref());
     }
 }
 
@@ -503,6 +902,17 @@ impl Color {
     pub const TILE_WALL: Color = color_rgb_255!(0, 0, 0);
     pub const TILE_START: Color = color_rgb_255!(0, 255, 0);
     pub const TILE_END: Color = color_rgb_255!(255, 0, 0);
-    pub const TILE_PATH: Color = color_rgb_255!(255, 255, 0);
+    pub const TILE_WAYPOINT: Color = color_rgb_255!(0, 80, 200);
+    pub const TILE_GOAL: Color = color_rgb_255!(160, 0, 0);
     pub const TILE_VISITED: Color = color_rgb_255!(255, 0, 255);
+    pub const TILE_VISITED_FROM_END: Color = color_rgb_255!(255, 140, 0);
+    pub const TILE_REEXPANDED: Color = color_rgb_255!(0, 200, 120);
+    pub const TILE_ONE_WAY: Color = color_rgb_255!(160, 228, 255);
+    pub const ARROW: Color = color_rgb_255!(0, 0, 0);
+    pub const WRAP_EDGE: Color = color_rgb_255!(40, 40, 160);
+    pub const PATH: Color = color_rgb_255!(255, 255, 0);
+
+    pub fn from_rgb_255((r, g, b): (u8, u8, u8)) -> Color {
+        color_rgb_255!(r, g, b)
+    }
 }
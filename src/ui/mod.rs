@@ -1,2 +1,3 @@
 pub mod grid;
 pub mod options;
+pub mod slots;
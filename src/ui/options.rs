@@ -1,12 +1,24 @@
-use crate::pathfinders::{Grid, PathFindAlgorithms, Pos, Unit};
+use crate::playback::PlaybackSpeed;
 use crate::ui::grid::GridRenderMode;
+use crate::ui::slots::SlotManager;
+use pathfinder_core::generators::terrain::{NoiseMapping, TerrainGenerators, TerrainOptions};
+use pathfinder_core::generators::MazeGenerators;
+use pathfinder_core::heuristic::{HeuristicOptions, Heuristics};
+use pathfinder_core::iterative_deepening::DeepeningProgress;
+use pathfinder_core::json::Document;
+use pathfinder_core::text::PORTAL_LETTERS;
+use pathfinder_core::{Direction, Grid, Movement, PathFindAlgorithms, Pos, Tile, Unit};
 use std::ops::Deref;
 use wasm_bindgen::JsCast;
-use web_sys::{HtmlInputElement, HtmlSelectElement};
+use web_sys::{HtmlDocument, HtmlInputElement, HtmlSelectElement, HtmlTextAreaElement};
 use yew::{
-    classes, function_component, html, use_mut_ref, use_state, Callback, Event, Html, Properties,
+    classes, function_component, html, use_mut_ref, use_node_ref, use_state, Callback, Event, Html,
+    Properties,
 };
 
+/// The number of portal pairs which can be painted.
+const PORTAL_BRUSHES: usize = 5;
+
 #[derive(Copy, Clone, PartialEq)]
 pub struct GridOptions {
     pub rows: usize,
@@ -26,6 +38,17 @@ impl Default for GridOptions {
     }
 }
 
+impl From<&Grid> for GridOptions {
+    fn from(grid: &Grid) -> Self {
+        Self {
+            rows: grid.width() as usize,
+            columns: grid.height() as usize,
+            start_pos: grid.start(),
+            end_pos: grid.end(),
+        }
+    }
+}
+
 impl From<GridOptions> for Grid {
     fn from(value: GridOptions) -> Self {
         Self::new(
@@ -41,17 +64,91 @@ impl From<GridOptions> for Grid {
 pub struct OptionsProps {
     #[prop_or(PathFindAlgorithms::BreadthFirst)]
     pub default_path_finder: PathFindAlgorithms,
-    pub on_find_path: Callback<PathFindAlgorithms>,
+    #[prop_or(Callback::noop())]
+    pub on_path_finder_change: Callback<PathFindAlgorithms>,
+    pub on_find_path: Callback<(PathFindAlgorithms, HeuristicOptions)>,
+    #[prop_or_default]
+    pub default_heuristic: HeuristicOptions,
+    #[prop_or(Callback::noop())]
+    pub on_heuristic_change: Callback<HeuristicOptions>,
+
+    /// Whether the search is played back or paused
+    #[prop_or(true)]
+    pub default_playing: bool,
+    #[prop_or(Callback::noop())]
+    pub on_playing_change: Callback<bool>,
+    /// Called when the search should do a single step
+    #[prop_or(Callback::noop())]
+    pub on_step: Callback<()>,
+    /// Called when the search should run to its end at once
+    #[prop_or(Callback::noop())]
+    pub on_finish: Callback<()>,
+    #[prop_or_default]
+    pub default_speed: PlaybackSpeed,
+    #[prop_or(Callback::noop())]
+    pub on_speed_change: Callback<PlaybackSpeed>,
 
     #[prop_or_default]
     pub default_grid_options: GridOptions,
     #[prop_or(Callback::noop())]
     pub on_grid_options_change: Callback<GridOptions>,
 
+    /// The movement of the current grid
+    #[prop_or_default]
+    pub default_movement: Movement,
+    #[prop_or(Callback::noop())]
+    pub on_movement_change: Callback<Movement>,
+    /// Whether the edges of the grid wrap around to the opposite side
+    #[prop_or_default]
+    pub default_wraps: bool,
+    #[prop_or(Callback::noop())]
+    pub on_wraps_change: Callback<bool>,
+
     #[prop_or(GridRenderMode::WebGL2)]
     pub default_grid_renderer: GridRenderMode,
     #[prop_or(Callback::noop())]
     pub on_grid_renderer_change: Callback<GridRenderMode>,
+
+    /// Called with the generator and the seed when a maze should be generated
+    #[prop_or(Callback::noop())]
+    pub on_generate_maze: Callback<(MazeGenerators, u64)>,
+    /// Called with the generator, its options and the seed when terrain should be generated
+    #[prop_or(Callback::noop())]
+    pub on_generate_terrain: Callback<(TerrainGenerators, TerrainOptions, u64)>,
+
+    #[prop_or(Tile::Wall)]
+    pub default_brush: Tile,
+    #[prop_or(Callback::noop())]
+    pub on_brush_change: Callback<Tile>,
+
+    /// Called when a waypoint should be added, which can then be dragged to where it's needed
+    #[prop_or(Callback::noop())]
+    pub on_add_waypoint: Callback<()>,
+    /// Called when a goal should be added, which can then be dragged to where it's needed
+    #[prop_or(Callback::noop())]
+    pub on_add_goal: Callback<()>,
+    /// Called when all waypoints and goals should be removed
+    #[prop_or(Callback::noop())]
+    pub on_clear_markers: Callback<()>,
+    /// Whether searches visit the waypoints in the cheapest order instead of their own order
+    #[prop_or_default]
+    pub default_tour: bool,
+    #[prop_or(Callback::noop())]
+    pub on_tour_change: Callback<bool>,
+
+    /// The current grid, which is shown in the text format and can be saved into slots
+    pub grid: Grid,
+    /// Called with a grid that was pasted as text or json or loaded from a slot.
+    /// Slots and json documents keep the movement and wrapping they were saved with, the text format gets the current ones.
+    #[prop_or(Callback::noop())]
+    pub on_grid_import: Callback<Grid>,
+
+    /// The cost of the last found path
+    #[prop_or_default]
+    pub path_cost: Option<f32>,
+    /// The iteration of a running iterative deepening search
+    #[prop_or_default]
+    pub deepening_progress: Option<DeepeningProgress>,
 }
 
 #[function_component]
@@ -66,14 +163,27 @@ pub fn Options(props: &OptionsProps) -> Html {
         use_mut_ref(|| default_grid_renderer)
     };
 
+    let heuristic = {
+        let default_heuristic = props.default_heuristic;
+        use_mut_ref(|| default_heuristic)
+    };
+
     // Pathfinder
     let on_click_find_path = {
         let on_find_path = props.on_find_path.clone();
         let selected_path_finder = selected_path_finder.clone();
 
-        Callback::from(move |_| on_find_path.emit(*selected_path_finder.borrow().deref()))
+        let heuristic = heuristic.clone();
+
+        Callback::from(move |_| {
+            on_find_path.emit((
+                *selected_path_finder.borrow().deref(),
+                *heuristic.borrow().deref(),
+            ))
+        })
     };
     let selection_on_change = {
+        let on_path_finder_change = props.on_path_finder_change.clone();
         let selected_path_finder = selected_path_finder.clone();
 
         Callback::from(move |e: Event| {
@@ -84,14 +194,78 @@ pub fn Options(props: &OptionsProps) -> Html {
                 .expect("Unable to cast to HtmlSelectElement");
             let selected = target.value();
 
-            selected_path_finder.replace_with(|_| {
-                path_finder_from_str(&selected).expect("Unable to parse path finder")
-            });
+            let new_path_finder =
+                PathFindAlgorithms::from_id(&selected).expect("Unable to parse path finder");
+            selected_path_finder.replace(new_path_finder);
+            on_path_finder_change.emit(new_path_finder);
         })
     };
 
     let selected_path_finder = *selected_path_finder.borrow().deref();
 
+    // Heuristic
+    let on_heuristic_change = {
+        let on_heuristic_change = props.on_heuristic_change.clone();
+        let heuristic = heuristic.clone();
+
+        Callback::from(move |e: Event| {
+            let target = e
+                .target()
+                .expect("Unable to get event target")
+                .dyn_into::<HtmlSelectElement>()
+                .expect("Unable to cast to HtmlSelectElement");
+            let selected = target.value();
+
+            heuristic.borrow_mut().heuristic =
+                Heuristics::from_id(&selected).expect("Unable to parse heuristic");
+            on_heuristic_change.emit(*heuristic.borrow());
+        })
+    };
+    let on_heuristic_weight_change = {
+        let on_heuristic_change = props.on_heuristic_change.clone();
+        let heuristic = heuristic.clone();
+
+        Callback::from(move |e: Event| {
+            let target = e
+                .target()
+                .expect("Unable to get event target")
+                .dyn_into::<HtmlInputElement>()
+                .expect("Unable to cast to HtmlInputElement");
+
+            // keep the old weight while the input isn't a valid number
+            if let Ok(weight) = target.value().parse::<f32>() {
+                heuristic.borrow_mut().weight = weight.max(0.0);
+                on_heuristic_change.emit(*heuristic.borrow());
+            }
+        })
+    };
+    let heuristic = *heuristic.borrow().deref();
+
+    // Playback
+    let on_click_play = {
+        let on_playing_change = props.on_playing_change.clone();
+        let playing = props.default_playing;
+
+        Callback::from(move |_| on_playing_change.emit(!playing))
+    };
+    let on_speed_change = {
+        let on_speed_change = props.on_speed_change.clone();
+
+        Callback::from(move |e: Event| {
+            let target = e
+                .target()
+                .expect("Unable to get event target")
+                .dyn_into::<HtmlInputElement>()
+                .expect("Unable to cast to HtmlInputElement");
+            let level = target
+                .value()
+                .parse::<u32>()
+                .expect("Unable to parse speed to u32");
+
+            on_speed_change.emit(PlaybackSpeed::new(level));
+        })
+    };
+
     // Rows/Columns
     let on_rows_change = {
         let on_grid_options_change = props.on_grid_options_change.clone();
@@ -140,6 +314,38 @@ pub fn Options(props: &OptionsProps) -> Html {
         })
     };
 
+    // Movement
+    // loaded slots bring their own movement, so the selection always follows the one of the grid
+    let movement = props.default_movement;
+    let on_movement_change = {
+        let on_movement_change = props.on_movement_change.clone();
+
+        Callback::from(move |e: Event| {
+            let target = e
+                .target()
+                .expect("Unable to get event target")
+                .dyn_into::<HtmlSelectElement>()
+                .expect("Unable to cast to HtmlSelectElement");
+            let selected = target.value();
+
+            let new_movement = Movement::from_id(&selected).expect("Unable to parse movement");
+            on_movement_change.emit(new_movement);
+        })
+    };
+    let on_wraps_change = {
+        let on_wraps_change = props.on_wraps_change.clone();
+
+        Callback::from(move |e: Event| {
+            let target = e
+                .target()
+                .expect("Unable to get event target")
+                .dyn_into::<HtmlInputElement>()
+                .expect("Unable to cast to HtmlInputElement");
+
+            on_wraps_change.emit(target.checked());
+        })
+    };
+
     // Grid Renderer
     let grid_renderer = *grid_renderer.borrow().deref();
     let on_grid_renderer_change = {
@@ -154,11 +360,257 @@ pub fn Options(props: &OptionsProps) -> Html {
             let selected = target.value();
 
             let grid_renderer =
-                grid_renderer_from_str(&selected).expect("Unable to parse grid renderer");
+                GridRenderMode::from_id(&selected).expect("Unable to parse grid renderer");
             on_grid_renderer_change.emit(grid_renderer);
         })
     };
 
+    // Brush
+    let brush = {
+        let default_brush = props.default_brush;
+        use_mut_ref(|| default_brush)
+    };
+    let on_brush_change = {
+        let on_brush_change = props.on_brush_change.clone();
+        let brush = brush.clone();
+
+        Callback::from(move |e: Event| {
+            let target = e
+                .target()
+                .expect("Unable to get event target")
+                .dyn_into::<HtmlSelectElement>()
+                .expect("Unable to cast to HtmlSelectElement");
+            let selected = target.value();
+
+            let new_brush = brush_from_str(&selected).expect("Unable to parse brush");
+            brush.replace(new_brush);
+            on_brush_change.emit(new_brush);
+        })
+    };
+    let brush = *brush.borrow().deref();
+
+    // Markers
+    let on_tour_change = {
+        let on_tour_change = props.on_tour_change.clone();
+
+        Callback::from(move |e: Event| {
+            let target = e
+                .target()
+                .expect("Unable to get event target")
+                .dyn_into::<HtmlInputElement>()
+                .expect("Unable to cast to HtmlInputElement");
+
+            on_tour_change.emit(target.checked());
+        })
+    };
+
+    // Maze and terrain generation
+    let maze_generator = use_mut_ref(|| MazeGenerators::RecursiveBacktracker);
+    let generator_seed = use_state(random_seed);
+    let on_maze_generator_change = {
+        let maze_generator = maze_generator.clone();
+
+        Callback::from(move |e: Event| {
+            let target = e
+                .target()
+                .expect("Unable to get event target")
+                .dyn_into::<HtmlSelectElement>()
+                .expect("Unable to cast to HtmlSelectElement");
+            let selected = target.value();
+
+            maze_generator.replace(
+                MazeGenerators::from_id(&selected).expect("Unable to parse maze generator"),
+            );
+        })
+    };
+    let on_seed_change = {
+        let generator_seed = generator_seed.clone();
+
+        Callback::from(move |e: Event| {
+            let target = e
+                .target()
+                .expect("Unable to get event target")
+                .dyn_into::<HtmlInputElement>()
+                .expect("Unable to cast to HtmlInputElement");
+
+            // keep the old seed while the input isn't a valid number
+            if let Ok(seed) = target.value().parse::<u64>() {
+                generator_seed.set(seed);
+            }
+        })
+    };
+    let on_new_seed = {
+        let generator_seed = generator_seed.clone();
+
+        Callback::from(move |_| generator_seed.set(random_seed()))
+    };
+    let on_click_generate_maze = {
+        let on_generate_maze = props.on_generate_maze.clone();
+        let maze_generator = maze_generator.clone();
+        let generator_seed = generator_seed.clone();
+
+        Callback::from(move |_| on_generate_maze.emit((*maze_generator.borrow(), *generator_seed)))
+    };
+    let maze_generator = *maze_generator.borrow().deref();
+
+    // Terrain
+    let terrain_generator = use_mut_ref(|| TerrainGenerators::Cave);
+    let terrain_options = use_mut_ref(TerrainOptions::default);
+    let on_terrain_generator_change = {
+        let terrain_generator = terrain_generator.clone();
+
+        Callback::from(move |e: Event| {
+            let target = e
+                .target()
+                .expect("Unable to get event target")
+                .dyn_into::<HtmlSelectElement>()
+                .expect("Unable to cast to HtmlSelectElement");
+            let selected = target.value();
+
+            terrain_generator.replace(
+                TerrainGenerators::from_id(&selected).expect("Unable to parse terrain generator"),
+            );
+        })
+    };
+    let on_terrain_density_change = {
+        let terrain_options = terrain_options.clone();
+
+        Callback::from(move |e: Event| {
+            let target = e
+                .target()
+                .expect("Unable to get event target")
+                .dyn_into::<HtmlInputElement>()
+                .expect("Unable to cast to HtmlInputElement");
+            let percent = target
+                .value()
+                .parse::<f32>()
+                .expect("Unable to parse density to f32");
+
+            terrain_options.borrow_mut().density = percent / 100.0;
+        })
+    };
+    let on_terrain_scale_change = {
+        let terrain_options = terrain_options.clone();
+
+        Callback::from(move |e: Event| {
+            let target = e
+                .target()
+                .expect("Unable to get event target")
+                .dyn_into::<HtmlInputElement>()
+                .expect("Unable to cast to HtmlInputElement");
+
+            // keep the old scale while the input isn't a valid number
+            if let Ok(scale) = target.value().parse::<f32>() {
+                terrain_options.borrow_mut().scale = scale.max(1.0);
+            }
+        })
+    };
+    let on_terrain_mapping_change = {
+        let terrain_options = terrain_options.clone();
+
+        Callback::from(move |e: Event| {
+            let target = e
+                .target()
+                .expect("Unable to get event target")
+                .dyn_into::<HtmlInputElement>()
+                .expect("Unable to cast to HtmlInputElement");
+
+            terrain_options.borrow_mut().mapping = if target.checked() {
+                NoiseMapping::Costs
+            } else {
+                NoiseMapping::Walls
+            };
+        })
+    };
+    let on_click_generate_terrain = {
+        let on_generate_terrain = props.on_generate_terrain.clone();
+        let terrain_generator = terrain_generator.clone();
+        let terrain_options = terrain_options.clone();
+        let generator_seed = generator_seed.clone();
+
+        Callback::from(move |_| {
+            on_generate_terrain.emit((
+                *terrain_generator.borrow(),
+                *terrain_options.borrow(),
+                *generator_seed,
+            ))
+        })
+    };
+    let terrain_generator = *terrain_generator.borrow().deref();
+    let terrain_options = *terrain_options.borrow().deref();
+
+    // Map text and slots
+    let import_grid = {
+        let on_grid_import = props.on_grid_import.clone();
+        let grid_options = grid_options.clone();
+
+        Callback::from(move |grid: Grid| {
+            grid_options.set(GridOptions::from(&grid));
+            on_grid_import.emit(grid);
+        })
+    };
+    let map_text_ref = use_node_ref();
+    let map_error = use_state(|| None::<String>);
+    let on_map_load = {
+        let import_grid = import_grid.clone();
+        let map_text_ref = map_text_ref.clone();
+        let map_error = map_error.clone();
+        let movement = props.default_movement;
+        let wraps = props.default_wraps;
+
+        Callback::from(move |_| {
+            let text_area = map_text_ref
+                .cast::<HtmlTextAreaElement>()
+                .expect("Unable to cast to HtmlTextAreaElement");
+
+            // json documents of other tools can be pasted as well as the text format
+            let text = text_area.value();
+            let grid = if text.trim_start().starts_with('{') {
+                Document::from_json(&text)
+                    .map(|document| document.grid)
+                    .map_err(|error| error.to_string())
+            } else {
+                // only the text format has no movement and wrapping, so it keeps the current ones
+                text.parse::<Grid>()
+                    .map(|mut grid| {
+                        grid.set_movement(movement);
+                        grid.set_wraps(wraps);
+                        grid
+                    })
+                    .map_err(|error| error.to_string())
+            };
+            match grid {
+                Ok(grid) => {
+                    map_error.set(None);
+                    import_grid.emit(grid);
+                }
+                Err(error) => map_error.set(Some(error)),
+            }
+        })
+    };
+    let on_map_copy = {
+        let map_text_ref = map_text_ref.clone();
+
+        Callback::from(move |_| {
+            let text_area = map_text_ref
+                .cast::<HtmlTextAreaElement>()
+                .expect("Unable to cast to HtmlTextAreaElement");
+            copy_text(&text_area);
+        })
+    };
+    let on_map_copy_json = {
+        let map_text_ref = map_text_ref.clone();
+        let grid = props.grid.clone();
+
+        Callback::from(move |_| {
+            let text_area = map_text_ref
+                .cast::<HtmlTextAreaElement>()
+                .expect("Unable to cast to HtmlTextAreaElement");
+            text_area.set_value(&Document::new(grid.clone()).to_json());
+            copy_text(&text_area);
+        })
+    };
+
     html! {
         <div class={classes!("options")}>
             <select onchange={selection_on_change}>
@@ -166,8 +618,85 @@ pub fn Options(props: &OptionsProps) -> Html {
                 {create_path_finder_option(PathFindAlgorithms::BreadthFirst, selected_path_finder, "Breadth First")}
                 {create_path_finder_option(PathFindAlgorithms::Dijkstra, selected_path_finder, "Dijkstra")}
                 {create_path_finder_option(PathFindAlgorithms::AStar, selected_path_finder, "A*")}
+                {create_path_finder_option(PathFindAlgorithms::GreedyBestFirst, selected_path_finder, "Greedy Best First")}
+                {create_path_finder_option(PathFindAlgorithms::BidirectionalBreadthFirst, selected_path_finder, "Bidirectional Breadth First")}
+                {create_path_finder_option(PathFindAlgorithms::BidirectionalDijkstra, selected_path_finder, "Bidirectional Dijkstra")}
+                {create_path_finder_option(PathFindAlgorithms::BidirectionalAStar, selected_path_finder, "Bidirectional A*")}
+                {create_path_finder_option(PathFindAlgorithms::JumpPointSearch, selected_path_finder, "Jump Point Search")}
+                {create_path_finder_option(PathFindAlgorithms::JumpPointSearchPlus, selected_path_finder, "Jump Point Search+")}
+                {create_path_finder_option(PathFindAlgorithms::LpaStar, selected_path_finder, "Lifelong Planning A*")}
+                {create_path_finder_option(PathFindAlgorithms::IdaStar, selected_path_finder, "Iterative Deepening A*")}
+                {create_path_finder_option(PathFindAlgorithms::IterativeDeepeningDepthFirst, selected_path_finder, "Iterative Deepening Depth First")}
+                {create_path_finder_option(PathFindAlgorithms::ThetaStar, selected_path_finder, "Theta*")}
+                {create_path_finder_option(PathFindAlgorithms::LazyThetaStar, selected_path_finder, "Lazy Theta*")}
             </select>
+            <div>
+                <h3 class={classes!("options-heuristic-header")}>{"Heuristic"}</h3>
+                <div class={classes!("options-heuristic")}>
+                    <select onchange={on_heuristic_change}>
+                        {create_heuristic_option(Heuristics::Auto, heuristic.heuristic, "Automatic")}
+                        {create_heuristic_option(Heuristics::Manhattan, heuristic.heuristic, "Manhattan")}
+                        {create_heuristic_option(Heuristics::Euclidean, heuristic.heuristic, "Euclidean")}
+                        {create_heuristic_option(Heuristics::Chebyshev, heuristic.heuristic, "Chebyshev")}
+                        {create_heuristic_option(Heuristics::Octile, heuristic.heuristic, "Octile")}
+                        {create_heuristic_option(Heuristics::Hex, heuristic.heuristic, "Hex distance")}
+                        {create_heuristic_option(Heuristics::Zero, heuristic.heuristic, "Zero")}
+                    </select>
+                    <div>
+                        <label>{"Weight: "}</label>
+                        <input type="number" min="0" step="0.1" value={heuristic.weight.to_string()} onchange={on_heuristic_weight_change} />
+                    </div>
+                </div>
+            </div>
             <button onclick={on_click_find_path}>{"Start Search"}</button>
+            if let Some(path_cost) = props.path_cost {
+                <span class={classes!("options-path-cost")}>{format!("Path cost: {path_cost:.1}")}</span>
+            }
+            if let Some(progress) = props.deepening_progress {
+                <span class={classes!("options-deepening-progress")}>{format!("Iteration: {}, threshold: {:.1}", progress.iteration, progress.threshold)}</span>
+            }
+
+            <div>
+                <h3 class={classes!("options-playback-header")}>{"Playback"}</h3>
+                <div class={classes!("options-playback")}>
+                    <div>
+                        <button onclick={on_click_play}>{if props.default_playing { "Pause" } else { "Play" }}</button>
+                        <button onclick={props.on_step.reform(|_| ())}>{"Step"}</button>
+                        <button onclick={props.on_finish.reform(|_| ())}>{"Finish"}</button>
+                    </div>
+                    <div>
+                        <label>{format!("Speed: {}", props.default_speed.label())}</label>
+                        <br />
+                        <input type="range" min="0" max={PlaybackSpeed::MAX.to_string()} value={props.default_speed.level().to_string()} onchange={on_speed_change} />
+                    </div>
+                </div>
+            </div>
+
+            <div>
+                <h3 class={classes!("options-brush-header")}>{"Brush"}</h3>
+                <select class={classes!("options-brush")} onchange={on_brush_change}>
+                    {create_brush_option(Tile::Wall, brush, "Wall".to_string())}
+                    {for (2..=Tile::MAX_COST).map(|cost| create_brush_option(Tile::Cost(cost), brush, format!("Cost {cost}")))}
+                    {for PORTAL_LETTERS.chars().take(PORTAL_BRUSHES).enumerate().map(|(number, letter)| create_brush_option(Tile::Portal(number as u8), brush, format!("Portal {letter}")))}
+                    {create_brush_option(Tile::OneWay(Direction::Up), brush, "One-way Up".to_string())}
+                    {create_brush_option(Tile::OneWay(Direction::Down), brush, "One-way Down".to_string())}
+                    {create_brush_option(Tile::OneWay(Direction::Left), brush, "One-way Left".to_string())}
+                    {create_brush_option(Tile::OneWay(Direction::Right), brush, "One-way Right".to_string())}
+                </select>
+            </div>
+
+            <div>
+                <h3 class={classes!("options-markers-header")}>{"Markers"}</h3>
+                <div class={classes!("options-markers")}>
+                    <button onclick={props.on_add_waypoint.reform(|_| ())}>{"Add Waypoint"}</button>
+                    <button onclick={props.on_add_goal.reform(|_| ())}>{"Add Goal"}</button>
+                    <button onclick={props.on_clear_markers.reform(|_| ())}>{"Clear"}</button>
+                </div>
+                <div class={classes!("options-markers")}>
+                    <input type="checkbox" checked={props.default_tour} onchange={on_tour_change} />
+                    <label>{"Visit waypoints in the cheapest order"}</label>
+                </div>
+            </div>
 
             <div>
                 <h3 class={classes!("options-grid-header")}>{"Grid Options"}</h3>
@@ -182,6 +711,82 @@ pub fn Options(props: &OptionsProps) -> Html {
                         <br />
                         <input type="range" min="1" max="25" value={grid_options.columns.to_string()} onchange={on_columns_change} />
                     </div>
+                    <div>
+                        <label>{"Movement: "}</label>
+                        <br />
+                        <select onchange={on_movement_change}>
+                            {create_movement_option(Movement::FourWay, movement, "4 Directions")}
+                            {create_movement_option(Movement::EightWay, movement, "8 Directions")}
+                            {create_movement_option(Movement::EightWayNoCornerCutting, movement, "8 Directions (no corner cutting)")}
+                            {create_movement_option(Movement::Hex, movement, "Hexagonal tiles")}
+                        </select>
+                    </div>
+                    <div>
+                        <input type="checkbox" checked={props.default_wraps} onchange={on_wraps_change} />
+                        <label>{"Wrap around the edges"}</label>
+                    </div>
+                </div>
+            </div>
+
+            <div>
+                <h3 class={classes!("options-generate-header")}>{"Generate"}</h3>
+                <div class={classes!("options-generate")}>
+                    <div>
+                        <label>{"Seed: "}</label>
+                        <input type="number" min="0" value={generator_seed.to_string()} onchange={on_seed_change} />
+                        <button onclick={on_new_seed}>{"New"}</button>
+                    </div>
+                    <select onchange={on_maze_generator_change}>
+                        {create_maze_generator_option(MazeGenerators::RecursiveBacktracker, maze_generator, "Recursive Backtracker")}
+                        {create_maze_generator_option(MazeGenerators::Prim, maze_generator, "Prim")}
+                        {create_maze_generator_option(MazeGenerators::Kruskal, maze_generator, "Kruskal")}
+                        {create_maze_generator_option(MazeGenerators::Eller, maze_generator, "Eller")}
+                        {create_maze_generator_option(MazeGenerators::Wilson, maze_generator, "Wilson")}
+                        {create_maze_generator_option(MazeGenerators::RecursiveDivision, maze_generator, "Recursive Division")}
+                    </select>
+                    <button onclick={on_click_generate_maze}>{"Generate Maze"}</button>
+                    <select onchange={on_terrain_generator_change}>
+                        {create_terrain_generator_option(TerrainGenerators::RandomFill, terrain_generator, "Random Fill")}
+                        {create_terrain_generator_option(TerrainGenerators::Cave, terrain_generator, "Cave")}
+                        {create_terrain_generator_option(TerrainGenerators::ValueNoise, terrain_generator, "Value Noise")}
+                        {create_terrain_generator_option(TerrainGenerators::PerlinNoise, terrain_generator, "Perlin Noise")}
+                    </select>
+                    <div>
+                        <label>{"Density: "}</label>
+                        <br />
+                        <input type="range" min="0" max="100" value={((terrain_options.density * 100.0).round()).to_string()} onchange={on_terrain_density_change} />
+                    </div>
+                    <div>
+                        <label>{"Noise scale: "}</label>
+                        <input type="number" min="1" step="1" value={terrain_options.scale.to_string()} onchange={on_terrain_scale_change} />
+                    </div>
+                    <div>
+                        <input type="checkbox" checked={terrain_options.mapping == NoiseMapping::Costs} onchange={on_terrain_mapping_change} />
+                        <label>{"Noise as weights"}</label>
+                    </div>
+                    <button onclick={on_click_generate_terrain}>{"Generate Terrain"}</button>
+                </div>
+            </div>
+
+            <div>
+                <h3 class={classes!("options-map-header")}>{"Map"}</h3>
+                <div class={classes!("options-map")}>
+                    <textarea ref={map_text_ref} rows="8" spellcheck="false" value={props.grid.to_string()} />
+                    <div>
+                        <button onclick={on_map_load}>{"Load"}</button>
+                        <button onclick={on_map_copy}>{"Copy"}</button>
+                        <button onclick={on_map_copy_json}>{"Copy JSON"}</button>
+                    </div>
+                    if let Some(error) = map_error.deref() {
+                        <span class={classes!("options-map-error")}>{error}</span>
+                    }
+                </div>
+            </div>
+
+            <div>
+                <h3 class={classes!("options-slots-header")}>{"Saved Grids"}</h3>
+                <div class={classes!("options-slots")}>
+                    <SlotManager grid={props.grid.clone()} on_load={import_grid} />
                 </div>
             </div>
 
@@ -201,7 +806,7 @@ fn create_path_finder_option(
     selected_path_finder: PathFindAlgorithms,
     name: &str,
 ) -> Html {
-    let path_finder_str = path_finder_str(path_finder);
+    let path_finder_str = path_finder.id();
     let selected = path_finder == selected_path_finder;
 
     html! {
@@ -220,31 +825,65 @@ fn update_start_end(grid_options: &mut GridOptions) {
     grid_options.end_pos = update_pos(grid_options, grid_options.end_pos);
 }
 
-fn path_finder_str(path_finder: PathFindAlgorithms) -> &'static str {
-    match path_finder {
-        PathFindAlgorithms::DepthFirst => "depth_first",
-        PathFindAlgorithms::BreadthFirst => "breadth_first",
-        PathFindAlgorithms::Dijkstra => "dijkstra",
-        PathFindAlgorithms::AStar => "a_star",
+fn create_heuristic_option(
+    heuristic: Heuristics,
+    selected_heuristic: Heuristics,
+    name: &str,
+) -> Html {
+    let heuristic_str = heuristic.id();
+    let selected = heuristic == selected_heuristic;
+
+    html! {
+        <option value={heuristic_str} selected={selected}>{name}</option>
     }
 }
 
-fn path_finder_from_str(str: &str) -> Option<PathFindAlgorithms> {
-    match str {
-        "depth_first" => Some(PathFindAlgorithms::DepthFirst),
-        "breadth_first" => Some(PathFindAlgorithms::BreadthFirst),
-        "dijkstra" => Some(PathFindAlgorithms::Dijkstra),
-        "a_star" => Some(PathFindAlgorithms::AStar),
-        _ => None,
+fn create_movement_option(movement: Movement, selected_movement: Movement, name: &str) -> Html {
+    let movement_str = movement.id();
+    let selected = movement == selected_movement;
+
+    html! {
+        <option value={movement_str} selected={selected}>{name}</option>
     }
 }
 
+fn create_maze_generator_option(
+    generator: MazeGenerators,
+    selected_generator: MazeGenerators,
+    name: &str,
+) -> Html {
+    let generator_str = generator.id();
+    let selected = generator == selected_generator;
+
+    html! {
+        <option value={generator_str} selected={selected}>{name}</option>
+    }
+}
+
+fn create_terrain_generator_option(
+    generator: TerrainGenerators,
+    selected_generator: TerrainGenerators,
+    name: &str,
+) -> Html {
+    let generator_str = generator.id();
+    let selected = generator == selected_generator;
+
+    html! {
+        <option value={generator_str} selected={selected}>{name}</option>
+    }
+}
+
+/// Returns a seed which fits into the number input without losing precision.
+fn random_seed() -> u64 {
+    (js_sys::Math::random() * u32::MAX as f64) as u64
+}
+
 fn create_grid_renderer_option(
     grid_renderer: GridRenderMode,
     selected_grid_renderer: GridRenderMode,
     name: &str,
 ) -> Html {
-    let grid_renderer_str = grid_renderer_str(grid_renderer);
+    let grid_renderer_str = grid_renderer.id();
     let selected = grid_renderer == selected_grid_renderer;
 
     html! {
@@ -252,17 +891,49 @@ fn create_grid_renderer_option(
     }
 }
 
-fn grid_renderer_str(grid_renderer: GridRenderMode) -> &'static str {
-    match grid_renderer {
-        GridRenderMode::Dom => "dom",
-        GridRenderMode::WebGL2 => "webgl_2",
+fn create_brush_option(brush: Tile, selected_brush: Tile, name: String) -> Html {
+    let brush_str = brush_str(brush);
+    let selected = brush == selected_brush;
+
+    html! {
+        <option value={brush_str} selected={selected}>{name}</option>
+    }
+}
+
+fn brush_str(brush: Tile) -> String {
+    match brush {
+        Tile::None => "none".to_string(),
+        Tile::Wall => "wall".to_string(),
+        Tile::Cost(cost) => format!("cost_{cost}"),
+        Tile::Portal(number) => format!("portal_{number}"),
+        Tile::OneWay(direction) => format!("one_way_{}", direction.id()),
     }
 }
 
-fn grid_renderer_from_str(str: &str) -> Option<GridRenderMode> {
+fn brush_from_str(str: &str) -> Option<Tile> {
     match str {
-        "dom" => Some(GridRenderMode::Dom),
-        "webgl_2" => Some(GridRenderMode::WebGL2),
-        _ => None,
+        "none" => Some(Tile::None),
+        "wall" => Some(Tile::Wall),
+        _ => {
+            if let Some(cost) = str.strip_prefix("cost_") {
+                cost.parse().ok().map(Tile::Cost)
+            } else if let Some(number) = str.strip_prefix("portal_") {
+                number.parse().ok().map(Tile::Portal)
+            } else {
+                let direction = str.strip_prefix("one_way_")?;
+                Direction::from_id(direction).map(Tile::OneWay)
+            }
+        }
     }
 }
+
+/// Copies the text of the text area.
+/// If the browser doesn't allow copying, the text stays selected so it can be copied by hand.
+fn copy_text(text_area: &HtmlTextAreaElement) {
+    let document = gloo::utils::document()
+        .dyn_into::<HtmlDocument>()
+        .expect("Unable to cast to HtmlDocument");
+
+    text_area.select();
+    let _ = document.exec_command("copy");
+}
@@ -0,0 +1,93 @@
+use crate::storage::GridSlot;
+use pathfinder_core::Grid;
+use std::ops::Deref;
+use web_sys::HtmlInputElement;
+use yew::{classes, function_component, html, use_node_ref, use_state, Callback, Html, Properties};
+
+#[derive(Properties, PartialEq)]
+pub struct SlotManagerProps {
+    /// The grid which is saved when a slot is saved
+    pub grid: Grid,
+    #[prop_or(Callback::noop())]
+    pub on_load: Callback<Grid>,
+}
+
+/// Saves grids under a name in the local storage and loads them again.
+#[function_component]
+pub fn SlotManager(props: &SlotManagerProps) -> Html {
+    let slots = use_state(GridSlot::load_all);
+    let name_ref = use_node_ref();
+
+    let on_save = {
+        let slots = slots.clone();
+        let name_ref = name_ref.clone();
+        let grid = props.grid.clone();
+
+        Callback::from(move |_| {
+            let input = name_ref
+                .cast::<HtmlInputElement>()
+                .expect("Unable to cast to HtmlInputElement");
+            let name = input.value().trim().to_string();
+            if name.is_empty() {
+                return;
+            }
+
+            // saving under an existing name overwrites that slot
+            let slot = GridSlot::new(name, &grid);
+            let mut new_slots = slots.deref().clone();
+            match new_slots
+                .iter_mut()
+                .find(|existing| existing.name == slot.name)
+            {
+                Some(existing) => *existing = slot,
+                None => new_slots.push(slot),
+            }
+
+            GridSlot::store_all(&new_slots);
+            slots.set(new_slots);
+            input.set_value("");
+        })
+    };
+
+    let slot_rows = slots.iter().enumerate().map(|(i, slot)| {
+        let on_load = {
+            let on_load = props.on_load.clone();
+            let slot = slot.clone();
+
+            Callback::from(move |_| {
+                if let Some(grid) = slot.grid() {
+                    on_load.emit(grid);
+                }
+            })
+        };
+        let on_delete = {
+            let slots = slots.clone();
+
+            Callback::from(move |_| {
+                let mut new_slots = slots.deref().clone();
+                new_slots.remove(i);
+
+                GridSlot::store_all(&new_slots);
+                slots.set(new_slots);
+            })
+        };
+
+        html! {
+            <div class={classes!("slot")}>
+                <span class={classes!("slot-name")}>{&slot.name}</span>
+                <button onclick={on_load}>{"Load"}</button>
+                <button onclick={on_delete}>{"Delete"}</button>
+            </div>
+        }
+    });
+
+    html! {
+        <div class={classes!("slots")}>
+            <div>
+                <input ref={name_ref} type="text" placeholder="Name" />
+                <button onclick={on_save}>{"Save"}</button>
+            </div>
+            {for slot_rows}
+        </div>
+    }
+}
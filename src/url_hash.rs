@@ -0,0 +1,63 @@
+use crate::ui::grid::GridRenderMode;
+use pathfinder_core::compact::{decode_grid, encode_grid};
+use pathfinder_core::{Grid, PathFindAlgorithms};
+use wasm_bindgen::JsValue;
+
+/// The state which is shared through the hash of the URL, like `#grid=AQoKAAAJCQAAZA&algorithm=a_star&renderer=webgl_2`.
+/// Parts which are missing or invalid are None, so the app can fall back to its defaults for them.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct UrlHash {
+    pub grid: Option<Grid>,
+    pub path_finder: Option<PathFindAlgorithms>,
+    pub render_mode: Option<GridRenderMode>,
+}
+
+impl UrlHash {
+    /// Reads the hash of the current page.
+    pub fn load() -> Self {
+        let hash = gloo::utils::window().location().hash().unwrap_or_default();
+        Self::parse(&hash)
+    }
+
+    /// Replaces the hash of the current page without adding an entry to the browser history.
+    pub fn store(&self) {
+        let result = gloo::utils::history().replace_state_with_url(
+            &JsValue::NULL,
+            "",
+            Some(&self.to_hash()),
+        );
+        if let Err(error) = result {
+            log::warn!("Unable to update the url: {:?}", error);
+        }
+    }
+
+    pub fn parse(hash: &str) -> Self {
+        let mut url_hash = Self::default();
+        for part in hash.trim_start_matches('#').split('&') {
+            match part.split_once('=') {
+                Some(("grid", grid)) => match decode_grid(grid) {
+                    Ok(grid) => url_hash.grid = Some(grid),
+                    Err(error) => log::warn!("Unable to restore the grid from the url: {}", error),
+                },
+                Some(("algorithm", id)) => url_hash.path_finder = PathFindAlgorithms::from_id(id),
+                Some(("renderer", id)) => url_hash.render_mode = GridRenderMode::from_id(id),
+                _ => {}
+            }
+        }
+        url_hash
+    }
+
+    pub fn to_hash(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(grid) = &self.grid {
+            parts.push(format!("grid={}", encode_grid(grid)));
+        }
+        if let Some(path_finder) = self.path_finder {
+            parts.push(format!("algorithm={}", path_finder.id()));
+        }
+        if let Some(render_mode) = self.render_mode {
+            parts.push(format!("renderer={}", render_mode.id()));
+        }
+        format!("#{}", parts.join("&"))
+    }
+}